@@ -1,23 +1,132 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+/// Where the SDK is looked for when `EDSDK_LIB_DIR` isn't set
+fn default_lib_dirs(target_os: &str, target_arch: &str) -> Vec<PathBuf> {
+    match target_os {
+        "macos" => vec!["/Library/Frameworks".into()],
+        "windows" => vec!["C:\\EDSDKv132010W\\Windows\\EDSDK_64\\Library".into()],
+        "linux" => {
+            let arch = match target_arch {
+                "aarch64" => "ARM64",
+                "arm" => "ARM32",
+                _ => "x86_64",
+            };
+            vec![
+                format!("/opt/EDSDK/Library/{}", arch).into(),
+                "/usr/local/lib".into(),
+                "/usr/lib".into(),
+            ]
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// The file that has to exist in a library directory for the SDK to be linked from it
+fn library_file(target_os: &str) -> &'static str {
+    match target_os {
+        "macos" => "EDSDK.framework",
+        "windows" => "EDSDK.lib",
+        _ => "libEDSDK.so",
+    }
+}
+
+/// Stops the build with `message` instead of a panic and its backtrace
+fn fail(message: String) -> ! {
+    eprintln!("error: {}", message);
+    std::process::exit(1);
+}
+
+fn find_lib_dir(target_os: &str, target_arch: &str) -> PathBuf {
+    let library_file = library_file(target_os);
+
+    if let Some(lib_dir) = env::var_os("EDSDK_LIB_DIR") {
+        let lib_dir = PathBuf::from(lib_dir);
+        if !lib_dir.join(library_file).exists() {
+            fail(format!(
+                "EDSDK_LIB_DIR is set to {}, but it doesn't contain {}",
+                lib_dir.display(),
+                library_file
+            ));
+        }
+        return lib_dir;
+    }
+
+    let candidates = default_lib_dirs(target_os, target_arch);
+    candidates
+        .iter()
+        .find(|dir| dir.join(library_file).exists())
+        .cloned()
+        .unwrap_or_else(|| {
+            fail(format!(
+                "Canon EDSDK not found, looked for {} in: {}\n\
+                 Set EDSDK_LIB_DIR to the directory containing {}",
+                library_file,
+                display_paths(&candidates),
+                library_file
+            ))
+        })
+}
+
+fn display_paths(paths: &[PathBuf]) -> String {
+    if paths.is_empty() {
+        return "(no default locations for this platform)".to_string();
+    }
+
+    paths
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn check_include_dir(include_dir: &Path) {
+    if !include_dir.join("EDSDK.h").exists() {
+        fail(format!(
+            "EDSDK_INCLUDE_DIR is set to {}, but it doesn't contain EDSDK.h",
+            include_dir.display()
+        ));
+    }
+}
+
 fn main() {
+    println!("cargo:rerun-if-env-changed=EDSDK_LIB_DIR");
+    println!("cargo:rerun-if-env-changed=EDSDK_INCLUDE_DIR");
+
+    // the simulation implements the SDK in rust, there is nothing to link
+    if env::var_os("CARGO_FEATURE_SIMULATED").is_some() {
+        return;
+    }
 
+    let target_os = env::var("CARGO_CFG_TARGET_OS").unwrap();
+    let target_arch = env::var("CARGO_CFG_TARGET_ARCH").unwrap();
+
+    let lib_dir = find_lib_dir(&target_os, &target_arch);
+
+    // the headers aren't needed to build this crate, they are passed on to dependents
+    // as DEP_EDSDK_INCLUDE
+    if let Some(include_dir) = env::var_os("EDSDK_INCLUDE_DIR") {
+        let include_dir = PathBuf::from(include_dir);
+        check_include_dir(&include_dir);
+        println!("cargo:include={}", include_dir.display());
+    }
 
-    #[cfg(target_os = "macos")]
-    {
-        println!(
-            "cargo:rustc-link-search=framework={}",
-            "/Library/Frameworks"
-        );
-        println!("cargo:rustc-link-lib=framework=EDSDK");
-        println!("cargo:rustc-link-arg=-Wl,-rpath,/Library/Frameworks")
-    }
-
-    #[cfg(target_os = "windows")]
-    {
-        println!(
-            "cargo:rustc-link-search=native={}",
-            "C:\\EDSDKv132010W\\Windows\\EDSDK_64\\Library"
-        );
-        println!("cargo:rustc-link-lib=EDSDK");
-        // println!("cargo:rustc-link-lib=framework=EDSDK");
+    match target_os.as_str() {
+        "macos" => {
+            println!("cargo:rustc-link-search=framework={}", lib_dir.display());
+            println!("cargo:rustc-link-lib=framework=EDSDK");
+            println!("cargo:rustc-link-arg=-Wl,-rpath,{}", lib_dir.display());
+        }
+        "windows" => {
+            println!("cargo:rustc-link-search=native={}", lib_dir.display());
+            println!("cargo:rustc-link-lib=EDSDK");
+        }
+        _ => {
+            println!("cargo:rustc-link-search=native={}", lib_dir.display());
+            println!("cargo:rustc-link-lib=dylib=EDSDK");
+            println!("cargo:rustc-link-arg=-Wl,-rpath,{}", lib_dir.display());
+        }
     }
 }
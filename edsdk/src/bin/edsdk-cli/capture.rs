@@ -0,0 +1,211 @@
+use std::{
+    fs,
+    path::Path,
+    sync::mpsc::{self, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use edsdk::{
+    Camera, CameraCommand, DirectoryItem, EdsAccess, EdsCapacity, EdsEvfOutputDevice,
+    EdsFileCreateDisposition, EdsSaveTo, EvfImage, ObjectEvent, PropertyId, Sdk, ShutterButton,
+    StateEvent, Stream,
+};
+use serde::Serialize;
+
+use crate::{sdk, CliResult, Context, Report};
+
+/// A file downloaded from the camera
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadedFile {
+    path: String,
+    /// The name of the file on the camera
+    file_name: String,
+    size: u64,
+}
+
+impl Report for DownloadedFile {
+    fn print(&self) {
+        println!(
+            "Saved {} ({} bytes) to {}",
+            self.file_name, self.size, self.path
+        );
+    }
+}
+
+/// Downloads `item` to `path`, overwriting any existing file
+pub fn download(sdk: &Sdk, item: &DirectoryItem, path: &Path) -> CliResult<DownloadedFile> {
+    let info = item.info().context("Failed to get the file info")?;
+
+    let stream = Stream::create_file(
+        sdk,
+        path,
+        EdsFileCreateDisposition::CreateAlways,
+        EdsAccess::ReadWrite,
+    )
+    .context("Failed to create the file")?;
+
+    item.download(info.size, &stream)
+        .context("Failed to download")?;
+    item.download_complete()
+        .context("Failed to complete the download")?;
+
+    Ok(DownloadedFile {
+        path: path.display().to_string(),
+        file_name: info.sz_file_name().to_string(),
+        size: info.size,
+    })
+}
+
+pub fn shoot(camera: &Camera, out: &Path, timeout: Duration) -> CliResult<DownloadedFile> {
+    let sdk = sdk()?;
+
+    camera
+        .set_property(PropertyId::SaveTo, &EdsSaveTo::Host)
+        .context("Failed to save pictures to the host")?;
+    camera
+        .set_capacity(EdsCapacity {
+            number_of_free_clusters: 0x7FFFFFFF,
+            bytes_per_sector: 0x1000,
+            reset: 1,
+        })
+        .context("Failed to set the capacity")?;
+
+    let (sender, receiver) = mpsc::channel();
+
+    camera
+        .on_object_event({
+            let sender = sender.clone();
+            let out = out.to_path_buf();
+            move |event, item| {
+                if let (ObjectEvent::DirItemRequestTransfer, Some(item)) = (event, item) {
+                    let _ = sender.send(download(sdk, item, &out));
+                }
+            }
+        })
+        .context("Failed to register the object event handler")?;
+    camera
+        .on_state_event(move |event| {
+            if let StateEvent::CaptureError(err) = event {
+                let _ = sender.send(Err(format!("Failed to take picture: {}", err)));
+            }
+        })
+        .context("Failed to register the state event handler")?;
+
+    let res = camera.send_command(CameraCommand::PressShutterButton(ShutterButton::Completely));
+    let _ = camera.send_command(CameraCommand::PressShutterButton(ShutterButton::Off));
+    res.context("Failed to release the shutter")?;
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        sdk.get_event().context("Failed to get events")?;
+
+        match receiver.recv_timeout(Duration::from_millis(100)) {
+            Ok(result) => return result,
+            Err(RecvTimeoutError::Timeout) if Instant::now() < deadline => {}
+            Err(_) => {
+                return Err(format!(
+                    "No picture arrived within {} seconds",
+                    timeout.as_secs()
+                ))
+            }
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveViewFrame {
+    path: String,
+    size: usize,
+    zoom: Option<u32>,
+    mean_luminance: Option<f64>,
+    clipped_highlights: Option<f64>,
+}
+
+impl Report for Vec<LiveViewFrame> {
+    fn print(&self) {
+        for frame in self {
+            print!("{} ({} bytes)", frame.path, frame.size);
+            if let Some(mean_luminance) = frame.mean_luminance {
+                print!(", mean luminance {:.1}", mean_luminance);
+            }
+            if let Some(clipped_highlights) = frame.clipped_highlights {
+                print!(", {:.1}% clipped", clipped_highlights * 100.0);
+            }
+            println!();
+        }
+    }
+}
+
+/// Saves `frames` live view frames to `out_dir`, the live view output is restored afterwards
+pub fn liveview(
+    camera: &Camera,
+    frames: u32,
+    out_dir: &Path,
+    interval: Duration,
+) -> CliResult<Vec<LiveViewFrame>> {
+    fs::create_dir_all(out_dir)
+        .map_err(|err| format!("Failed to create {}: {}", out_dir.display(), err))?;
+
+    let output_device = camera
+        .get_property::<EdsEvfOutputDevice>(PropertyId::EvfOutputDevice)
+        .context("Failed to get the live view output")?;
+    camera
+        .set_property(
+            PropertyId::EvfOutputDevice,
+            &(output_device | EdsEvfOutputDevice::PC),
+        )
+        .context("Failed to enable live view")?;
+
+    let result = save_frames(camera, frames, out_dir, interval);
+
+    let _ = camera.set_property(PropertyId::EvfOutputDevice, &output_device);
+    result
+}
+
+fn save_frames(
+    camera: &Camera,
+    frames: u32,
+    out_dir: &Path,
+    interval: Duration,
+) -> CliResult<Vec<LiveViewFrame>> {
+    let sdk = sdk()?;
+    let mut saved = Vec::new();
+    let mut retries = 0;
+
+    while saved.len() < frames as usize {
+        sdk.get_event().context("Failed to get events")?;
+        thread::sleep(interval);
+
+        let stream = Stream::create_memory(sdk, 0).context("Failed to create a stream")?;
+        let evf_image = EvfImage::new(&stream).context("Failed to create a live view image")?;
+
+        match camera.download_evf_image(&evf_image) {
+            Ok(()) => retries = 0,
+            // the first frames take a moment after live view was enabled
+            Err(err) if err.is_retryable() && retries < 50 => {
+                retries += 1;
+                continue;
+            }
+            Err(err) => return Err(format!("Failed to download a live view frame: {}", err)),
+        }
+
+        let jpeg = stream.to_vec().context("Failed to read the frame")?;
+        let path = out_dir.join(format!("frame-{:04}.jpg", saved.len() + 1));
+        fs::write(&path, &jpeg)
+            .map_err(|err| format!("Failed to write {}: {}", path.display(), err))?;
+
+        let histogram = evf_image.histogram().ok();
+        saved.push(LiveViewFrame {
+            path: path.display().to_string(),
+            size: jpeg.len(),
+            zoom: evf_image.zoom().ok(),
+            mean_luminance: histogram.as_ref().and_then(|h| h.mean_luminance()),
+            clipped_highlights: histogram.as_ref().and_then(|h| h.clipped_highlights()),
+        });
+    }
+
+    Ok(saved)
+}
@@ -0,0 +1,121 @@
+use std::path::Path;
+
+use edsdk::{Camera, DirectoryEntry};
+use serde::Serialize;
+
+use crate::{
+    capture::{download, DownloadedFile},
+    sdk, CliResult, Context, Report,
+};
+
+/// A file on one of the cards, `path` is relative to the root of `volume`
+#[derive(Serialize)]
+pub struct CardFile {
+    volume: String,
+    path: String,
+    size: u64,
+}
+
+impl Report for CardFile {
+    fn print(&self) {
+        println!("{:>12}  {}  {}", self.size, self.volume, self.path);
+    }
+}
+
+impl Report for Vec<CardFile> {
+    fn print(&self) {
+        if self.is_empty() {
+            println!("No files on the cards");
+        }
+
+        for file in self {
+            file.print();
+        }
+    }
+}
+
+pub fn ls(camera: &Camera) -> CliResult<Vec<CardFile>> {
+    let mut files = Vec::new();
+
+    for volume in camera.volumes().context("Failed to get the cards")? {
+        let label = volume
+            .info()
+            .context("Failed to get the card info")?
+            .sz_volume_label()
+            .to_string();
+
+        for entry in volume.files().context("Failed to list the files")? {
+            files.push(CardFile {
+                volume: label.clone(),
+                size: entry
+                    .item
+                    .info()
+                    .context("Failed to get the file info")?
+                    .size,
+                path: entry.path,
+            });
+        }
+    }
+
+    Ok(files)
+}
+
+pub fn get(
+    camera: &Camera,
+    path: &str,
+    volume: Option<&str>,
+    out: Option<&Path>,
+) -> CliResult<DownloadedFile> {
+    let (_, entry) = find(camera, path, volume)?;
+
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    download(sdk()?, &entry.item, out.unwrap_or(Path::new(file_name)))
+}
+
+/// Reports the file that was deleted
+pub fn rm(camera: &Camera, path: &str, volume: Option<&str>) -> CliResult<CardFile> {
+    let (label, entry) = find(camera, path, volume)?;
+    let size = entry
+        .item
+        .info()
+        .context("Failed to get the file info")?
+        .size;
+
+    entry.item.delete().context("Failed to delete the file")?;
+
+    Ok(CardFile {
+        volume: label,
+        path: entry.path,
+        size,
+    })
+}
+
+/// Looks for `path` on the card labelled `volume`, or on all cards
+fn find<'sdk>(
+    camera: &Camera<'sdk>,
+    path: &str,
+    volume: Option<&str>,
+) -> CliResult<(String, DirectoryEntry<'sdk>)> {
+    for card in camera.volumes().context("Failed to get the cards")? {
+        let label = card
+            .info()
+            .context("Failed to get the card info")?
+            .sz_volume_label()
+            .to_string();
+
+        if volume.is_some_and(|volume| volume != label) {
+            continue;
+        }
+
+        if let Some(entry) = card
+            .files()
+            .context("Failed to list the files")?
+            .into_iter()
+            .find(|entry| entry.path == path)
+        {
+            return Ok((label, entry));
+        }
+    }
+
+    Err(format!("{} is not on the cards", path))
+}
@@ -0,0 +1,152 @@
+use edsdk::{Camera, EdsImageQuality, PropertyId};
+use serde::Serialize;
+
+use crate::{sdk, CliResult, Context, Report};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CameraSummary {
+    index: u32,
+    port: String,
+    description: String,
+    sub_type: u32,
+    body_id: Option<String>,
+    product_name: Option<String>,
+    firmware_version: Option<String>,
+}
+
+impl Report for Vec<CameraSummary> {
+    fn print(&self) {
+        if self.is_empty() {
+            println!("No cameras found");
+        }
+
+        for camera in self {
+            println!(
+                "{}: {} ({}), body ID {}, firmware {}",
+                camera.index,
+                camera.product_name.as_ref().unwrap_or(&camera.description),
+                camera.port,
+                camera.body_id.as_deref().unwrap_or("unknown"),
+                camera.firmware_version.as_deref().unwrap_or("unknown")
+            );
+        }
+    }
+}
+
+pub fn list() -> CliResult<Vec<CameraSummary>> {
+    let descriptions = sdk()?
+        .camera_list()
+        .and_then(|camera_list| camera_list.describe())
+        .context("Failed to describe the cameras")?;
+
+    Ok(descriptions
+        .into_iter()
+        .zip(0..)
+        .map(|(description, index)| CameraSummary {
+            index,
+            port: description.port_name,
+            description: description.description,
+            sub_type: description.sub_type,
+            body_id: description.body_id,
+            product_name: description.product_name,
+            firmware_version: description.firmware_version,
+        })
+        .collect())
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VolumeSummary {
+    label: String,
+    max_capacity: u64,
+    free_space: u64,
+}
+
+/// What the camera reports about itself, properties it doesn't support are left out
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CameraInfo {
+    description: String,
+    port: String,
+    product_name: Option<String>,
+    body_id: Option<String>,
+    firmware_version: Option<String>,
+    lens_name: Option<String>,
+    battery_level: Option<u32>,
+    available_shots: Option<u32>,
+    image_quality: Option<String>,
+    volumes: Vec<VolumeSummary>,
+}
+
+impl Report for CameraInfo {
+    fn print(&self) {
+        println!("{} ({})", self.description, self.port);
+
+        let fields = [
+            ("Product name", self.product_name.clone()),
+            ("Body ID", self.body_id.clone()),
+            ("Firmware", self.firmware_version.clone()),
+            ("Lens", self.lens_name.clone()),
+            (
+                "Battery",
+                self.battery_level.map(|level| format!("{}%", level)),
+            ),
+            (
+                "Available shots",
+                self.available_shots.map(|shots| shots.to_string()),
+            ),
+            ("Image quality", self.image_quality.clone()),
+        ];
+        for (name, value) in fields {
+            if let Some(value) = value {
+                println!("  {}: {}", name, value);
+            }
+        }
+
+        for volume in &self.volumes {
+            println!(
+                "  Card {}: {} MB free of {} MB",
+                volume.label,
+                volume.free_space / 1_000_000,
+                volume.max_capacity / 1_000_000
+            );
+        }
+    }
+}
+
+pub fn info(camera: &Camera) -> CliResult<CameraInfo> {
+    let device_info = camera
+        .device_info()
+        .context("Failed to get the device info")?;
+
+    let volumes = camera
+        .volumes()
+        .context("Failed to get the cards")?
+        .iter()
+        .map(|volume| {
+            let info = volume.info().context("Failed to get the card info")?;
+            Ok(VolumeSummary {
+                label: info.sz_volume_label().to_string(),
+                max_capacity: info.max_capacity,
+                free_space: info.free_space_in_bytes,
+            })
+        })
+        .collect::<CliResult<_>>()?;
+
+    Ok(CameraInfo {
+        description: device_info.sz_device_description().to_string(),
+        port: device_info.sz_port_name().to_string(),
+        product_name: camera.get_property(PropertyId::ProductName).ok(),
+        body_id: camera.get_property(PropertyId::BodyIdEx).ok(),
+        firmware_version: camera.get_property(PropertyId::FirmwareVersion).ok(),
+        lens_name: camera.get_property(PropertyId::LensName).ok(),
+        battery_level: camera.get_property(PropertyId::BatteryLevel).ok(),
+        available_shots: camera.get_property(PropertyId::AvailableShots).ok(),
+        image_quality: camera
+            .get_property::<EdsImageQuality>(PropertyId::ImageQuality)
+            .ok()
+            .map(|quality| quality.description().to_string()),
+        volumes,
+    })
+}
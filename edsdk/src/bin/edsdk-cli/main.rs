@@ -0,0 +1,242 @@
+//! Checks a camera without the photobooth app, e.g. when a booth misbehaves on site.
+//!
+//! Every command prints a short human readable report, or JSON with `--json`.
+
+use std::{path::PathBuf, process::ExitCode, sync::OnceLock, time::Duration};
+
+use clap::{Parser, Subcommand};
+use edsdk::{Camera, EdsError, Sdk};
+use serde::Serialize;
+
+mod capture;
+mod card;
+mod info;
+mod props;
+
+#[derive(Parser)]
+#[command(
+    name = "edsdk-cli",
+    about = "Inspect and control a Canon camera through the EDSDK"
+)]
+struct Cli {
+    /// Print the result as JSON
+    #[arg(long, global = true)]
+    json: bool,
+
+    /// The index of the camera to use, see `list`
+    #[arg(long, short, global = true, default_value_t = 0)]
+    camera: u32,
+
+    /// Use the camera with this body ID (serial number) instead, see `list`
+    #[arg(long, global = true)]
+    body_id: Option<String>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List the connected cameras
+    List,
+
+    /// Show the model, firmware, battery and cards of a camera
+    Info,
+
+    /// Read or change properties
+    #[command(subcommand)]
+    Props(PropsCommand),
+
+    /// Take a picture and download it
+    Shoot {
+        /// Where to save the picture
+        #[arg(long)]
+        out: PathBuf,
+
+        /// How many seconds to wait for the picture
+        #[arg(long, default_value_t = 30)]
+        timeout: u64,
+    },
+
+    /// Save live view frames as jpegs
+    Liveview {
+        /// The number of frames to save
+        #[arg(long, default_value_t = 1)]
+        frames: u32,
+
+        /// The directory the frames are saved to, it's created if necessary
+        #[arg(long)]
+        out_dir: PathBuf,
+
+        /// Milliseconds to wait between frames
+        #[arg(long, default_value_t = 100)]
+        interval: u64,
+    },
+
+    /// Browse the camera's cards
+    #[command(subcommand)]
+    Card(CardCommand),
+}
+
+#[derive(Subcommand)]
+enum PropsCommand {
+    /// Print every property the camera supports, along with the values it can be set to
+    Dump,
+
+    /// Set a property, by name (`IsoSpeed`) or code (`0x402`)
+    Set {
+        property: String,
+
+        /// A code (`0x48`), a label as printed by `props dump` (`400`, `f/5.6`) or text for string
+        /// properties
+        value: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum CardCommand {
+    /// List the files on all cards
+    Ls,
+
+    /// Download a file, `path` as printed by `card ls`
+    Get {
+        path: String,
+
+        /// Only look on the card with this label
+        #[arg(long)]
+        volume: Option<String>,
+
+        /// Where to save the file, defaults to its name in the current directory
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+
+    /// Delete a file, `path` as printed by `card ls`
+    Rm {
+        path: String,
+
+        /// Only look on the card with this label
+        #[arg(long)]
+        volume: Option<String>,
+    },
+}
+
+type CliResult<T> = Result<T, String>;
+
+/// Adds what was being done to an SDK error.
+trait Context<T> {
+    fn context(self, what: &str) -> CliResult<T>;
+}
+
+impl<T> Context<T> for Result<T, EdsError> {
+    fn context(self, what: &str) -> CliResult<T> {
+        self.map_err(|err| format!("{}: {}", what, err))
+    }
+}
+
+/// The result of a command, printed as text unless `--json` is passed.
+trait Report: Serialize {
+    fn print(&self);
+}
+
+/// Event handlers must be `'static`, so the SDK stays initialized until the process exits, like
+/// in the app
+static SDK: OnceLock<Result<Sdk, EdsError>> = OnceLock::new();
+
+fn sdk() -> CliResult<&'static Sdk> {
+    SDK.get_or_init(Sdk::init)
+        .as_ref()
+        .map_err(|err| format!("Failed to initialize the SDK: {}", err))
+}
+
+fn open_camera(index: u32, body_id: Option<&str>) -> CliResult<Camera<'static>> {
+    let camera_list = sdk()?
+        .camera_list()
+        .context("Failed to get the camera list")?;
+
+    if let Some(body_id) = body_id {
+        let mut camera = camera_list
+            .find_by_body_id(body_id)
+            .map_err(|err| format!("Failed to find camera {}: {}", body_id, err))?;
+        camera.open_session().context("Failed to open a session")?;
+        return Ok(camera);
+    }
+
+    let count = camera_list.len().context("Failed to count cameras")?;
+    if index >= count {
+        return Err(format!(
+            "There is no camera {}, found {} camera(s)",
+            index, count
+        ));
+    }
+
+    let mut camera = camera_list.get(index).context("Failed to get the camera")?;
+    camera.open_session().context("Failed to open a session")?;
+    Ok(camera)
+}
+
+fn run(cli: Cli) -> CliResult<()> {
+    if let Command::List = cli.command {
+        return output(cli.json, &info::list()?);
+    }
+
+    let camera = open_camera(cli.camera, cli.body_id.as_deref())?;
+
+    match cli.command {
+        Command::List => unreachable!(),
+        Command::Info => output(cli.json, &info::info(&camera)?),
+        Command::Props(PropsCommand::Dump) => output(cli.json, &props::dump(&camera)),
+        Command::Props(PropsCommand::Set { property, value }) => {
+            output(cli.json, &props::set(&camera, &property, &value)?)
+        }
+        Command::Shoot { out, timeout } => output(
+            cli.json,
+            &capture::shoot(&camera, &out, Duration::from_secs(timeout))?,
+        ),
+        Command::Liveview {
+            frames,
+            out_dir,
+            interval,
+        } => output(
+            cli.json,
+            &capture::liveview(&camera, frames, &out_dir, Duration::from_millis(interval))?,
+        ),
+        Command::Card(CardCommand::Ls) => output(cli.json, &card::ls(&camera)?),
+        Command::Card(CardCommand::Get { path, volume, out }) => output(
+            cli.json,
+            &card::get(&camera, &path, volume.as_deref(), out.as_deref())?,
+        ),
+        Command::Card(CardCommand::Rm { path, volume }) => {
+            output(cli.json, &card::rm(&camera, &path, volume.as_deref())?)
+        }
+    }
+}
+
+fn output(json: bool, report: &impl Report) -> CliResult<()> {
+    if json {
+        let json = serde_json::to_string_pretty(report)
+            .map_err(|err| format!("Failed to serialize the result: {}", err))?;
+        println!("{}", json);
+    } else {
+        report.print();
+    }
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let json = cli.json;
+
+    match run(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            if json {
+                println!("{}", serde_json::json!({ "error": err }));
+            } else {
+                eprintln!("error: {}", err);
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use edsdk::{
+    Camera, EdsDataType, EdsError, EdsImageQuality, EdsPoint, EdsRational, EdsRect, EdsTime,
+    PropertyId, PropertyValue,
+};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::{CliResult, Context, Report};
+
+#[derive(Serialize)]
+pub struct AvailableValue {
+    code: u32,
+    label: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PropertyEntry {
+    name: String,
+    code: u32,
+    data_type: String,
+    value: Value,
+    /// What a code stands for, if the bindings know it
+    label: Option<String>,
+    /// The values the property can currently be set to, empty if the camera doesn't say
+    available: Vec<AvailableValue>,
+}
+
+impl Report for PropertyEntry {
+    fn print(&self) {
+        match &self.label {
+            Some(label) => println!("{:<24} {} ({})", self.name, self.value, label),
+            None => println!("{:<24} {}", self.name, self.value),
+        }
+
+        if !self.available.is_empty() {
+            let labels: Vec<_> = self
+                .available
+                .iter()
+                .map(|value| value.label.as_str())
+                .collect();
+            println!("{:<24} available: {}", "", labels.join(", "));
+        }
+    }
+}
+
+impl Report for Vec<PropertyEntry> {
+    fn print(&self) {
+        for entry in self {
+            entry.print();
+        }
+    }
+}
+
+/// Reads every known property, the ones the camera doesn't support are left out
+pub fn dump(camera: &Camera) -> Vec<PropertyEntry> {
+    PropertyId::ALL
+        .iter()
+        .filter_map(|property| read_property(camera, *property).ok())
+        .collect()
+}
+
+/// Sets `property` and reports the value the camera ended up with
+pub fn set(camera: &Camera, property: &str, value: &str) -> CliResult<PropertyEntry> {
+    let property = property
+        .parse()
+        .ok()
+        .or_else(|| parse_number(property).map(PropertyId::from))
+        .ok_or_else(|| format!("Unknown property {}", property))?;
+
+    let (data_type, _) = camera
+        .property_size(property, 0)
+        .context("The camera doesn't support this property")?;
+
+    if data_type == EdsDataType::String {
+        camera.set_property(property, &value.to_string())
+    } else {
+        camera.set_property(property, &parse_code(camera, property, value)?)
+    }
+    .context("Failed to set the property")?;
+
+    read_property(camera, property).context("Failed to read the property back")
+}
+
+fn read_property(camera: &Camera, property: PropertyId) -> Result<PropertyEntry, EdsError> {
+    let (data_type, _) = camera.property_size(property, 0)?;
+    let value = read_value(camera, property, data_type)?;
+
+    Ok(PropertyEntry {
+        name: format!("{:?}", property),
+        code: property.code(),
+        data_type: format!("{:?}", data_type),
+        label: value.as_u64().and_then(|code| label(property, code as u32)),
+        value,
+        available: camera
+            .available_values(property)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|value| AvailableValue {
+                code: value.code(),
+                label: value.label(),
+            })
+            .collect(),
+    })
+}
+
+fn read_value(
+    camera: &Camera,
+    property: PropertyId,
+    data_type: EdsDataType,
+) -> Result<Value, EdsError> {
+    Ok(match data_type {
+        EdsDataType::String => camera.get_property::<String>(property)?.into(),
+        EdsDataType::Bool => camera.get_property::<bool>(property)?.into(),
+        EdsDataType::Int8 | EdsDataType::UInt8 => camera.get_property::<u8>(property)?.into(),
+        EdsDataType::Int16 => camera.get_property::<i16>(property)?.into(),
+        EdsDataType::UInt16 => camera.get_property::<u16>(property)?.into(),
+        EdsDataType::Int32 => camera.get_property::<i32>(property)?.into(),
+        EdsDataType::UInt32 => camera.get_property::<u32>(property)?.into(),
+        EdsDataType::Int64 => camera.get_property::<i64>(property)?.into(),
+        EdsDataType::UInt64 => camera.get_property::<u64>(property)?.into(),
+        EdsDataType::Int32Array | EdsDataType::UInt32Array => {
+            camera.get_property::<Vec<u32>>(property)?.into()
+        }
+        EdsDataType::Rational => {
+            let rational = camera.get_property::<EdsRational>(property)?;
+            json!({ "numerator": rational.numerator, "denominator": rational.denominator })
+        }
+        EdsDataType::Point => {
+            let point = camera.get_property::<EdsPoint>(property)?;
+            json!({ "x": point.x, "y": point.y })
+        }
+        EdsDataType::Rect => {
+            let rect = camera.get_property::<EdsRect>(property)?;
+            json!({
+                "x": rect.point.x,
+                "y": rect.point.y,
+                "width": rect.size.width,
+                "height": rect.size.height,
+            })
+        }
+        EdsDataType::Time => {
+            let time = camera.get_property::<EdsTime>(property)?;
+            format!(
+                "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+                time.year, time.month, time.day, time.hour, time.minute, time.second
+            )
+            .into()
+        }
+        // anything else is printed as hex
+        _ => camera
+            .get_property::<Vec<u8>>(property)?
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>()
+            .into(),
+    })
+}
+
+fn label(property: PropertyId, code: u32) -> Option<String> {
+    match (property, PropertyValue::decode(property, code)) {
+        (PropertyId::ImageQuality, _) => match EdsImageQuality::from(code) {
+            EdsImageQuality::Unknown(_) => None,
+            quality => Some(quality.description().to_string()),
+        },
+        (_, PropertyValue::Other(_)) => None,
+        (_, value) => Some(value.label()),
+    }
+}
+
+/// Accepts a label of one of the available values, a code or an image quality name. Labels win,
+/// ISO `400` is a label and not a code
+fn parse_code(camera: &Camera, property: PropertyId, value: &str) -> CliResult<u32> {
+    let available = camera
+        .available_values(property)
+        .unwrap_or_default()
+        .into_iter()
+        .find(|available| available.label().eq_ignore_ascii_case(value));
+    if let Some(available) = available {
+        return Ok(available.code());
+    }
+
+    // known labels work even if the camera doesn't list the value right now
+    match PropertyValue::from_label(property, value) {
+        Ok(PropertyValue::Other(_)) | Err(_) => {}
+        Ok(value) => return Ok(value.code()),
+    }
+
+    if let Some(code) = parse_number(value) {
+        return Ok(code);
+    }
+
+    match property {
+        PropertyId::ImageQuality => value
+            .parse::<EdsImageQuality>()
+            .map(u32::from)
+            .map_err(|_| format!("{} is not an image quality", value)),
+        _ => Err(format!("{} is not an available value", value)),
+    }
+}
+
+fn parse_number(value: &str) -> Option<u32> {
+    match value.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
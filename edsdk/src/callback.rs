@@ -0,0 +1,248 @@
+use std::{
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::{Mutex, MutexGuard},
+};
+
+use crate::{
+    handle::OwnedRef, set_camera_state_event_handler, set_object_event_handler,
+    set_property_event_handler, DirectoryItem, EdsBaseRef, EdsBool, EdsCameraRef, EdsError,
+    EdsErrorCode, EdsObjectEvent, EdsPropertyEvent, EdsPropertyId, EdsStateEvent, EdsVoid,
+    ObjectEvent, PropertyEvent, StateEvent,
+};
+
+/// Identifies a handler registered with [`crate::Camera::on_object_event`],
+/// [`crate::Camera::on_property_event`] or [`crate::Camera::on_state_event`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct HandlerId(u64);
+
+pub type ObjectEventHandler = Box<dyn FnMut(ObjectEvent, Option<&DirectoryItem>) + Send>;
+pub type PropertyEventHandler = Box<dyn FnMut(PropertyEvent) + Send>;
+pub type StateEventHandler = Box<dyn FnMut(StateEvent) + Send>;
+
+/// Called with the percentage of a transfer done so far, returning `false` cancels the transfer.
+pub type ProgressHandler = Box<dyn FnMut(u32) -> bool + Send>;
+
+/// The context passed to [`progress_trampoline`], boxed so its address stays the same.
+pub(crate) type ProgressContext = Box<Mutex<ProgressHandler>>;
+
+/// The handlers for one kind of event. The list is boxed, its address is the context passed to
+/// the SDK.
+type Subscribers<F> = Box<Mutex<Vec<(HandlerId, F)>>>;
+
+/// The handlers registered for a camera. A trampoline is registered with the SDK for as long as
+/// there is at least one handler for its kind of event.
+#[derive(Default)]
+pub(crate) struct EventHandlers {
+    next_id: u64,
+    object: Option<Subscribers<ObjectEventHandler>>,
+    property: Option<Subscribers<PropertyEventHandler>>,
+    state: Option<Subscribers<StateEventHandler>>,
+}
+
+impl EventHandlers {
+    fn next_id(&mut self) -> HandlerId {
+        self.next_id += 1;
+        HandlerId(self.next_id)
+    }
+
+    pub(crate) fn add_object(
+        &mut self,
+        camera: EdsCameraRef,
+        handler: ObjectEventHandler,
+    ) -> Result<HandlerId, EdsError> {
+        let id = self.next_id();
+        add(&mut self.object, id, handler, |context| unsafe {
+            set_object_event_handler(camera, Some(object_trampoline), context)
+        })?;
+        Ok(id)
+    }
+
+    pub(crate) fn add_property(
+        &mut self,
+        camera: EdsCameraRef,
+        handler: PropertyEventHandler,
+    ) -> Result<HandlerId, EdsError> {
+        let id = self.next_id();
+        add(&mut self.property, id, handler, |context| unsafe {
+            set_property_event_handler(camera, Some(property_trampoline), context)
+        })?;
+        Ok(id)
+    }
+
+    pub(crate) fn add_state(
+        &mut self,
+        camera: EdsCameraRef,
+        handler: StateEventHandler,
+    ) -> Result<HandlerId, EdsError> {
+        let id = self.next_id();
+        add(&mut self.state, id, handler, |context| unsafe {
+            set_camera_state_event_handler(camera, Some(state_trampoline), context)
+        })?;
+        Ok(id)
+    }
+
+    /// Removes the handler with `id`. Once the last handler of a kind is removed, the trampoline
+    /// is unregistered and the list is freed.
+    pub(crate) fn remove(&mut self, camera: EdsCameraRef, id: HandlerId) -> Result<(), EdsError> {
+        if remove(&mut self.object, id, || unsafe {
+            set_object_event_handler(camera, None, std::ptr::null_mut())
+        })? || remove(&mut self.property, id, || unsafe {
+            set_property_event_handler(camera, None, std::ptr::null_mut())
+        })? || remove(&mut self.state, id, || unsafe {
+            set_camera_state_event_handler(camera, None, std::ptr::null_mut())
+        })? {
+            Ok(())
+        } else {
+            Err(EdsError::InvalidParameter)
+        }
+    }
+
+    /// Unregisters all trampolines and frees the handlers.
+    pub(crate) fn clear(&mut self, camera: EdsCameraRef) {
+        if self.object.take().is_some() {
+            let _ = unsafe { set_object_event_handler(camera, None, std::ptr::null_mut()) };
+        }
+        if self.property.take().is_some() {
+            let _ = unsafe { set_property_event_handler(camera, None, std::ptr::null_mut()) };
+        }
+        if self.state.take().is_some() {
+            let _ = unsafe { set_camera_state_event_handler(camera, None, std::ptr::null_mut()) };
+        }
+    }
+}
+
+fn add<F>(
+    subscribers: &mut Option<Subscribers<F>>,
+    id: HandlerId,
+    handler: F,
+    register: impl FnOnce(*mut EdsVoid) -> Result<(), EdsError>,
+) -> Result<(), EdsError> {
+    if let Some(subscribers) = subscribers {
+        lock(subscribers).push((id, handler));
+        return Ok(());
+    }
+
+    let new_subscribers = Box::new(Mutex::new(vec![(id, handler)]));
+    register(&*new_subscribers as *const _ as *mut EdsVoid)?;
+    *subscribers = Some(new_subscribers);
+    Ok(())
+}
+
+/// Returns whether a handler with `id` was found.
+fn remove<F>(
+    subscribers: &mut Option<Subscribers<F>>,
+    id: HandlerId,
+    unregister: impl FnOnce() -> Result<(), EdsError>,
+) -> Result<bool, EdsError> {
+    let Some(list) = subscribers else {
+        return Ok(false);
+    };
+
+    let mut handlers = lock(list);
+    let Some(index) = handlers
+        .iter()
+        .position(|(handler_id, _)| *handler_id == id)
+    else {
+        return Ok(false);
+    };
+
+    if handlers.len() > 1 {
+        handlers.remove(index);
+        return Ok(true);
+    }
+
+    // the SDK must not call the trampoline anymore before the list is freed
+    drop(handlers);
+    unregister()?;
+    *subscribers = None;
+    Ok(true)
+}
+
+/// The list isn't poisoned by a panicking handler, those are caught in [`dispatch`].
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+/// Calls every handler in the list behind `context`. Panics don't unwind into the SDK, the
+/// remaining handlers still run and the SDK gets an internal error instead.
+///
+/// # Safety
+/// `context` must be the list registered by [`add`], for handlers of type `F`.
+unsafe fn dispatch<F>(context: *mut EdsVoid, mut call: impl FnMut(&mut F)) -> EdsErrorCode {
+    let subscribers = unsafe { &*(context as *const Mutex<Vec<(HandlerId, F)>>) };
+
+    let mut panicked = false;
+    for (_, handler) in lock(subscribers).iter_mut() {
+        panicked |= catch_unwind(AssertUnwindSafe(|| call(handler))).is_err();
+    }
+
+    if panicked {
+        EdsError::InternalError.into()
+    } else {
+        EdsErrorCode::OK
+    }
+}
+
+extern "C" fn object_trampoline(
+    event: EdsObjectEvent,
+    object: EdsBaseRef,
+    context: *mut EdsVoid,
+) -> EdsErrorCode {
+    let event = ObjectEvent::from(event);
+
+    // the handler owns the reference, it's released once every handler ran
+    let item = match event {
+        ObjectEvent::DirItemCreated
+        | ObjectEvent::DirItemRemoved
+        | ObjectEvent::DirItemInfoChanged
+        | ObjectEvent::DirItemContentChanged
+        | ObjectEvent::DirItemRequestTransfer
+        | ObjectEvent::DirItemRequestTransferDt
+        | ObjectEvent::DirItemCancelTransferDt
+        | ObjectEvent::FolderUpdateItems => Some(unsafe { DirectoryItem::from_raw(object) }),
+        _ => {
+            drop(unsafe { OwnedRef::from_raw(object) });
+            None
+        }
+    };
+
+    unsafe { dispatch::<ObjectEventHandler>(context, |handler| handler(event, item.as_ref())) }
+}
+
+extern "C" fn property_trampoline(
+    event: EdsPropertyEvent,
+    property_id: EdsPropertyId,
+    param: u32,
+    context: *mut EdsVoid,
+) -> EdsErrorCode {
+    let event = PropertyEvent::from_raw(event, property_id, param);
+    unsafe { dispatch::<PropertyEventHandler>(context, |handler| handler(event)) }
+}
+
+extern "C" fn state_trampoline(
+    event: EdsStateEvent,
+    event_data: u32,
+    context: *mut EdsVoid,
+) -> EdsErrorCode {
+    let event = StateEvent::from_raw(event, event_data);
+    unsafe { dispatch::<StateEventHandler>(context, |handler| handler(event)) }
+}
+
+pub(crate) extern "C" fn progress_trampoline(
+    percent: u32,
+    context: *mut EdsVoid,
+    cancel: *mut EdsBool,
+) -> EdsErrorCode {
+    let handler = unsafe { &*(context as *const Mutex<ProgressHandler>) };
+
+    match catch_unwind(AssertUnwindSafe(|| lock(handler)(percent))) {
+        Ok(true) => EdsErrorCode::OK,
+        Ok(false) => {
+            if !cancel.is_null() {
+                unsafe { cancel.write(1) };
+            }
+            EdsErrorCode::OK
+        }
+        Err(_) => EdsError::InternalError.into(),
+    }
+}
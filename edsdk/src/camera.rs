@@ -0,0 +1,279 @@
+use std::{marker::PhantomData, sync::Mutex};
+
+use crate::{
+    callback::EventHandlers, get_property_size, get_property_with_param, handle::OwnedRef,
+    send_command, set_camera_state_event_handler, set_object_event_handler,
+    set_property_event_handler, set_property_with_param, CameraCommand, DirectoryItem,
+    EdsCameraRef, EdsCapacity, EdsCloseSession, EdsDataType, EdsDeviceInfo, EdsDownloadEvfImage,
+    EdsError, EdsGetChildAtIndex, EdsGetChildCount, EdsGetDeviceInfo, EdsGetPropertyDesc,
+    EdsObjectEventHandler, EdsOpenSession, EdsPropertyDesc, EdsPropertyEventHandler,
+    EdsSetCapacity, EdsStateEventHandler, EdsVoid, EdsVolumeRef, EvfImage, HandlerId, ObjectEvent,
+    PropertyData, PropertyEvent, PropertyId, PropertyValue, Sdk, StateEvent, Volume,
+};
+
+/// What identifies a connected camera, see [`crate::CameraList::describe`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct CameraDescription {
+    pub port_name: String,
+    pub description: String,
+    pub sub_type: u32,
+    /// The serial number of the body (`BodyIDEx`). This and the other properties are `None` if
+    /// they couldn't be read, e.g. because another application has a session open.
+    pub body_id: Option<String>,
+    pub product_name: Option<String>,
+    pub firmware_version: Option<String>,
+}
+
+/// A camera, see [`crate::CameraList`]. Event handlers are unregistered and an open session is
+/// closed when this is dropped.
+pub struct Camera<'sdk> {
+    raw: OwnedRef,
+    session_open: bool,
+    handlers: Mutex<EventHandlers>,
+    _sdk: PhantomData<&'sdk Sdk>,
+}
+
+impl<'sdk> Camera<'sdk> {
+    /// # Safety
+    /// `raw` must be a valid camera reference that the caller owns.
+    pub unsafe fn from_raw(raw: EdsCameraRef) -> Self {
+        Self {
+            raw: unsafe { OwnedRef::from_raw(raw) },
+            session_open: false,
+            handlers: Mutex::default(),
+            _sdk: PhantomData,
+        }
+    }
+
+    pub fn as_raw(&self) -> EdsCameraRef {
+        self.raw.as_raw()
+    }
+
+    pub fn device_info(&self) -> Result<EdsDeviceInfo, EdsError> {
+        let mut device_info = EdsDeviceInfo::default();
+        unsafe { EdsGetDeviceInfo(self.as_raw(), &mut device_info).res() }?;
+        Ok(device_info)
+    }
+
+    /// Reads what identifies this camera. Properties can only be read during a session, so one is
+    /// opened and closed again if there is none.
+    pub fn describe(&mut self) -> Result<CameraDescription, EdsError> {
+        let device_info = self.device_info()?;
+        let temporary_session = !self.session_open && self.open_session().is_ok();
+
+        let description = CameraDescription {
+            port_name: device_info.sz_port_name().to_string(),
+            description: device_info.sz_device_description().to_string(),
+            sub_type: device_info.device_sub_type,
+            body_id: self.get_property(PropertyId::BodyIdEx).ok(),
+            product_name: self.get_property(PropertyId::ProductName).ok(),
+            firmware_version: self.get_property(PropertyId::FirmwareVersion).ok(),
+        };
+
+        if temporary_session {
+            self.close_session()?;
+        }
+
+        Ok(description)
+    }
+
+    /// Reads the serial number of the body (`BodyIDEx`). Properties can only be read during a
+    /// session, so one is opened if there is none and kept open for using the camera.
+    pub fn body_id(&mut self) -> Result<String, EdsError> {
+        self.open_session()?;
+        self.get_property(PropertyId::BodyIdEx)
+    }
+
+    /// Opens a session, unless one is open already.
+    pub fn open_session(&mut self) -> Result<(), EdsError> {
+        if self.session_open {
+            return Ok(());
+        }
+
+        unsafe { EdsOpenSession(self.as_raw()).res() }?;
+        self.session_open = true;
+        Ok(())
+    }
+
+    pub fn close_session(&mut self) -> Result<(), EdsError> {
+        if !self.session_open {
+            return Ok(());
+        }
+
+        self.session_open = false;
+        unsafe { EdsCloseSession(self.as_raw()).res() }
+    }
+
+    pub fn is_session_open(&self) -> bool {
+        self.session_open
+    }
+
+    pub fn property_size(
+        &self,
+        property: PropertyId,
+        param: i32,
+    ) -> Result<(EdsDataType, u32), EdsError> {
+        unsafe { get_property_size(self.as_raw(), property, param) }
+    }
+
+    /// Returns the values `property` can currently be set to.
+    pub fn property_desc(&self, property: PropertyId) -> Result<EdsPropertyDesc, EdsError> {
+        let mut desc = EdsPropertyDesc::default();
+        unsafe { EdsGetPropertyDesc(self.as_raw(), property.code(), &mut desc).res() }?;
+        Ok(desc)
+    }
+
+    /// Like [`Self::property_desc`], with every value decoded according to `property`.
+    pub fn available_values(&self, property: PropertyId) -> Result<Vec<PropertyValue>, EdsError> {
+        Ok(self
+            .property_desc(property)?
+            .values()
+            .iter()
+            .map(|code| PropertyValue::decode(property, *code as u32))
+            .collect())
+    }
+
+    pub fn get_property<T: PropertyData>(&self, property: PropertyId) -> Result<T, EdsError> {
+        self.get_property_with_param(property, 0)
+    }
+
+    pub fn get_property_with_param<T: PropertyData>(
+        &self,
+        property: PropertyId,
+        param: i32,
+    ) -> Result<T, EdsError> {
+        unsafe { get_property_with_param(self.as_raw(), property, param) }
+    }
+
+    pub fn set_property<T: PropertyData>(
+        &self,
+        property: PropertyId,
+        value: &T,
+    ) -> Result<(), EdsError> {
+        self.set_property_with_param(property, 0, value)
+    }
+
+    pub fn set_property_with_param<T: PropertyData>(
+        &self,
+        property: PropertyId,
+        param: i32,
+        value: &T,
+    ) -> Result<(), EdsError> {
+        unsafe { set_property_with_param(self.as_raw(), property, param, value) }
+    }
+
+    pub fn send_command(&self, command: CameraCommand) -> Result<(), EdsError> {
+        unsafe { send_command(self.as_raw(), command) }
+    }
+
+    /// Tells the camera how much space is left on the host when saving to [`crate::EdsSaveTo::Host`].
+    pub fn set_capacity(&self, capacity: EdsCapacity) -> Result<(), EdsError> {
+        unsafe { EdsSetCapacity(self.as_raw(), capacity).res() }
+    }
+
+    /// Calls `handler` for every object event of this camera. Directory items passed to the
+    /// handler are released once every handler ran, download them from within the handler.
+    ///
+    /// Handlers run on the thread calling [`Sdk::get_event`] and must not register or remove
+    /// handlers themselves.
+    pub fn on_object_event(
+        &self,
+        handler: impl FnMut(ObjectEvent, Option<&DirectoryItem>) + Send + 'static,
+    ) -> Result<HandlerId, EdsError> {
+        self.lock_handlers()
+            .add_object(self.as_raw(), Box::new(handler))
+    }
+
+    /// Calls `handler` for every property event of this camera, see [`Self::on_object_event`].
+    pub fn on_property_event(
+        &self,
+        handler: impl FnMut(PropertyEvent) + Send + 'static,
+    ) -> Result<HandlerId, EdsError> {
+        self.lock_handlers()
+            .add_property(self.as_raw(), Box::new(handler))
+    }
+
+    /// Calls `handler` for every state event of this camera, see [`Self::on_object_event`].
+    pub fn on_state_event(
+        &self,
+        handler: impl FnMut(StateEvent) + Send + 'static,
+    ) -> Result<HandlerId, EdsError> {
+        self.lock_handlers()
+            .add_state(self.as_raw(), Box::new(handler))
+    }
+
+    /// Removes a handler registered with one of the `on_*_event` methods.
+    pub fn remove_event_handler(&self, id: HandlerId) -> Result<(), EdsError> {
+        self.lock_handlers().remove(self.as_raw(), id)
+    }
+
+    fn lock_handlers(&self) -> std::sync::MutexGuard<'_, EventHandlers> {
+        self.handlers.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Replaces the handlers registered with [`Self::on_object_event`].
+    ///
+    /// # Safety
+    /// `context` must stay valid for as long as the handler is registered.
+    pub unsafe fn set_object_event_handler(
+        &self,
+        handler: Option<EdsObjectEventHandler>,
+        context: *mut EdsVoid,
+    ) -> Result<(), EdsError> {
+        unsafe { set_object_event_handler(self.as_raw(), handler, context) }
+    }
+
+    /// Replaces the handlers registered with [`Self::on_property_event`].
+    ///
+    /// # Safety
+    /// `context` must stay valid for as long as the handler is registered.
+    pub unsafe fn set_property_event_handler(
+        &self,
+        handler: Option<EdsPropertyEventHandler>,
+        context: *mut EdsVoid,
+    ) -> Result<(), EdsError> {
+        unsafe { set_property_event_handler(self.as_raw(), handler, context) }
+    }
+
+    /// Replaces the handlers registered with [`Self::on_state_event`].
+    ///
+    /// # Safety
+    /// `context` must stay valid for as long as the handler is registered.
+    pub unsafe fn set_camera_state_event_handler(
+        &self,
+        handler: Option<EdsStateEventHandler>,
+        context: *mut EdsVoid,
+    ) -> Result<(), EdsError> {
+        unsafe { set_camera_state_event_handler(self.as_raw(), handler, context) }
+    }
+
+    /// The storage cards inserted into the camera.
+    pub fn volumes(&self) -> Result<Vec<Volume<'sdk>>, EdsError> {
+        let mut count = 0;
+        unsafe { EdsGetChildCount(self.as_raw(), &mut count).res() }?;
+
+        (0..count)
+            .map(|index| {
+                let mut volume = std::ptr::null_mut() as EdsVolumeRef;
+                unsafe { EdsGetChildAtIndex(self.as_raw(), index, &mut volume).res() }?;
+                Ok(unsafe { Volume::from_raw(volume) })
+            })
+            .collect()
+    }
+
+    /// Downloads the current live view frame into the stream backing `evf_image`.
+    pub fn download_evf_image(&self, evf_image: &EvfImage<'_>) -> Result<(), EdsError> {
+        unsafe { EdsDownloadEvfImage(self.as_raw(), evf_image.as_raw()).res() }
+    }
+}
+
+impl Drop for Camera<'_> {
+    fn drop(&mut self) {
+        let raw = self.as_raw();
+        self.handlers
+            .get_mut()
+            .unwrap_or_else(|err| err.into_inner())
+            .clear(raw);
+        let _ = self.close_session();
+    }
+}
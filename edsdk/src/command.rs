@@ -0,0 +1,123 @@
+use crate::{EdsCameraCommand, EdsCameraRef, EdsError, EdsPoint, EdsSendCommand};
+
+/// Shutter button states for [`CameraCommand::PressShutterButton`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ShutterButton {
+    Off,
+    Halfway,
+    Completely,
+
+    /// Press halfway without triggering auto focus.
+    HalfwayNonAf,
+
+    /// Press completely without triggering auto focus.
+    CompletelyNonAf,
+}
+
+impl ShutterButton {
+    pub fn param(&self) -> i32 {
+        match self {
+            Self::Off => 0x00000,
+            Self::Halfway => 0x00001,
+            Self::Completely => 0x00003,
+            Self::HalfwayNonAf => 0x10001,
+            Self::CompletelyNonAf => 0x10003,
+        }
+    }
+}
+
+/// Auto focus states for [`CameraCommand::DoEvfAf`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EvfAf {
+    Off,
+    On,
+}
+
+impl EvfAf {
+    pub fn param(&self) -> i32 {
+        match self {
+            Self::Off => 0,
+            Self::On => 1,
+        }
+    }
+}
+
+/// Focus steps for [`CameraCommand::DriveLensEvf`], from the smallest (`1`) to the largest (`3`).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DriveLens {
+    Near1,
+    Near2,
+    Near3,
+    Far1,
+    Far2,
+    Far3,
+}
+
+impl DriveLens {
+    pub fn param(&self) -> i32 {
+        match self {
+            Self::Near1 => 0x0001,
+            Self::Near2 => 0x0002,
+            Self::Near3 => 0x0003,
+            Self::Far1 => 0x8001,
+            Self::Far2 => 0x8002,
+            Self::Far3 => 0x8003,
+        }
+    }
+}
+
+/// Commands that can be sent to a camera with `EdsSendCommand`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CameraCommand {
+    TakePicture,
+
+    /// Resets the auto power-off timer of the camera.
+    ExtendShutDownTimer,
+
+    BulbStart,
+    BulbEnd,
+    PressShutterButton(ShutterButton),
+
+    /// Auto focus while live view is running.
+    DoEvfAf(EvfAf),
+
+    /// Moves the focus while live view is running.
+    DriveLensEvf(DriveLens),
+
+    /// Sets the white balance from the given point of the live view image.
+    DoClickWbEvf(EdsPoint),
+}
+
+impl CameraCommand {
+    pub fn code(&self) -> EdsCameraCommand {
+        match self {
+            Self::TakePicture => 0x000,
+            Self::ExtendShutDownTimer => 0x001,
+            Self::BulbStart => 0x002,
+            Self::BulbEnd => 0x003,
+            Self::PressShutterButton(_) => 0x004,
+            Self::DoEvfAf(_) => 0x102,
+            Self::DriveLensEvf(_) => 0x103,
+            Self::DoClickWbEvf(_) => 0x104,
+        }
+    }
+
+    pub fn param(&self) -> i32 {
+        match self {
+            Self::TakePicture | Self::ExtendShutDownTimer | Self::BulbStart | Self::BulbEnd => 0,
+            Self::PressShutterButton(button) => button.param(),
+            Self::DoEvfAf(af) => af.param(),
+            Self::DriveLensEvf(drive) => drive.param(),
+            // x in the upper, y in the lower 16 bits
+            Self::DoClickWbEvf(point) => ((point.x & 0xffff) << 16) | (point.y & 0xffff),
+        }
+    }
+}
+
+/// Sends `command` to `camera`.
+///
+/// # Safety
+/// `camera` must be a valid camera reference with an open session.
+pub unsafe fn send_command(camera: EdsCameraRef, command: CameraCommand) -> Result<(), EdsError> {
+    unsafe { EdsSendCommand(camera, command.code(), command.param()).res() }
+}
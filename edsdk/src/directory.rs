@@ -0,0 +1,126 @@
+use std::marker::PhantomData;
+
+use crate::{
+    handle::OwnedRef, EdsBaseRef, EdsDeleteDirectoryItem, EdsDirectoryItemInfo,
+    EdsDirectoryItemRef, EdsDownload, EdsDownloadCancel, EdsDownloadComplete, EdsDownloadThumbnail,
+    EdsError, EdsGetChildAtIndex, EdsGetChildCount, EdsGetDirectoryItemInfo, Sdk, Stream,
+};
+
+/// A file or folder on the camera, e.g. a picture waiting to be transferred.
+pub struct DirectoryItem<'sdk> {
+    raw: OwnedRef,
+    _sdk: PhantomData<&'sdk Sdk>,
+}
+
+impl<'sdk> DirectoryItem<'sdk> {
+    /// Takes ownership of `raw`, e.g. the reference passed to an object event handler.
+    ///
+    /// # Safety
+    /// `raw` must be a valid directory item reference that the caller owns.
+    pub unsafe fn from_raw(raw: EdsDirectoryItemRef) -> Self {
+        Self {
+            raw: unsafe { OwnedRef::from_raw(raw) },
+            _sdk: PhantomData,
+        }
+    }
+
+    pub fn as_raw(&self) -> EdsDirectoryItemRef {
+        self.raw.as_raw()
+    }
+
+    pub fn info(&self) -> Result<EdsDirectoryItemInfo, EdsError> {
+        let mut info = EdsDirectoryItemInfo::default();
+        unsafe { EdsGetDirectoryItemInfo(self.as_raw(), &mut info).res() }?;
+        Ok(info)
+    }
+
+    /// Downloads the first `size` bytes of the item into `stream`.
+    ///
+    /// A transfer requested by the camera has to be finished with [`Self::download_complete`]
+    /// or [`Self::download_cancel`].
+    pub fn download(&self, size: u64, stream: &Stream<'_>) -> Result<(), EdsError> {
+        unsafe { EdsDownload(self.as_raw(), size, stream.as_raw()).res() }
+    }
+
+    /// Downloads the thumbnail embedded in the item into `stream`, read it with [`crate::Image`]
+    /// and [`crate::EdsImageSource::Thumbnail`]. This doesn't finish a transfer requested by the
+    /// camera.
+    pub fn download_thumbnail(&self, stream: &Stream<'_>) -> Result<(), EdsError> {
+        unsafe { EdsDownloadThumbnail(self.as_raw(), stream.as_raw()).res() }
+    }
+
+    /// Downloads the whole item into memory.
+    ///
+    /// Like [`Self::download`], a transfer requested by the camera still has to be finished.
+    pub fn download_to_vec(&self, sdk: &'sdk Sdk) -> Result<Vec<u8>, EdsError> {
+        let size = self.info()?.size;
+        let stream = Stream::create_memory(sdk, size)?;
+        self.download(size, &stream)?;
+        stream.to_vec()
+    }
+
+    /// The items in this folder.
+    pub fn children(&self) -> Result<Vec<DirectoryItem<'sdk>>, EdsError> {
+        unsafe { children(self.as_raw()) }
+    }
+
+    /// Deletes the item from the card.
+    pub fn delete(self) -> Result<(), EdsError> {
+        unsafe { EdsDeleteDirectoryItem(self.as_raw()).res() }
+    }
+
+    pub fn download_complete(&self) -> Result<(), EdsError> {
+        unsafe { EdsDownloadComplete(self.as_raw()).res() }
+    }
+
+    pub fn download_cancel(&self) -> Result<(), EdsError> {
+        unsafe { EdsDownloadCancel(self.as_raw()).res() }
+    }
+}
+
+/// A file found by [`crate::Volume::files`], `path` is relative to the volume root.
+pub struct DirectoryEntry<'sdk> {
+    pub path: String,
+    pub item: DirectoryItem<'sdk>,
+}
+
+/// # Safety
+/// `parent` must be a valid volume or directory item reference.
+pub(crate) unsafe fn children<'sdk>(
+    parent: EdsBaseRef,
+) -> Result<Vec<DirectoryItem<'sdk>>, EdsError> {
+    let mut count = 0;
+    unsafe { EdsGetChildCount(parent, &mut count).res() }?;
+
+    (0..count)
+        .map(|index| {
+            let mut child = std::ptr::null_mut() as EdsDirectoryItemRef;
+            unsafe { EdsGetChildAtIndex(parent, index, &mut child).res() }?;
+            Ok(unsafe { DirectoryItem::from_raw(child) })
+        })
+        .collect()
+}
+
+/// Collects the files below `items`, descending into folders.
+pub(crate) fn collect_files<'sdk>(
+    prefix: &str,
+    items: Vec<DirectoryItem<'sdk>>,
+    files: &mut Vec<DirectoryEntry<'sdk>>,
+) -> Result<(), EdsError> {
+    for item in items {
+        let info = item.info()?;
+        let path = if prefix.is_empty() {
+            info.sz_file_name().to_string()
+        } else {
+            format!("{}/{}", prefix, info.sz_file_name())
+        };
+
+        if info.is_folder != 0 {
+            collect_files(&path, item.children()?, files)?;
+        } else {
+            files.push(DirectoryEntry { path, item });
+        }
+    }
+
+    Ok(())
+}
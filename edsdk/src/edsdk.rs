@@ -1,42 +1,12 @@
 /// Rust bindings for Canon's EDSDK (v. 13.20.10)
-use std::ffi::CStr;
+use std::{borrow::Cow, ffi::CStr};
 
 use bitflags::bitflags;
 
-#[repr(u32)]
-#[derive(PartialEq, Eq, Debug)]
-pub enum EdsError {
-    Ok = 0,
-
-    /// This error doesn't exist on the EDSDK but is used to indicate that no camera was found.
-    NoCameraFound = u32::MAX,
-}
-
-impl EdsError {
-    pub fn is_ok(&self) -> bool {
-        *self == EdsError::Ok
-    }
-
-    pub fn res(self) -> Result<(), EdsError> {
-        if self.is_ok() {
-            Ok(())
-        } else {
-            Err(self)
-        }
-    }
-}
-
-impl From<EdsError> for Result<(), EdsError> {
-    fn from(value: EdsError) -> Self {
-        if value.is_ok() {
-            Ok(())
-        } else {
-            Err(value)
-        }
-    }
-}
+use crate::{EdsError, EdsErrorCode};
 
 #[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum EdsFileCreateDisposition {
     CreateNew = 0,
     CreateAlways = 1,
@@ -45,7 +15,8 @@ pub enum EdsFileCreateDisposition {
     TruncateExisting = 4,
 }
 
-#[repr(C)]
+#[repr(u32)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum EdsAccess {
     Read = 0,
     Write = 1,
@@ -54,14 +25,35 @@ pub enum EdsAccess {
 }
 
 #[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum EdsSaveTo {
     Camera = 1,
     Host = 2,
     Both = Self::Camera as isize | Self::Host as isize,
 }
 
+impl TryFrom<u32> for EdsSaveTo {
+    type Error = u32;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Self::Camera),
+            2 => Ok(Self::Host),
+            3 => Ok(Self::Both),
+            _ => Err(value),
+        }
+    }
+}
+
+impl From<EdsSaveTo> for u32 {
+    fn from(value: EdsSaveTo) -> Self {
+        value as u32
+    }
+}
+
 bitflags! {
     #[repr(transparent)]
+    #[derive(Clone, Copy, PartialEq, Eq, Debug)]
     pub struct EdsEvfOutputDevice : u32 {
         const TFT = 1;
         const PC = 2;
@@ -69,12 +61,94 @@ bitflags! {
     }
 }
 
+impl From<u32> for EdsEvfOutputDevice {
+    fn from(value: u32) -> Self {
+        Self::from_bits_retain(value)
+    }
+}
+
+impl From<EdsEvfOutputDevice> for u32 {
+    fn from(value: EdsEvfOutputDevice) -> Self {
+        value.bits()
+    }
+}
+
 impl Default for EdsEvfOutputDevice {
     fn default() -> Self {
         EdsEvfOutputDevice::TFT
     }
 }
 
+/// Data type of a property as reported by `EdsGetPropertySize`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum EdsDataType {
+    Unknown,
+    Bool,
+    String,
+    Int8,
+    UInt8,
+    Int16,
+    UInt16,
+    Int32,
+    UInt32,
+    Int64,
+    UInt64,
+    Float,
+    Double,
+    ByteBlock,
+    Rational,
+    Point,
+    Rect,
+    Time,
+    BoolArray,
+    Int8Array,
+    Int16Array,
+    Int32Array,
+    UInt8Array,
+    UInt16Array,
+    UInt32Array,
+    RationalArray,
+    FocusInfo,
+    PictureStyleDesc,
+    Other(u32),
+}
+
+impl From<u32> for EdsDataType {
+    fn from(value: u32) -> Self {
+        match value {
+            0 => Self::Unknown,
+            1 => Self::Bool,
+            2 => Self::String,
+            3 => Self::Int8,
+            6 => Self::UInt8,
+            4 => Self::Int16,
+            7 => Self::UInt16,
+            8 => Self::Int32,
+            9 => Self::UInt32,
+            10 => Self::Int64,
+            11 => Self::UInt64,
+            12 => Self::Float,
+            13 => Self::Double,
+            14 => Self::ByteBlock,
+            20 => Self::Rational,
+            21 => Self::Point,
+            22 => Self::Rect,
+            23 => Self::Time,
+            30 => Self::BoolArray,
+            31 => Self::Int8Array,
+            32 => Self::Int16Array,
+            33 => Self::Int32Array,
+            34 => Self::UInt8Array,
+            35 => Self::UInt16Array,
+            36 => Self::UInt32Array,
+            37 => Self::RationalArray,
+            101 => Self::FocusInfo,
+            102 => Self::PictureStyleDesc,
+            other => Self::Other(other),
+        }
+    }
+}
+
 pub type EdsVoid = std::os::raw::c_void;
 
 pub type EdsChar = std::os::raw::c_char;
@@ -87,17 +161,49 @@ pub type EdsCameraListRef = EdsBaseRef;
 
 pub type EdsStreamRef = EdsBaseRef;
 
+pub type EdsVolumeRef = EdsBaseRef;
 pub type EdsDirectoryItemRef = EdsBaseRef;
 
 pub type EdsEvfImageRef = EdsBaseRef;
+pub type EdsImageRef = EdsBaseRef;
 
 pub type EdsCameraCommand = u32;
 
 pub type EdsPropertyId = u32;
 
 pub type EdsObjectEvent = u32;
-pub type EdsObjectEventHandler =
-    extern "C" fn(event: EdsObjectEvent, object_ref: EdsBaseRef, context: *mut EdsVoid) -> EdsError;
+pub type EdsObjectEventHandler = extern "C" fn(
+    event: EdsObjectEvent,
+    object_ref: EdsBaseRef,
+    context: *mut EdsVoid,
+) -> EdsErrorCode;
+
+pub type EdsPropertyEvent = u32;
+pub type EdsPropertyEventHandler = extern "C" fn(
+    event: EdsPropertyEvent,
+    property_id: EdsPropertyId,
+    param: u32,
+    context: *mut EdsVoid,
+) -> EdsErrorCode;
+
+pub type EdsStateEvent = u32;
+pub type EdsStateEventHandler =
+    extern "C" fn(event: EdsStateEvent, event_data: u32, context: *mut EdsVoid) -> EdsErrorCode;
+
+/// Setting `cancel` to a non-zero value aborts the operation.
+pub type EdsProgressCallback =
+    extern "C" fn(percent: u32, context: *mut EdsVoid, cancel: *mut EdsBool) -> EdsErrorCode;
+
+/// When a progress callback is called.
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EdsProgressOption {
+    NoReport = 0,
+    /// Once the operation is done.
+    Done = 1,
+    /// Every few percent.
+    Periodically = 2,
+}
 
 #[repr(C)]
 pub struct EdsDeviceInfo {
@@ -132,6 +238,35 @@ impl Default for EdsDeviceInfo {
     }
 }
 
+#[repr(C)]
+#[derive(Debug)]
+pub struct EdsVolumeInfo {
+    pub storage_type: u32,
+    pub access: u32,
+    pub max_capacity: u64,
+    pub free_space_in_bytes: u64,
+    pub sz_volume_label: [EdsChar; 256],
+}
+
+impl EdsVolumeInfo {
+    /// The label as the camera reports it, bytes that aren't valid UTF-8 are replaced.
+    pub fn sz_volume_label(&self) -> Cow<'_, str> {
+        unsafe { CStr::from_ptr(self.sz_volume_label.as_ptr()).to_string_lossy() }
+    }
+}
+
+impl Default for EdsVolumeInfo {
+    fn default() -> Self {
+        Self {
+            storage_type: Default::default(),
+            access: Default::default(),
+            max_capacity: Default::default(),
+            free_space_in_bytes: Default::default(),
+            sz_volume_label: [0; 256],
+        }
+    }
+}
+
 #[repr(C)]
 #[derive(Debug)]
 pub struct EdsDirectoryItemInfo {
@@ -166,122 +301,473 @@ impl Default for EdsDirectoryItemInfo {
 }
 
 #[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct EdsPoint {
+    pub x: i32,
+    pub y: i32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct EdsSize {
+    pub width: i32,
+    pub height: i32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct EdsRect {
+    pub point: EdsPoint,
+    pub size: EdsSize,
+}
+
+/// Which of the images contained in an image file to read.
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EdsImageSource {
+    FullView = 0,
+    /// The embedded thumbnail, usually 160x120.
+    Thumbnail = 1,
+    /// The embedded preview of a RAW file.
+    Preview = 2,
+    RawThumbnail = 3,
+    RawFullView = 4,
+}
+
+/// The pixel format `EdsGetImage` outputs.
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EdsTargetImageType {
+    Unknown = 0,
+    Jpeg = 1,
+    Tiff = 7,
+    Tiff16 = 8,
+    Rgb = 9,
+    Rgb16 = 10,
+    Dib = 11,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct EdsImageInfo {
+    pub width: u32,
+    pub height: u32,
+    pub num_of_components: u32,
+    pub component_depth: u32,
+    /// The area of the image that contains the picture, thumbnails are letterboxed.
+    pub effective_rect: EdsRect,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct EdsRational {
+    pub numerator: i32,
+    pub denominator: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct EdsTime {
+    pub year: u32,
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+    pub milliseconds: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct EdsFocusPoint {
+    pub valid: u32,
+    pub selected: u32,
+    pub just_focus: u32,
+    pub rect: EdsRect,
+    pub reserved: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct EdsPropertyDesc {
+    pub form: i32,
+    pub access: i32,
+    pub num_elements: i32,
+    pub prop_desc: [i32; 128],
+}
+
+impl EdsPropertyDesc {
+    /// The values the property can currently be set to.
+    pub fn values(&self) -> &[i32] {
+        let len = self.num_elements.clamp(0, self.prop_desc.len() as i32) as usize;
+        &self.prop_desc[..len]
+    }
+}
+
+impl Default for EdsPropertyDesc {
+    fn default() -> Self {
+        Self {
+            form: Default::default(),
+            access: Default::default(),
+            num_elements: Default::default(),
+            prop_desc: [0; 128],
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
 pub struct EdsCapacity {
     pub number_of_free_clusters: u32,
     pub bytes_per_sector: u32,
     pub reset: EdsBool,
 }
 
-/// *TODO*: The rest of the quality options
-#[repr(C)]
-pub enum EdsImageQuality {
-    JpegLarge = 0x0010ff0f,         /* Jpeg Large */
-    JpegMiddle = 0x0110ff0f,        /* Jpeg Middle */
-    JpegMiddle1 = 0x0510ff0f,       /* Jpeg Middle1 */
-    JpegMiddle1Fine = 0x0513FF0F,   /* Jpeg Middle1 Fine */
-    JpegMiddle1Normal = 0x0512FF0F, /* Jpeg Middle1 Normal */
-    JpegMiddle2 = 0x0610ff0f,       /* Jpeg Middle2 */
-    JpegMiddle2Fine = 0x0613FF0F,   /* Jpeg Middle2 Fine */
-    JpegMiddle2Normal = 0x0612FF0F, /* Jpeg Middle2 Normal */
-    JpegSmall = 0x0210ff0f,         /* Jpeg Small */
-    JpegSmall1 = 0x0e10ff0f,        /* Jpeg Small1 */
-    JpegSmall2 = 0x0f10ff0f,        /* Jpeg Small2 */
-    JpegLargeFine = 0x0013ff0f,     /* Jpeg Large Fine */
-    JpegLargeNormal = 0x0012ff0f,   /* Jpeg Large Normal */
-    JpegMiddleFine = 0x0113ff0f,    /* Jpeg Middle Fine */
-    JpegMiddleNormal = 0x0112ff0f,  /* Jpeg Middle Normal */
-    JpegSmallFine = 0x0213ff0f,     /* Jpeg Small Fine */
-    JpegSmallNormal = 0x0212ff0f,   /* Jpeg Small Normal */
-    JpegSmall1Fine = 0x0E13ff0f,    /* Jpeg Small1 Fine */
-    JpegSmall1Normal = 0x0E12ff0f,  /* Jpeg Small1 Normal */
-    JpegSmall2Fine = 0x0F13ff0f,    /* Jpeg Small2 */
-    JpegSmall3 = 0x1013ff0f,        /* Jpeg Small3 */
+macro_rules! image_qualities {
+    ($($name:ident = $code:literal => $description:literal,)*) => {
+        /// The `kEdsImageQuality_*` values. Codes that aren't listed are kept as `Unknown`.
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+        pub enum EdsImageQuality {
+            $(#[doc = $description] $name,)*
+            Unknown(u32),
+        }
+
+        impl EdsImageQuality {
+            pub fn description(&self) -> &'static str {
+                match self {
+                    $(Self::$name => $description,)*
+                    Self::Unknown(_) => "unknown image quality",
+                }
+            }
+        }
+
+        impl From<u32> for EdsImageQuality {
+            fn from(value: u32) -> Self {
+                match value {
+                    $($code => Self::$name,)*
+                    value => Self::Unknown(value),
+                }
+            }
+        }
+
+        impl From<EdsImageQuality> for u32 {
+            fn from(value: EdsImageQuality) -> Self {
+                match value {
+                    $(EdsImageQuality::$name => $code,)*
+                    EdsImageQuality::Unknown(value) => value,
+                }
+            }
+        }
+
+        impl std::str::FromStr for EdsImageQuality {
+            type Err = EdsError;
+
+            /// Parses a variant name like `JpegSmall` or `RawJpegLargeFine`.
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s {
+                    $(stringify!($name) => Ok(Self::$name),)*
+                    _ => Err(EdsError::InvalidParameter),
+                }
+            }
+        }
+    };
 }
 
+image_qualities! {
+    // Jpeg only
+    JpegLarge = 0x0010ff0f => "Jpeg Large",
+    JpegMiddle = 0x0110ff0f => "Jpeg Middle",
+    JpegMiddle1 = 0x0510ff0f => "Jpeg Middle1",
+    JpegMiddle1Fine = 0x0513ff0f => "Jpeg Middle1 Fine",
+    JpegMiddle1Normal = 0x0512ff0f => "Jpeg Middle1 Normal",
+    JpegMiddle2 = 0x0610ff0f => "Jpeg Middle2",
+    JpegMiddle2Fine = 0x0613ff0f => "Jpeg Middle2 Fine",
+    JpegMiddle2Normal = 0x0612ff0f => "Jpeg Middle2 Normal",
+    JpegSmall = 0x0210ff0f => "Jpeg Small",
+    JpegSmall1 = 0x0e10ff0f => "Jpeg Small1",
+    JpegSmall2 = 0x0f10ff0f => "Jpeg Small2",
+    JpegLargeFine = 0x0013ff0f => "Jpeg Large Fine",
+    JpegLargeNormal = 0x0012ff0f => "Jpeg Large Normal",
+    JpegMiddleFine = 0x0113ff0f => "Jpeg Middle Fine",
+    JpegMiddleNormal = 0x0112ff0f => "Jpeg Middle Normal",
+    JpegSmallFine = 0x0213ff0f => "Jpeg Small Fine",
+    JpegSmallNormal = 0x0212ff0f => "Jpeg Small Normal",
+    JpegSmall1Fine = 0x0e13ff0f => "Jpeg Small1 Fine",
+    JpegSmall1Normal = 0x0e12ff0f => "Jpeg Small1 Normal",
+    JpegSmall2Fine = 0x0f13ff0f => "Jpeg Small2",
+    JpegSmall3 = 0x1013ff0f => "Jpeg Small3",
+
+    // RAW + Jpeg
+    Raw = 0x0064ff0f => "RAW",
+    RawJpegLargeFine = 0x00640013 => "RAW + Jpeg Large Fine",
+    RawJpegLargeNormal = 0x00640012 => "RAW + Jpeg Large Normal",
+    RawJpegMiddleFine = 0x00640113 => "RAW + Jpeg Middle Fine",
+    RawJpegMiddleNormal = 0x00640112 => "RAW + Jpeg Middle Normal",
+    RawJpegMiddle1Fine = 0x00640513 => "RAW + Jpeg Middle1 Fine",
+    RawJpegMiddle1Normal = 0x00640512 => "RAW + Jpeg Middle1 Normal",
+    RawJpegMiddle2Fine = 0x00640613 => "RAW + Jpeg Middle2 Fine",
+    RawJpegMiddle2Normal = 0x00640612 => "RAW + Jpeg Middle2 Normal",
+    RawJpegSmallFine = 0x00640213 => "RAW + Jpeg Small Fine",
+    RawJpegSmallNormal = 0x00640212 => "RAW + Jpeg Small Normal",
+    RawJpegSmall1Fine = 0x00640e13 => "RAW + Jpeg Small1 Fine",
+    RawJpegSmall1Normal = 0x00640e12 => "RAW + Jpeg Small1 Normal",
+    RawJpegSmall2Fine = 0x00640f13 => "RAW + Jpeg Small2",
+    RawJpegSmall3 = 0x00641013 => "RAW + Jpeg Small3",
+    RawJpegLarge = 0x00640010 => "RAW + Jpeg Large",
+    RawJpegMiddle1 = 0x00640510 => "RAW + Jpeg Middle1",
+    RawJpegMiddle2 = 0x00640610 => "RAW + Jpeg Middle2",
+    RawJpegSmall = 0x00640210 => "RAW + Jpeg Small",
+
+    // MRAW (SRAW1) + Jpeg
+    MRaw = 0x0164ff0f => "MRAW",
+    MRawJpegLargeFine = 0x01640013 => "MRAW + Jpeg Large Fine",
+    MRawJpegLargeNormal = 0x01640012 => "MRAW + Jpeg Large Normal",
+    MRawJpegMiddleFine = 0x01640113 => "MRAW + Jpeg Middle Fine",
+    MRawJpegMiddleNormal = 0x01640112 => "MRAW + Jpeg Middle Normal",
+    MRawJpegMiddle1Fine = 0x01640513 => "MRAW + Jpeg Middle1 Fine",
+    MRawJpegMiddle1Normal = 0x01640512 => "MRAW + Jpeg Middle1 Normal",
+    MRawJpegMiddle2Fine = 0x01640613 => "MRAW + Jpeg Middle2 Fine",
+    MRawJpegMiddle2Normal = 0x01640612 => "MRAW + Jpeg Middle2 Normal",
+    MRawJpegSmallFine = 0x01640213 => "MRAW + Jpeg Small Fine",
+    MRawJpegSmallNormal = 0x01640212 => "MRAW + Jpeg Small Normal",
+    MRawJpegSmall1Fine = 0x01640e13 => "MRAW + Jpeg Small1 Fine",
+    MRawJpegSmall1Normal = 0x01640e12 => "MRAW + Jpeg Small1 Normal",
+    MRawJpegSmall2Fine = 0x01640f13 => "MRAW + Jpeg Small2",
+    MRawJpegSmall3 = 0x01641013 => "MRAW + Jpeg Small3",
+    MRawJpegLarge = 0x01640010 => "MRAW + Jpeg Large",
+    MRawJpegMiddle1 = 0x01640510 => "MRAW + Jpeg Middle1",
+    MRawJpegMiddle2 = 0x01640610 => "MRAW + Jpeg Middle2",
+    MRawJpegSmall = 0x01640210 => "MRAW + Jpeg Small",
+
+    // SRAW (SRAW2) + Jpeg
+    SRaw = 0x0264ff0f => "SRAW",
+    SRawJpegLargeFine = 0x02640013 => "SRAW + Jpeg Large Fine",
+    SRawJpegLargeNormal = 0x02640012 => "SRAW + Jpeg Large Normal",
+    SRawJpegMiddleFine = 0x02640113 => "SRAW + Jpeg Middle Fine",
+    SRawJpegMiddleNormal = 0x02640112 => "SRAW + Jpeg Middle Normal",
+    SRawJpegMiddle1Fine = 0x02640513 => "SRAW + Jpeg Middle1 Fine",
+    SRawJpegMiddle1Normal = 0x02640512 => "SRAW + Jpeg Middle1 Normal",
+    SRawJpegMiddle2Fine = 0x02640613 => "SRAW + Jpeg Middle2 Fine",
+    SRawJpegMiddle2Normal = 0x02640612 => "SRAW + Jpeg Middle2 Normal",
+    SRawJpegSmallFine = 0x02640213 => "SRAW + Jpeg Small Fine",
+    SRawJpegSmallNormal = 0x02640212 => "SRAW + Jpeg Small Normal",
+    SRawJpegSmall1Fine = 0x02640e13 => "SRAW + Jpeg Small1 Fine",
+    SRawJpegSmall1Normal = 0x02640e12 => "SRAW + Jpeg Small1 Normal",
+    SRawJpegSmall2Fine = 0x02640f13 => "SRAW + Jpeg Small2",
+    SRawJpegSmall3 = 0x02641013 => "SRAW + Jpeg Small3",
+    SRawJpegLarge = 0x02640010 => "SRAW + Jpeg Large",
+    SRawJpegMiddle1 = 0x02640510 => "SRAW + Jpeg Middle1",
+    SRawJpegMiddle2 = 0x02640610 => "SRAW + Jpeg Middle2",
+    SRawJpegSmall = 0x02640210 => "SRAW + Jpeg Small",
+
+    // CRAW + Jpeg
+    CRaw = 0x0063ff0f => "CRAW",
+    CRawJpegLargeFine = 0x00630013 => "CRAW + Jpeg Large Fine",
+    CRawJpegLargeNormal = 0x00630012 => "CRAW + Jpeg Large Normal",
+    CRawJpegMiddleFine = 0x00630113 => "CRAW + Jpeg Middle Fine",
+    CRawJpegMiddleNormal = 0x00630112 => "CRAW + Jpeg Middle Normal",
+    CRawJpegMiddle1Fine = 0x00630513 => "CRAW + Jpeg Middle1 Fine",
+    CRawJpegMiddle1Normal = 0x00630512 => "CRAW + Jpeg Middle1 Normal",
+    CRawJpegMiddle2Fine = 0x00630613 => "CRAW + Jpeg Middle2 Fine",
+    CRawJpegMiddle2Normal = 0x00630612 => "CRAW + Jpeg Middle2 Normal",
+    CRawJpegSmallFine = 0x00630213 => "CRAW + Jpeg Small Fine",
+    CRawJpegSmallNormal = 0x00630212 => "CRAW + Jpeg Small Normal",
+    CRawJpegSmall1Fine = 0x00630e13 => "CRAW + Jpeg Small1 Fine",
+    CRawJpegSmall1Normal = 0x00630e12 => "CRAW + Jpeg Small1 Normal",
+    CRawJpegSmall2Fine = 0x00630f13 => "CRAW + Jpeg Small2",
+    CRawJpegSmall3 = 0x00631013 => "CRAW + Jpeg Small3",
+    CRawJpegLarge = 0x00630010 => "CRAW + Jpeg Large",
+    CRawJpegMiddle1 = 0x00630510 => "CRAW + Jpeg Middle1",
+    CRawJpegMiddle2 = 0x00630610 => "CRAW + Jpeg Middle2",
+    CRawJpegSmall = 0x00630210 => "CRAW + Jpeg Small",
+
+    // HEIF
+    Heif = 0x0080ff0f => "HEIF Large",
+    HeifLargeFine = 0x0083ff0f => "HEIF Large Fine",
+    HeifLargeNormal = 0x0082ff0f => "HEIF Large Normal",
+    HeifMiddleFine = 0x0183ff0f => "HEIF Middle Fine",
+    HeifMiddleNormal = 0x0182ff0f => "HEIF Middle Normal",
+    HeifSmall1Fine = 0x0e83ff0f => "HEIF Small1 Fine",
+    HeifSmall1Normal = 0x0e82ff0f => "HEIF Small1 Normal",
+    HeifSmall2Fine = 0x0f83ff0f => "HEIF Small2 Fine",
+    RawHeif = 0x00640080 => "RAW + HEIF Large",
+    RawHeifLargeFine = 0x00640083 => "RAW + HEIF Large Fine",
+    RawHeifLargeNormal = 0x00640082 => "RAW + HEIF Large Normal",
+    RawHeifMiddleFine = 0x00640183 => "RAW + HEIF Middle Fine",
+    RawHeifMiddleNormal = 0x00640182 => "RAW + HEIF Middle Normal",
+    RawHeifSmall1Fine = 0x00640e83 => "RAW + HEIF Small1 Fine",
+    RawHeifSmall1Normal = 0x00640e82 => "RAW + HEIF Small1 Normal",
+    RawHeifSmall2Fine = 0x00640f83 => "RAW + HEIF Small2 Fine",
+    CRawHeif = 0x00630080 => "CRAW + HEIF Large",
+    CRawHeifLargeFine = 0x00630083 => "CRAW + HEIF Large Fine",
+    CRawHeifLargeNormal = 0x00630082 => "CRAW + HEIF Large Normal",
+    CRawHeifMiddleFine = 0x00630183 => "CRAW + HEIF Middle Fine",
+    CRawHeifMiddleNormal = 0x00630182 => "CRAW + HEIF Middle Normal",
+    CRawHeifSmall1Fine = 0x00630e83 => "CRAW + HEIF Small1 Fine",
+    CRawHeifSmall1Normal = 0x00630e82 => "CRAW + HEIF Small1 Normal",
+    CRawHeifSmall2Fine = 0x00630f83 => "CRAW + HEIF Small2 Fine",
+}
+
+#[cfg(feature = "simulated")]
+pub use crate::simulated::*;
+
+#[cfg(not(feature = "simulated"))]
 #[cfg_attr(target_os = "macos", link(name = "EDSDK", kind = "framework"))]
 #[cfg_attr(target_os = "windows", link(name = "EDSDK"))]
+#[cfg_attr(target_os = "linux", link(name = "EDSDK"))]
 extern "C" {
-    pub fn EdsInitializeSDK() -> EdsError;
-    pub fn EdsTerminateSDK() -> EdsError;
+    pub fn EdsInitializeSDK() -> EdsErrorCode;
+    pub fn EdsTerminateSDK() -> EdsErrorCode;
 
-    pub fn EdsRelease(base_ref: EdsBaseRef) -> EdsError;
+    pub fn EdsRelease(base_ref: EdsBaseRef) -> EdsErrorCode;
 
-    pub fn EdsGetChildCount(base_ref: EdsBaseRef, count: *mut u32) -> EdsError;
+    pub fn EdsGetChildCount(base_ref: EdsBaseRef, count: *mut u32) -> EdsErrorCode;
     pub fn EdsGetChildAtIndex(
         parent_ref: EdsBaseRef,
         index: u32,
         child_ref: *mut EdsBaseRef,
-    ) -> EdsError;
+    ) -> EdsErrorCode;
 
-    pub fn EdsGetCameraList(camera_list: *mut EdsCameraListRef) -> EdsError;
+    pub fn EdsGetCameraList(camera_list: *mut EdsCameraListRef) -> EdsErrorCode;
 
-    pub fn EdsGetDeviceInfo(camera_ref: EdsCameraRef, device_info: *mut EdsDeviceInfo) -> EdsError;
+    pub fn EdsGetDeviceInfo(
+        camera_ref: EdsCameraRef,
+        device_info: *mut EdsDeviceInfo,
+    ) -> EdsErrorCode;
 
-    pub fn EdsOpenSession(camera_ref: EdsCameraRef) -> EdsError;
-    pub fn EdsCloseSession(camera_ref: EdsCameraRef) -> EdsError;
+    pub fn EdsOpenSession(camera_ref: EdsCameraRef) -> EdsErrorCode;
+    pub fn EdsCloseSession(camera_ref: EdsCameraRef) -> EdsErrorCode;
 
+    pub fn EdsGetPropertySize(
+        in_ref: EdsBaseRef,
+        property_id: EdsPropertyId,
+        param: i32,
+        out_data_type: *mut u32,
+        out_size: *mut u32,
+    ) -> EdsErrorCode;
+    pub fn EdsGetPropertyDesc(
+        in_ref: EdsBaseRef,
+        property_id: EdsPropertyId,
+        out_property_desc: *mut EdsPropertyDesc,
+    ) -> EdsErrorCode;
     pub fn EdsGetPropertyData(
         in_ref: EdsBaseRef,
         property_id: EdsPropertyId,
         param: i32,
         size: u32,
         out_data: *mut EdsVoid,
-    ) -> EdsError;
+    ) -> EdsErrorCode;
     pub fn EdsSetPropertyData(
         in_ref: EdsBaseRef,
         property_id: EdsPropertyId,
         param: i32,
         size: u32,
         data: *const EdsVoid,
-    ) -> EdsError;
+    ) -> EdsErrorCode;
 
     pub fn EdsSendCommand(
         camera_ref: EdsCameraRef,
         command: EdsCameraCommand,
         param: i32,
-    ) -> EdsError;
+    ) -> EdsErrorCode;
 
-    pub fn EdsGetEvent() -> EdsError;
+    pub fn EdsGetEvent() -> EdsErrorCode;
     pub fn EdsSetObjectEventHandler(
         camera_ref: EdsCameraRef,
         event: EdsObjectEvent,
-        event_handler: EdsObjectEventHandler,
+        event_handler: Option<EdsObjectEventHandler>,
+        context: *mut EdsVoid,
+    ) -> EdsErrorCode;
+    pub fn EdsSetPropertyEventHandler(
+        camera_ref: EdsCameraRef,
+        event: EdsPropertyEvent,
+        event_handler: Option<EdsPropertyEventHandler>,
         context: *mut EdsVoid,
-    ) -> EdsError;
+    ) -> EdsErrorCode;
+    pub fn EdsSetCameraStateEventHandler(
+        camera_ref: EdsCameraRef,
+        event: EdsStateEvent,
+        event_handler: Option<EdsStateEventHandler>,
+        context: *mut EdsVoid,
+    ) -> EdsErrorCode;
 
+    pub fn EdsGetVolumeInfo(
+        in_volume_ref: EdsVolumeRef,
+        out_volume_info: *mut EdsVolumeInfo,
+    ) -> EdsErrorCode;
+    pub fn EdsFormatVolume(in_volume_ref: EdsVolumeRef) -> EdsErrorCode;
+
+    pub fn EdsDeleteDirectoryItem(in_dir_item_ref: EdsDirectoryItemRef) -> EdsErrorCode;
     pub fn EdsGetDirectoryItemInfo(
         in_dir_item_ref: EdsDirectoryItemRef,
         out_info: *mut EdsDirectoryItemInfo,
-    ) -> EdsError;
+    ) -> EdsErrorCode;
 
     pub fn EdsCreateFileStream(
         in_file_name: *const EdsChar,
         in_create_disposition: EdsFileCreateDisposition,
         in_desired_access: EdsAccess,
         out_stream: *mut EdsStreamRef,
-    ) -> EdsError;
+    ) -> EdsErrorCode;
 
+    pub fn EdsCreateMemoryStream(
+        in_buffer_size: u64,
+        out_stream: *mut EdsStreamRef,
+    ) -> EdsErrorCode;
     pub fn EdsCreateMemoryStreamFromPointer(
         in_user_buffer: *mut EdsVoid,
         in_buffer_size: u64,
         out_stream: *mut EdsStreamRef,
-    ) -> EdsError;
+    ) -> EdsErrorCode;
+
+    pub fn EdsGetPointer(in_stream: EdsStreamRef, out_pointer: *mut *mut EdsVoid) -> EdsErrorCode;
+    pub fn EdsGetLength(in_stream: EdsStreamRef, out_length: *mut u64) -> EdsErrorCode;
 
     pub fn EdsDownload(
         in_dir_item_ref: EdsDirectoryItemRef,
         in_read_size: u64,
         in_stream_ref: EdsStreamRef,
-    ) -> EdsError;
-    pub fn EdsDownloadComplete(in_dir_item_ref: EdsDirectoryItemRef) -> EdsError;
+    ) -> EdsErrorCode;
+    pub fn EdsDownloadComplete(in_dir_item_ref: EdsDirectoryItemRef) -> EdsErrorCode;
+    pub fn EdsDownloadThumbnail(
+        in_dir_item_ref: EdsDirectoryItemRef,
+        in_stream: EdsStreamRef,
+    ) -> EdsErrorCode;
+    pub fn EdsDownloadCancel(in_dir_item_ref: EdsDirectoryItemRef) -> EdsErrorCode;
 
-    pub fn EdsSetCapacity(camera_ref: EdsCameraRef, capacity: EdsCapacity) -> EdsError;
+    pub fn EdsSetCapacity(camera_ref: EdsCameraRef, capacity: EdsCapacity) -> EdsErrorCode;
 
     pub fn EdsCreateEvfImageRef(
         in_stream: EdsStreamRef,
         out_evf_image_ref: *mut EdsEvfImageRef,
-    ) -> EdsError;
+    ) -> EdsErrorCode;
     pub fn EdsDownloadEvfImage(
         in_camera: EdsCameraRef,
         in_evf_image_ref: EdsEvfImageRef,
-    ) -> EdsError;
+    ) -> EdsErrorCode;
+
+    pub fn EdsCreateImageRef(
+        in_stream_ref: EdsStreamRef,
+        out_image_ref: *mut EdsImageRef,
+    ) -> EdsErrorCode;
+    pub fn EdsGetImageInfo(
+        in_image_ref: EdsImageRef,
+        in_image_source: EdsImageSource,
+        out_image_info: *mut EdsImageInfo,
+    ) -> EdsErrorCode;
+    pub fn EdsGetImage(
+        in_image_ref: EdsImageRef,
+        in_image_source: EdsImageSource,
+        in_image_type: EdsTargetImageType,
+        in_src_rect: EdsRect,
+        in_dst_size: EdsSize,
+        out_stream_ref: EdsStreamRef,
+    ) -> EdsErrorCode;
+
+    pub fn EdsSetProgressCallback(
+        in_ref: EdsBaseRef,
+        in_progress_func: Option<EdsProgressCallback>,
+        in_progress_option: EdsProgressOption,
+        in_context: *mut EdsVoid,
+    ) -> EdsErrorCode;
 }
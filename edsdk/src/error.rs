@@ -0,0 +1,370 @@
+use std::fmt;
+
+use crate::EdsDataType;
+
+/// Raw `EdsError` value as returned by the EDSDK.
+///
+/// The SDK may return any `u32`, so FFI functions return this transparent wrapper instead of
+/// [`EdsError`] directly. Use [`EdsErrorCode::res`] or [`EdsError::from`] to inspect it.
+#[repr(transparent)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct EdsErrorCode(pub u32);
+
+impl EdsErrorCode {
+    pub const OK: Self = Self(0);
+
+    pub fn is_ok(self) -> bool {
+        self == Self::OK
+    }
+
+    pub fn res(self) -> Result<(), EdsError> {
+        EdsError::from(self).res()
+    }
+}
+
+impl From<EdsError> for EdsErrorCode {
+    fn from(value: EdsError) -> Self {
+        Self(value.code())
+    }
+}
+
+impl From<EdsErrorCode> for EdsError {
+    fn from(value: EdsErrorCode) -> Self {
+        Self::from_code(value.0)
+    }
+}
+
+/// Broad groups of EDSDK errors, following the sections of `EDSDKErrors.h`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EdsErrorCategory {
+    Ok,
+    Miscellaneous,
+    File,
+    Directory,
+    Property,
+    Parameter,
+    Device,
+    Stream,
+    Communication,
+    UsbLock,
+    Sti,
+    Ptp,
+    PtpVendor,
+    TakePicture,
+    Unknown,
+}
+
+macro_rules! eds_errors {
+    ($($name:ident = $code:literal => $description:literal,)*) => {
+        /// Every error code defined by the EDSDK, plus a few errors used by this crate only.
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+        pub enum EdsError {
+            $($name,)*
+
+            /// This error doesn't exist on the EDSDK but is used to indicate that no camera was found.
+            NoCameraFound,
+
+            /// This error doesn't exist on the EDSDK but is used to indicate that a property holds
+            /// a different data type than the one it was read or written as.
+            PropertyTypeMismatch(EdsDataType),
+
+            /// This error doesn't exist on the EDSDK but is used to indicate that a property holds
+            /// a different amount of data than the one it was read or written as.
+            PropertySizeMismatch { expected: u32, actual: u32 },
+
+            /// A code that isn't known to these bindings.
+            Unknown(u32),
+        }
+
+        impl EdsError {
+            pub fn from_code(code: u32) -> Self {
+                match code {
+                    $($code => Self::$name,)*
+                    code => Self::Unknown(code),
+                }
+            }
+
+            pub fn code(&self) -> u32 {
+                match self {
+                    $(Self::$name => $code,)*
+                    Self::NoCameraFound => Self::DeviceNotFound.code(),
+                    Self::PropertyTypeMismatch(_) | Self::PropertySizeMismatch { .. } => {
+                        Self::PropertiesMismatch.code()
+                    }
+                    Self::Unknown(code) => *code,
+                }
+            }
+
+            pub fn description(&self) -> &'static str {
+                match self {
+                    $(Self::$name => $description,)*
+                    Self::NoCameraFound => "no camera found",
+                    Self::PropertyTypeMismatch(_) => "property data type mismatch",
+                    Self::PropertySizeMismatch { .. } => "property data size mismatch",
+                    Self::Unknown(_) => "unknown error",
+                }
+            }
+        }
+    };
+}
+
+eds_errors! {
+    Ok = 0x00000000 => "ok",
+
+    // Miscellaneous errors
+    Unimplemented = 0x00000001 => "unimplemented",
+    InternalError = 0x00000002 => "internal error",
+    MemAllocFailed = 0x00000003 => "memory allocation failed",
+    MemFreeFailed = 0x00000004 => "memory release failed",
+    OperationCancelled = 0x00000005 => "operation cancelled",
+    IncompatibleVersion = 0x00000006 => "incompatible version",
+    NotSupported = 0x00000007 => "not supported",
+    UnexpectedException = 0x00000008 => "unexpected exception",
+    ProtectionViolation = 0x00000009 => "protection violation",
+    MissingSubcomponent = 0x0000000A => "missing subcomponent",
+    SelectionUnavailable = 0x0000000B => "selection unavailable",
+
+    // File errors
+    FileIoError = 0x00000020 => "file I/O error",
+    FileTooManyOpen = 0x00000021 => "too many open files",
+    FileNotFound = 0x00000022 => "file not found",
+    FileOpenError = 0x00000023 => "file open error",
+    FileCloseError = 0x00000024 => "file close error",
+    FileSeekError = 0x00000025 => "file seek error",
+    FileTellError = 0x00000026 => "file tell error",
+    FileReadError = 0x00000027 => "file read error",
+    FileWriteError = 0x00000028 => "file write error",
+    FilePermissionError = 0x00000029 => "file permission error",
+    FileDiskFullError = 0x0000002A => "disk full",
+    FileAlreadyExists = 0x0000002B => "file already exists",
+    FileFormatUnrecognized = 0x0000002C => "file format unrecognized",
+    FileDataCorrupt = 0x0000002D => "file data corrupt",
+    FileNamingNa = 0x0000002E => "file naming not available",
+
+    // Directory errors
+    DirNotFound = 0x00000040 => "directory not found",
+    DirIoError = 0x00000041 => "directory I/O error",
+    DirEntryNotFound = 0x00000042 => "directory entry not found",
+    DirEntryExists = 0x00000043 => "directory entry exists",
+    DirNotEmpty = 0x00000044 => "directory not empty",
+
+    // Property errors
+    PropertiesUnavailable = 0x00000050 => "properties unavailable",
+    PropertiesMismatch = 0x00000051 => "properties mismatch",
+    PropertiesNotLoaded = 0x00000053 => "properties not loaded",
+
+    // Function parameter errors
+    InvalidParameter = 0x00000060 => "invalid parameter",
+    InvalidHandle = 0x00000061 => "invalid handle",
+    InvalidPointer = 0x00000062 => "invalid pointer",
+    InvalidIndex = 0x00000063 => "invalid index",
+    InvalidLength = 0x00000064 => "invalid length",
+    InvalidFnPointer = 0x00000065 => "invalid function pointer",
+    InvalidSortFn = 0x00000066 => "invalid sort function",
+
+    // Device errors
+    DeviceNotFound = 0x00000080 => "device not found",
+    DeviceBusy = 0x00000081 => "device busy",
+    DeviceInvalid = 0x00000082 => "device invalid",
+    DeviceEmergency = 0x00000083 => "device emergency",
+    DeviceMemoryFull = 0x00000084 => "device memory full",
+    DeviceInternalError = 0x00000085 => "device internal error",
+    DeviceInvalidParameter = 0x00000086 => "device invalid parameter",
+    DeviceNoDisk = 0x00000087 => "no disk in device",
+    DeviceDiskError = 0x00000088 => "device disk error",
+    DeviceCfGateChanged = 0x00000089 => "card slot cover opened",
+    DeviceDialChanged = 0x0000008A => "mode dial changed",
+    DeviceNotInstalled = 0x0000008B => "device not installed",
+    DeviceStayAwake = 0x0000008C => "device connected in awake mode",
+    DeviceNotReleased = 0x0000008D => "device not released",
+
+    // Stream errors
+    StreamIoError = 0x000000A0 => "stream I/O error",
+    StreamNotOpen = 0x000000A1 => "stream not open",
+    StreamAlreadyOpen = 0x000000A2 => "stream already open",
+    StreamOpenError = 0x000000A3 => "stream open error",
+    StreamCloseError = 0x000000A4 => "stream close error",
+    StreamSeekError = 0x000000A5 => "stream seek error",
+    StreamTellError = 0x000000A6 => "stream tell error",
+    StreamReadError = 0x000000A7 => "stream read error",
+    StreamWriteError = 0x000000A8 => "stream write error",
+    StreamPermissionError = 0x000000A9 => "stream permission error",
+    StreamCouldntBeginThread = 0x000000AA => "stream could not begin thread",
+    StreamBadOptions = 0x000000AB => "bad stream options",
+    StreamEndOfStream = 0x000000AC => "end of stream",
+
+    // Communications errors
+    CommPortIsInUse = 0x000000C0 => "port in use",
+    CommDisconnected = 0x000000C1 => "disconnected",
+    CommDeviceIncompatible = 0x000000C2 => "device incompatible",
+    CommBufferFull = 0x000000C3 => "buffer full",
+    CommUsbBusErr = 0x000000C4 => "USB bus error",
+
+    // Lock/Unlock errors
+    UsbDeviceLockError = 0x000000D0 => "failed to lock the UI",
+    UsbDeviceUnlockError = 0x000000D1 => "failed to unlock the UI",
+
+    // STI/WIA errors
+    StiUnknownError = 0x000000E0 => "unknown STI error",
+    StiInternalError = 0x000000E1 => "internal STI error",
+    StiDeviceCreateError = 0x000000E2 => "STI device create error",
+    StiDeviceReleaseError = 0x000000E3 => "STI device release error",
+    DeviceNotLaunched = 0x000000E4 => "device startup failed",
+
+    EnumNa = 0x000000F0 => "enumeration terminated",
+    InvalidFnCall = 0x000000F1 => "called in a mode when the function could not be used",
+    HandleNotFound = 0x000000F2 => "handle not found",
+    InvalidId = 0x000000F3 => "invalid id",
+    WaitTimeoutError = 0x000000F4 => "timeout",
+
+    // PTP errors
+    SessionNotOpen = 0x00002003 => "session not open",
+    InvalidTransactionId = 0x00002004 => "invalid transaction id",
+    IncompleteTransfer = 0x00002007 => "incomplete transfer",
+    InvalidStorageId = 0x00002008 => "invalid storage id",
+    DevicePropNotSupported = 0x0000200A => "device property not supported",
+    InvalidObjectFormatCode = 0x0000200B => "invalid object format code",
+    SelfTestFailed = 0x00002011 => "self test failed",
+    PartialDeletion = 0x00002012 => "partial deletion",
+    SpecificationByFormatUnsupported = 0x00002014 => "specification by format unsupported",
+    NoValidObjectInfo = 0x00002015 => "no valid object info",
+    InvalidCodeFormat = 0x00002016 => "invalid code format",
+    UnknownVendorCode = 0x00002017 => "unknown vendor code",
+    CaptureAlreadyTerminated = 0x00002018 => "capture already terminated",
+    PtpDeviceBusy = 0x00002019 => "PTP device busy",
+    InvalidParentObject = 0x0000201A => "invalid parent object",
+    InvalidDevicePropFormat = 0x0000201B => "invalid device property format",
+    InvalidDevicePropValue = 0x0000201C => "invalid device property value",
+    SessionAlreadyOpen = 0x0000201E => "session already open",
+    TransactionCancelled = 0x0000201F => "transaction cancelled",
+    SpecificationOfDestinationUnsupported = 0x00002020 => "specification of destination unsupported",
+    NotCameraSupportSdkVersion = 0x00002021 => "camera does not support this SDK version",
+
+    // PTP vendor errors
+    UnknownCommand = 0x0000A001 => "unknown command",
+    OperationRefused = 0x0000A005 => "operation refused",
+    LensCoverClose = 0x0000A006 => "lens cover closed",
+    LowBattery = 0x0000A101 => "low battery",
+    ObjectNotReady = 0x0000A102 => "object not ready",
+    CannotMakeObject = 0x0000A104 => "cannot make object",
+    MemoryStatusNotReady = 0x0000A106 => "memory status not ready",
+
+    // Take picture errors
+    TakePictureAfNg = 0x00008D01 => "focus failed",
+    TakePictureReserved = 0x00008D02 => "reserved",
+    TakePictureMirrorUpNg = 0x00008D03 => "currently configuring mirror up",
+    TakePictureSensorCleaningNg = 0x00008D04 => "currently cleaning sensor",
+    TakePictureSilenceNg = 0x00008D05 => "currently performing silent operations",
+    TakePictureNoCardNg = 0x00008D06 => "card not installed",
+    TakePictureCardNg = 0x00008D07 => "error writing to card",
+    TakePictureCardProtectNg = 0x00008D08 => "card write protected",
+    TakePictureMovieCropNg = 0x00008D09 => "movie crop",
+    TakePictureStroboChargeNg = 0x00008D0A => "flash charging",
+    TakePictureNoLensNg = 0x00008D0B => "no lens attached",
+    TakePictureSpecialMovieModeNg = 0x00008D0C => "special movie mode",
+    TakePictureLvRelProhibitModeNg = 0x00008D0D => "live view release prohibited",
+    TakePictureMovieModeNg = 0x00008D0E => "movie mode",
+    TakePictureRetractedLensNg = 0x00008D0F => "lens retracted",
+}
+
+impl EdsError {
+    pub fn is_ok(&self) -> bool {
+        *self == EdsError::Ok
+    }
+
+    pub fn res(self) -> Result<(), EdsError> {
+        if self.is_ok() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+
+    pub fn category(&self) -> EdsErrorCategory {
+        match self {
+            Self::Ok => EdsErrorCategory::Ok,
+            Self::NoCameraFound => EdsErrorCategory::Device,
+            _ => match self.code() {
+                0x01..=0x1F => EdsErrorCategory::Miscellaneous,
+                0x20..=0x3F => EdsErrorCategory::File,
+                0x40..=0x4F => EdsErrorCategory::Directory,
+                0x50..=0x5F => EdsErrorCategory::Property,
+                0x60..=0x7F => EdsErrorCategory::Parameter,
+                0x80..=0x9F => EdsErrorCategory::Device,
+                0xA0..=0xBF => EdsErrorCategory::Stream,
+                0xC0..=0xCF => EdsErrorCategory::Communication,
+                0xD0..=0xDF => EdsErrorCategory::UsbLock,
+                0xE0..=0xEF => EdsErrorCategory::Sti,
+                0xF0..=0xFF => EdsErrorCategory::Miscellaneous,
+                0x2000..=0x2FFF => EdsErrorCategory::Ptp,
+                0x8D00..=0x8DFF => EdsErrorCategory::TakePicture,
+                0xA000..=0xAFFF => EdsErrorCategory::PtpVendor,
+                _ => EdsErrorCategory::Unknown,
+            },
+        }
+    }
+
+    /// The camera is busy with something else, e.g. still writing the last shot.
+    pub fn is_device_busy(&self) -> bool {
+        matches!(self, Self::DeviceBusy | Self::PtpDeviceBusy)
+    }
+
+    /// The camera is gone, either because it was unplugged, switched off or the session was lost.
+    pub fn is_disconnected(&self) -> bool {
+        matches!(
+            self,
+            Self::CommDisconnected
+                | Self::CommUsbBusErr
+                | Self::DeviceNotFound
+                | Self::DeviceInvalid
+                | Self::SessionNotOpen
+                | Self::NoCameraFound
+        )
+    }
+
+    /// The shutter release was refused by the camera.
+    pub fn is_take_picture_error(&self) -> bool {
+        self.category() == EdsErrorCategory::TakePicture
+    }
+
+    /// The camera is only busy for a moment, e.g. still writing a photo or charging the flash, so
+    /// the same call is likely to succeed if it is simply tried again a moment later. A failed
+    /// autofocus isn't, nothing changes in front of the camera until somebody moves.
+    pub fn is_retryable(&self) -> bool {
+        self.is_device_busy()
+            || matches!(
+                self,
+                Self::ObjectNotReady
+                    | Self::MemoryStatusNotReady
+                    | Self::TakePictureStroboChargeNg
+                    | Self::TakePictureSensorCleaningNg
+            )
+    }
+}
+
+impl From<EdsError> for Result<(), EdsError> {
+    fn from(value: EdsError) -> Self {
+        value.res()
+    }
+}
+
+impl fmt::Display for EdsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoCameraFound => f.write_str(self.description()),
+            Self::PropertyTypeMismatch(actual) => {
+                write!(f, "{} (property is {:?})", self.description(), actual)
+            }
+            Self::PropertySizeMismatch { expected, actual } => write!(
+                f,
+                "{} (expected {} bytes, property has {} bytes)",
+                self.description(),
+                expected,
+                actual
+            ),
+            _ => write!(f, "{} (0x{:08X})", self.description(), self.code()),
+        }
+    }
+}
+
+impl std::error::Error for EdsError {}
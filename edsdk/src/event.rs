@@ -0,0 +1,181 @@
+use crate::{
+    EdsCameraRef, EdsError, EdsObjectEvent, EdsObjectEventHandler, EdsPropertyEvent,
+    EdsPropertyEventHandler, EdsPropertyId, EdsSetCameraStateEventHandler,
+    EdsSetObjectEventHandler, EdsSetPropertyEventHandler, EdsStateEvent, EdsStateEventHandler,
+    EdsVoid, PropertyId,
+};
+
+/// Registers a handler for every property event (`kEdsPropertyEvent_All`).
+pub const PROPERTY_EVENT_ALL: EdsPropertyEvent = 0x100;
+
+/// Registers a handler for every object event (`kEdsObjectEvent_All`).
+pub const OBJECT_EVENT_ALL: EdsObjectEvent = 0x200;
+
+/// Registers a handler for every camera state event (`kEdsStateEvent_All`).
+pub const STATE_EVENT_ALL: EdsStateEvent = 0x300;
+
+/// A property event as passed to an [`EdsPropertyEventHandler`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PropertyEvent {
+    /// The value of a property changed.
+    PropertyChanged { property: PropertyId, param: u32 },
+
+    /// The list of values a property can be set to changed.
+    PropertyDescChanged { property: PropertyId, param: u32 },
+
+    Other {
+        event: EdsPropertyEvent,
+        property: PropertyId,
+        param: u32,
+    },
+}
+
+impl PropertyEvent {
+    pub fn from_raw(event: EdsPropertyEvent, property: EdsPropertyId, param: u32) -> Self {
+        let property = PropertyId::from(property);
+        match event {
+            0x101 => Self::PropertyChanged { property, param },
+            0x102 => Self::PropertyDescChanged { property, param },
+            event => Self::Other {
+                event,
+                property,
+                param,
+            },
+        }
+    }
+}
+
+/// The kind of an object event as passed to an [`EdsObjectEventHandler`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ObjectEvent {
+    VolumeInfoChanged,
+    VolumeUpdateItems,
+    FolderUpdateItems,
+    DirItemCreated,
+    DirItemRemoved,
+    DirItemInfoChanged,
+    DirItemContentChanged,
+
+    /// A picture was taken and is waiting to be downloaded to the host.
+    DirItemRequestTransfer,
+    DirItemRequestTransferDt,
+    DirItemCancelTransferDt,
+    VolumeAdded,
+    VolumeRemoved,
+    Other(EdsObjectEvent),
+}
+
+impl From<EdsObjectEvent> for ObjectEvent {
+    fn from(value: EdsObjectEvent) -> Self {
+        match value {
+            0x201 => Self::VolumeInfoChanged,
+            0x202 => Self::VolumeUpdateItems,
+            0x203 => Self::FolderUpdateItems,
+            0x204 => Self::DirItemCreated,
+            0x205 => Self::DirItemRemoved,
+            0x206 => Self::DirItemInfoChanged,
+            0x207 => Self::DirItemContentChanged,
+            0x208 => Self::DirItemRequestTransfer,
+            0x209 => Self::DirItemRequestTransferDt,
+            0x20a => Self::DirItemCancelTransferDt,
+            0x20c => Self::VolumeAdded,
+            0x20d => Self::VolumeRemoved,
+            other => Self::Other(other),
+        }
+    }
+}
+
+/// A camera state event as passed to an [`EdsStateEventHandler`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StateEvent {
+    /// The camera was disconnected or switched off. The session is no longer usable.
+    Shutdown,
+
+    /// The camera started (`busy == true`) or finished processing a job.
+    JobStatusChanged {
+        busy: bool,
+    },
+
+    /// The camera is about to shut down because of its auto power-off setting.
+    /// Sending `ExtendShutDownTimer` keeps it awake.
+    WillSoonShutDown {
+        seconds: u32,
+    },
+
+    ShutDownTimerUpdate,
+
+    /// Taking a picture failed after the shutter was released.
+    CaptureError(EdsError),
+
+    InternalError,
+
+    AfResult(u32),
+
+    /// Seconds elapsed since a bulb exposure started.
+    BulbExposureTime {
+        seconds: u32,
+    },
+
+    PowerZoomInfoChanged,
+
+    Other {
+        event: EdsStateEvent,
+        data: u32,
+    },
+}
+
+impl StateEvent {
+    pub fn from_raw(event: EdsStateEvent, data: u32) -> Self {
+        match event {
+            0x301 => Self::Shutdown,
+            0x302 => Self::JobStatusChanged { busy: data != 0 },
+            0x303 => Self::WillSoonShutDown { seconds: data },
+            0x304 => Self::ShutDownTimerUpdate,
+            0x305 => Self::CaptureError(EdsError::from_code(data)),
+            0x306 => Self::InternalError,
+            0x309 => Self::AfResult(data),
+            0x310 => Self::BulbExposureTime { seconds: data },
+            0x311 => Self::PowerZoomInfoChanged,
+            event => Self::Other { event, data },
+        }
+    }
+}
+
+/// Registers `handler` for all object events of `camera`, `None` unregisters the current handler.
+///
+/// # Safety
+/// `camera` must be a valid camera reference and `context` must stay valid for as long as the
+/// handler is registered.
+pub unsafe fn set_object_event_handler(
+    camera: EdsCameraRef,
+    handler: Option<EdsObjectEventHandler>,
+    context: *mut EdsVoid,
+) -> Result<(), EdsError> {
+    unsafe { EdsSetObjectEventHandler(camera, OBJECT_EVENT_ALL, handler, context).res() }
+}
+
+/// Registers `handler` for all property events of `camera`, `None` unregisters the current handler.
+///
+/// # Safety
+/// `camera` must be a valid camera reference and `context` must stay valid for as long as the
+/// handler is registered.
+pub unsafe fn set_property_event_handler(
+    camera: EdsCameraRef,
+    handler: Option<EdsPropertyEventHandler>,
+    context: *mut EdsVoid,
+) -> Result<(), EdsError> {
+    unsafe { EdsSetPropertyEventHandler(camera, PROPERTY_EVENT_ALL, handler, context).res() }
+}
+
+/// Registers `handler` for all camera state events of `camera`, `None` unregisters the current handler.
+///
+/// # Safety
+/// `camera` must be a valid camera reference and `context` must stay valid for as long as the
+/// handler is registered.
+pub unsafe fn set_camera_state_event_handler(
+    camera: EdsCameraRef,
+    handler: Option<EdsStateEventHandler>,
+    context: *mut EdsVoid,
+) -> Result<(), EdsError> {
+    unsafe { EdsSetCameraStateEventHandler(camera, STATE_EVENT_ALL, handler, context).res() }
+}
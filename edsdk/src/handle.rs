@@ -0,0 +1,167 @@
+use std::{
+    marker::PhantomData,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use crate::{
+    Camera, CameraDescription, EdsBaseRef, EdsCameraListRef, EdsError, EdsGetCameraList,
+    EdsGetChildAtIndex, EdsGetChildCount, EdsGetEvent, EdsInitializeSDK, EdsRelease,
+    EdsTerminateSDK,
+};
+
+/// An owned reference to an EDSDK object, released when dropped.
+pub(crate) struct OwnedRef(EdsBaseRef);
+
+impl OwnedRef {
+    /// # Safety
+    /// `raw` must be null or a valid reference that the caller owns.
+    pub(crate) unsafe fn from_raw(raw: EdsBaseRef) -> Self {
+        Self(raw)
+    }
+
+    pub(crate) fn as_raw(&self) -> EdsBaseRef {
+        self.0
+    }
+}
+
+impl Drop for OwnedRef {
+    fn drop(&mut self) {
+        if !self.0.is_null() {
+            unsafe { EdsRelease(self.0) };
+        }
+    }
+}
+
+/// An initialized EDSDK. Every object handed out by the SDK borrows from it, so it can't outlive
+/// the SDK. The SDK is terminated when this is dropped.
+///
+/// Only a single `Sdk` can exist at a time, as terminating one would pull the SDK out from under
+/// the objects of the others.
+pub struct Sdk {
+    _private: (),
+}
+
+/// Whether an [`Sdk`] exists
+static INITIALIZED: AtomicBool = AtomicBool::new(false);
+
+impl Sdk {
+    /// Initializes the SDK. Fails with [`EdsError::InternalError`] while another `Sdk` exists.
+    pub fn init() -> Result<Self, EdsError> {
+        if INITIALIZED
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
+            return Err(EdsError::InternalError);
+        }
+
+        if let Err(err) = unsafe { EdsInitializeSDK().res() } {
+            INITIALIZED.store(false, Ordering::Release);
+            return Err(err);
+        }
+        Ok(Self { _private: () })
+    }
+
+    /// Dispatches pending events to the registered handlers.
+    ///
+    /// On macOS and Windows events are only delivered when this is called regularly.
+    pub fn get_event(&self) -> Result<(), EdsError> {
+        unsafe { EdsGetEvent().res() }
+    }
+
+    /// Returns the list of currently connected cameras.
+    pub fn camera_list(&self) -> Result<CameraList<'_>, EdsError> {
+        let mut camera_list = std::ptr::null_mut() as EdsCameraListRef;
+        unsafe { EdsGetCameraList(&mut camera_list).res() }?;
+        Ok(CameraList {
+            raw: unsafe { OwnedRef::from_raw(camera_list) },
+            _sdk: PhantomData,
+        })
+    }
+}
+
+impl Drop for Sdk {
+    fn drop(&mut self) {
+        unsafe { EdsTerminateSDK() };
+        INITIALIZED.store(false, Ordering::Release);
+    }
+}
+
+/// The cameras connected when [`Sdk::camera_list`] was called.
+pub struct CameraList<'sdk> {
+    raw: OwnedRef,
+    _sdk: PhantomData<&'sdk Sdk>,
+}
+
+impl<'sdk> CameraList<'sdk> {
+    pub fn len(&self) -> Result<u32, EdsError> {
+        let mut count = 0;
+        unsafe { EdsGetChildCount(self.raw.as_raw(), &mut count).res() }?;
+        Ok(count)
+    }
+
+    pub fn is_empty(&self) -> Result<bool, EdsError> {
+        Ok(self.len()? == 0)
+    }
+
+    /// Returns the camera that enumerated first, or [`EdsError::NoCameraFound`] if there is none.
+    pub fn first(&self) -> Result<Camera<'sdk>, EdsError> {
+        if self.is_empty()? {
+            return Err(EdsError::NoCameraFound);
+        }
+        self.get(0)
+    }
+
+    pub fn get(&self, index: u32) -> Result<Camera<'sdk>, EdsError> {
+        let mut camera = std::ptr::null_mut() as EdsBaseRef;
+        unsafe { EdsGetChildAtIndex(self.raw.as_raw(), index, &mut camera).res() }?;
+        Ok(unsafe { Camera::from_raw(camera) })
+    }
+
+    pub fn iter(
+        &self,
+    ) -> Result<impl Iterator<Item = Result<Camera<'sdk>, EdsError>> + '_, EdsError> {
+        Ok((0..self.len()?).map(|index| self.get(index)))
+    }
+
+    /// Describes every camera, in the order of [`Self::get`].
+    pub fn describe(&self) -> Result<Vec<CameraDescription>, EdsError> {
+        Ok(self
+            .describe_cameras()?
+            .into_iter()
+            .map(|(_, description)| description)
+            .collect())
+    }
+
+    /// Like [`Self::describe`], along with the cameras, so one can be picked by its description
+    /// without describing it again.
+    pub fn describe_cameras(&self) -> Result<Vec<(Camera<'sdk>, CameraDescription)>, EdsError> {
+        self.iter()?
+            .map(|camera| {
+                let mut camera = camera?;
+                let description = camera.describe()?;
+                Ok((camera, description))
+            })
+            .collect()
+    }
+
+    /// Returns the camera whose body ID is `body_id`, see [`CameraDescription::body_id`]. Only the
+    /// body ID of each camera is read, the session opened for it stays open on the camera found.
+    pub fn find_by_body_id(&self, body_id: &str) -> Result<Camera<'sdk>, EdsError> {
+        if self.is_empty()? {
+            return Err(EdsError::NoCameraFound);
+        }
+
+        for camera in self.iter()? {
+            let mut camera = camera?;
+            // e.g. another application has a session with it, so it can't be ours anyway
+            if camera
+                .body_id()
+                .is_ok_and(|candidate| candidate.trim() == body_id.trim())
+            {
+                return Ok(camera);
+            }
+        }
+
+        Err(EdsError::DeviceNotFound)
+    }
+}
@@ -0,0 +1,120 @@
+use std::marker::PhantomData;
+
+use crate::{
+    handle::OwnedRef, EdsCreateImageRef, EdsError, EdsGetImage, EdsGetImageInfo, EdsImageInfo,
+    EdsImageRef, EdsImageSource, EdsPoint, EdsRect, EdsSize, EdsTargetImageType, Sdk, Stream,
+};
+
+/// An image file read by the SDK, e.g. a downloaded picture or the stream filled by
+/// [`crate::DirectoryItem::download_thumbnail`]. The SDK decodes RAW files too, so this yields
+/// displayable pixels without a separate decoder.
+pub struct Image<'stream> {
+    raw: OwnedRef,
+    _stream: PhantomData<&'stream Stream<'stream>>,
+}
+
+/// 8 bit RGB pixels, row by row from the top.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RgbImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl<'stream> Image<'stream> {
+    pub fn new(stream: &'stream Stream<'_>) -> Result<Self, EdsError> {
+        let mut image = std::ptr::null_mut() as EdsImageRef;
+        unsafe { EdsCreateImageRef(stream.as_raw(), &mut image).res() }?;
+
+        Ok(Self {
+            raw: unsafe { OwnedRef::from_raw(image) },
+            _stream: PhantomData,
+        })
+    }
+
+    pub fn as_raw(&self) -> EdsImageRef {
+        self.raw.as_raw()
+    }
+
+    pub fn info(&self, source: EdsImageSource) -> Result<EdsImageInfo, EdsError> {
+        let mut info = EdsImageInfo::default();
+        unsafe { EdsGetImageInfo(self.as_raw(), source, &mut info).res() }?;
+        Ok(info)
+    }
+
+    /// Decodes `rect` of `source`, scaled to `size`.
+    pub fn rgb(
+        &self,
+        sdk: &Sdk,
+        source: EdsImageSource,
+        rect: EdsRect,
+        size: EdsSize,
+    ) -> Result<RgbImage, EdsError> {
+        let stream = Stream::create_memory(sdk, 0)?;
+        unsafe {
+            EdsGetImage(
+                self.as_raw(),
+                source,
+                EdsTargetImageType::Rgb,
+                rect,
+                size,
+                stream.as_raw(),
+            )
+            .res()
+        }?;
+
+        let width = size.width.max(0) as u32;
+        let height = size.height.max(0) as u32;
+        let mut pixels = stream.to_vec()?;
+
+        let length = width as usize * height as usize * 3;
+        if pixels.len() < length {
+            return Err(EdsError::StreamEndOfStream);
+        }
+        pixels.truncate(length);
+
+        Ok(RgbImage {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    /// Decodes the picture in `source`, scaled down to fit into `max_size` x `max_size` while
+    /// keeping its aspect ratio. Letterboxing around thumbnails is cut off.
+    pub fn scaled_rgb(
+        &self,
+        sdk: &Sdk,
+        source: EdsImageSource,
+        max_size: u32,
+    ) -> Result<RgbImage, EdsError> {
+        let info = self.info(source)?;
+
+        let rect = if info.effective_rect.size.width > 0 && info.effective_rect.size.height > 0 {
+            info.effective_rect
+        } else {
+            EdsRect {
+                point: EdsPoint::default(),
+                size: EdsSize {
+                    width: info.width as i32,
+                    height: info.height as i32,
+                },
+            }
+        };
+
+        let longest_side = rect.size.width.max(rect.size.height).max(1) as u64;
+        let scale = |length: i32| {
+            if longest_side <= max_size as u64 {
+                length
+            } else {
+                (length as u64 * max_size as u64 / longest_side).max(1) as i32
+            }
+        };
+        let size = EdsSize {
+            width: scale(rect.size.width),
+            height: scale(rect.size.height),
+        };
+
+        self.rgb(sdk, source, rect, size)
+    }
+}
@@ -1,2 +1,43 @@
 mod edsdk;
 pub use edsdk::*;
+
+mod error;
+pub use error::*;
+
+mod property;
+pub use property::*;
+
+mod event;
+pub use event::*;
+
+mod command;
+pub use command::*;
+
+mod callback;
+pub use callback::{
+    HandlerId, ObjectEventHandler, ProgressHandler, PropertyEventHandler, StateEventHandler,
+};
+
+mod handle;
+pub use handle::{CameraList, Sdk};
+
+mod camera;
+pub use camera::*;
+
+mod stream;
+pub use stream::*;
+
+mod directory;
+pub use directory::{DirectoryEntry, DirectoryItem};
+
+mod volume;
+pub use volume::*;
+
+mod values;
+pub use values::*;
+
+mod image;
+pub use image::*;
+
+#[cfg(feature = "simulated")]
+mod simulated;
@@ -0,0 +1,517 @@
+use std::{ffi::CStr, mem::size_of};
+
+use crate::{
+    EdsBaseRef, EdsDataType, EdsError, EdsEvfOutputDevice, EdsFocusPoint, EdsGetPropertyData,
+    EdsGetPropertySize, EdsImageQuality, EdsPoint, EdsPropertyId, EdsRational, EdsRect, EdsSaveTo,
+    EdsSetPropertyData, EdsSize, EdsTime, EdsVoid,
+};
+
+macro_rules! property_ids {
+    ($($name:ident = $code:literal,)*) => {
+        /// Property IDs known to the EDSDK (`kEdsPropID_*`).
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+        pub enum PropertyId {
+            $($name,)*
+
+            /// A property ID that isn't known to these bindings.
+            Other(EdsPropertyId),
+        }
+
+        impl PropertyId {
+            /// Every property ID known to these bindings.
+            pub const ALL: &'static [PropertyId] = &[$(Self::$name,)*];
+
+            pub fn code(&self) -> EdsPropertyId {
+                match self {
+                    $(Self::$name => $code,)*
+                    Self::Other(code) => *code,
+                }
+            }
+        }
+
+        impl std::str::FromStr for PropertyId {
+            type Err = EdsError;
+
+            /// Parses a variant name like `IsoSpeed` or `ImageQuality`.
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s {
+                    $(stringify!($name) => Ok(Self::$name),)*
+                    _ => Err(EdsError::InvalidParameter),
+                }
+            }
+        }
+
+        impl From<EdsPropertyId> for PropertyId {
+            fn from(value: EdsPropertyId) -> Self {
+                match value {
+                    $($code => Self::$name,)*
+                    other => Self::Other(other),
+                }
+            }
+        }
+    };
+}
+
+property_ids! {
+    Unknown = 0x0000ffff,
+
+    // Camera setting properties
+    ProductName = 0x00000002,
+    OwnerName = 0x00000004,
+    MakerName = 0x00000005,
+    DateTime = 0x00000006,
+    FirmwareVersion = 0x00000007,
+    BatteryLevel = 0x00000008,
+    SaveTo = 0x0000000b,
+    CurrentStorage = 0x0000000c,
+    CurrentFolder = 0x0000000d,
+    BatteryQuality = 0x00000010,
+    BodyIdEx = 0x00000015,
+    HdDirectoryStructure = 0x00000020,
+    TempStatus = 0x01000415,
+    AutoPowerOffSetting = 0x0100045e,
+
+    // Image properties
+    ImageQuality = 0x00000100,
+    Orientation = 0x00000102,
+    IccProfile = 0x00000103,
+    FocusInfo = 0x00000104,
+    WhiteBalance = 0x00000106,
+    ColorTemperature = 0x00000107,
+    WhiteBalanceShift = 0x00000108,
+    ColorSpace = 0x0000010d,
+    PictureStyle = 0x00000114,
+    PictureStyleDesc = 0x00000115,
+    PictureStyleCaption = 0x00000200,
+
+    // Capture properties
+    AeMode = 0x00000400,
+    DriveMode = 0x00000401,
+    IsoSpeed = 0x00000402,
+    MeteringMode = 0x00000403,
+    AfMode = 0x00000404,
+    Av = 0x00000405,
+    Tv = 0x00000406,
+    ExposureCompensation = 0x00000407,
+    FocalLength = 0x00000409,
+    AvailableShots = 0x0000040a,
+    Bracket = 0x0000040b,
+    WhiteBalanceBracket = 0x0000040c,
+    LensName = 0x0000040d,
+    AeBracket = 0x0000040e,
+    FeBracket = 0x0000040f,
+    IsoBracket = 0x00000410,
+    NoiseReduction = 0x00000411,
+    FlashOn = 0x00000412,
+    RedEye = 0x00000413,
+    FlashMode = 0x00000414,
+    LensStatus = 0x00000416,
+    Artist = 0x00000418,
+    Copyright = 0x00000419,
+    AeModeSelect = 0x00000436,
+
+    // EVF properties
+    EvfOutputDevice = 0x00000500,
+    EvfMode = 0x00000501,
+    EvfWhiteBalance = 0x00000502,
+    EvfColorTemperature = 0x00000503,
+    EvfDepthOfFieldPreview = 0x00000504,
+    EvfZoom = 0x00000507,
+    EvfZoomPosition = 0x00000508,
+    EvfHistogram = 0x0000050a,
+    EvfImagePosition = 0x0000050b,
+    EvfHistogramStatus = 0x0000050c,
+    EvfAfMode = 0x0000050e,
+    Record = 0x00000510,
+    EvfHistogramY = 0x00000515,
+    EvfHistogramR = 0x00000516,
+    EvfHistogramG = 0x00000517,
+    EvfHistogramB = 0x00000518,
+    EvfCoordinateSystem = 0x00000540,
+    EvfZoomRect = 0x00000541,
+    EvfImageClipRect = 0x00000545,
+}
+
+/// A Rust type that a property value can be read into and written from.
+pub trait PropertyData: Sized {
+    /// The exact size of the property data, or `None` if it depends on the value (strings, arrays).
+    const SIZE: Option<usize>;
+
+    /// Whether a property reported as `data_type` can be represented by this type.
+    fn accepts(data_type: EdsDataType) -> bool;
+
+    /// Decodes the raw property data. `bytes` has exactly the size reported by the SDK.
+    fn from_bytes(bytes: &[u8]) -> Result<Self, EdsError>;
+
+    fn to_bytes(&self) -> Vec<u8>;
+}
+
+macro_rules! int_property_data {
+    ($($ty:ty => [$($data_type:ident),*],)*) => {
+        $(
+            impl PropertyData for $ty {
+                const SIZE: Option<usize> = Some(size_of::<$ty>());
+
+                fn accepts(data_type: EdsDataType) -> bool {
+                    matches!(data_type, $(EdsDataType::$data_type)|*)
+                }
+
+                fn from_bytes(bytes: &[u8]) -> Result<Self, EdsError> {
+                    bytes
+                        .try_into()
+                        .map(<$ty>::from_ne_bytes)
+                        .map_err(|_| size_mismatch::<$ty>(bytes.len()))
+                }
+
+                fn to_bytes(&self) -> Vec<u8> {
+                    self.to_ne_bytes().to_vec()
+                }
+            }
+        )*
+    };
+}
+
+int_property_data! {
+    u8 => [UInt8, Int8],
+    i16 => [Int16, UInt16],
+    u16 => [UInt16, Int16],
+    i32 => [Int32, UInt32],
+    u32 => [UInt32, Int32],
+    i64 => [Int64, UInt64],
+    u64 => [UInt64, Int64],
+}
+
+impl PropertyData for bool {
+    const SIZE: Option<usize> = Some(size_of::<u32>());
+
+    fn accepts(data_type: EdsDataType) -> bool {
+        data_type == EdsDataType::Bool
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, EdsError> {
+        u32::from_bytes(bytes).map(|value| value != 0)
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        (*self as u32).to_bytes()
+    }
+}
+
+impl PropertyData for String {
+    const SIZE: Option<usize> = None;
+
+    fn accepts(data_type: EdsDataType) -> bool {
+        data_type == EdsDataType::String
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, EdsError> {
+        let value = match CStr::from_bytes_until_nul(bytes) {
+            Ok(value) => value.to_string_lossy(),
+            Err(_) => String::from_utf8_lossy(bytes),
+        };
+        Ok(value.into_owned())
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.as_bytes().to_vec();
+        bytes.push(0);
+        bytes
+    }
+}
+
+impl PropertyData for Vec<u8> {
+    const SIZE: Option<usize> = None;
+
+    fn accepts(data_type: EdsDataType) -> bool {
+        matches!(
+            data_type,
+            EdsDataType::ByteBlock | EdsDataType::UInt8Array | EdsDataType::Int8Array
+        )
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, EdsError> {
+        Ok(bytes.to_vec())
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.clone()
+    }
+}
+
+macro_rules! array_property_data {
+    ($($ty:ty => [$($data_type:ident),*],)*) => {
+        $(
+            impl PropertyData for Vec<$ty> {
+                const SIZE: Option<usize> = None;
+
+                fn accepts(data_type: EdsDataType) -> bool {
+                    matches!(data_type, $(EdsDataType::$data_type)|*)
+                }
+
+                fn from_bytes(bytes: &[u8]) -> Result<Self, EdsError> {
+                    let chunks = bytes.chunks_exact(size_of::<$ty>());
+                    if !chunks.remainder().is_empty() {
+                        return Err(size_mismatch::<$ty>(bytes.len()));
+                    }
+                    chunks.map(<$ty>::from_bytes).collect()
+                }
+
+                fn to_bytes(&self) -> Vec<u8> {
+                    self.iter().flat_map(|value| value.to_bytes()).collect()
+                }
+            }
+        )*
+    };
+}
+
+array_property_data! {
+    i32 => [Int32Array, UInt32Array],
+    u32 => [UInt32Array, Int32Array],
+    EdsRational => [RationalArray],
+}
+
+/// Implements [`PropertyData`] for a `#[repr(C)]` struct made up of plain integers only.
+macro_rules! plain_property_data {
+    ($($ty:ty => [$($data_type:ident),*],)*) => {
+        $(
+            impl PropertyData for $ty {
+                const SIZE: Option<usize> = Some(size_of::<$ty>());
+
+                fn accepts(data_type: EdsDataType) -> bool {
+                    matches!(data_type, $(EdsDataType::$data_type)|*)
+                }
+
+                fn from_bytes(bytes: &[u8]) -> Result<Self, EdsError> {
+                    if bytes.len() != size_of::<$ty>() {
+                        return Err(size_mismatch::<$ty>(bytes.len()));
+                    }
+                    // SAFETY: the length was checked above and every bit pattern is a valid value
+                    // for a struct made up of integers only.
+                    Ok(unsafe { std::ptr::read_unaligned(bytes.as_ptr() as *const $ty) })
+                }
+
+                fn to_bytes(&self) -> Vec<u8> {
+                    // SAFETY: the struct is `#[repr(C)]` and made up of integers only, so it
+                    // doesn't contain any padding.
+                    unsafe {
+                        std::slice::from_raw_parts(self as *const $ty as *const u8, size_of::<$ty>())
+                    }
+                    .to_vec()
+                }
+            }
+        )*
+    };
+}
+
+plain_property_data! {
+    EdsPoint => [Point],
+    EdsSize => [Point, ByteBlock],
+    EdsRect => [Rect],
+    EdsRational => [Rational],
+    EdsTime => [Time],
+    EdsFocusPoint => [FocusInfo, ByteBlock],
+}
+
+/// [`PropertyId::FocusInfo`], the AF frames of the camera.
+///
+/// The SDK's `EdsFocusInfo` has a fixed number of frames that differs between SDK versions, so
+/// this is decoded from the `point_number` frames that are actually filled in.
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+pub struct FocusInfo {
+    pub image_rect: EdsRect,
+    pub points: Vec<EdsFocusPoint>,
+    pub execute_mode: u32,
+}
+
+impl PropertyData for FocusInfo {
+    const SIZE: Option<usize> = None;
+
+    fn accepts(data_type: EdsDataType) -> bool {
+        matches!(data_type, EdsDataType::FocusInfo | EdsDataType::ByteBlock)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, EdsError> {
+        let header = size_of::<EdsRect>() + size_of::<u32>();
+        if bytes.len() < header + size_of::<u32>() {
+            return Err(size_mismatch::<EdsRect>(bytes.len()));
+        }
+
+        let image_rect = EdsRect::from_bytes(&bytes[..size_of::<EdsRect>()])?;
+        let point_number = u32::from_bytes(&bytes[size_of::<EdsRect>()..header])? as usize;
+
+        let points_end = header + point_number * size_of::<EdsFocusPoint>();
+        if bytes.len() < points_end + size_of::<u32>() {
+            return Err(size_mismatch::<EdsFocusPoint>(bytes.len()));
+        }
+
+        let points = bytes[header..points_end]
+            .chunks_exact(size_of::<EdsFocusPoint>())
+            .map(EdsFocusPoint::from_bytes)
+            .collect::<Result<_, _>>()?;
+        let execute_mode = u32::from_bytes(&bytes[bytes.len() - size_of::<u32>()..])?;
+
+        Ok(Self {
+            image_rect,
+            points,
+            execute_mode,
+        })
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.image_rect.to_bytes();
+        bytes.extend((self.points.len() as u32).to_bytes());
+        bytes.extend(self.points.iter().flat_map(|point| point.to_bytes()));
+        bytes.extend(self.execute_mode.to_bytes());
+        bytes
+    }
+}
+
+/// Implements [`PropertyData`] for types that the SDK stores as a plain `EdsUInt32` code.
+macro_rules! code_property_data {
+    ($($ty:ty,)*) => {
+        $(
+            impl PropertyData for $ty {
+                const SIZE: Option<usize> = Some(size_of::<u32>());
+
+                fn accepts(data_type: EdsDataType) -> bool {
+                    u32::accepts(data_type)
+                }
+
+                fn from_bytes(bytes: &[u8]) -> Result<Self, EdsError> {
+                    <$ty>::try_from(u32::from_bytes(bytes)?)
+                        .map_err(|_| EdsError::InvalidDevicePropValue)
+                }
+
+                fn to_bytes(&self) -> Vec<u8> {
+                    u32::from(*self).to_bytes()
+                }
+            }
+        )*
+    };
+}
+
+code_property_data! {
+    EdsSaveTo,
+    EdsImageQuality,
+    EdsEvfOutputDevice,
+}
+
+fn size_mismatch<T>(actual: usize) -> EdsError {
+    EdsError::PropertySizeMismatch {
+        expected: size_of::<T>() as u32,
+        actual: actual as u32,
+    }
+}
+
+/// Returns the data type and size of a property as reported by the SDK.
+///
+/// # Safety
+/// `in_ref` must be a valid reference to an object that has properties (e.g. a camera).
+pub unsafe fn get_property_size(
+    in_ref: EdsBaseRef,
+    property: PropertyId,
+    param: i32,
+) -> Result<(EdsDataType, u32), EdsError> {
+    let mut data_type = 0;
+    let mut size = 0;
+    unsafe { EdsGetPropertySize(in_ref, property.code(), param, &mut data_type, &mut size).res() }?;
+    Ok((data_type.into(), size))
+}
+
+/// Reads a property and decodes it as `T`, checking the data type and size reported by the SDK.
+///
+/// # Safety
+/// `in_ref` must be a valid reference to an object that has properties (e.g. a camera).
+pub unsafe fn get_property<T: PropertyData>(
+    in_ref: EdsBaseRef,
+    property: PropertyId,
+) -> Result<T, EdsError> {
+    unsafe { get_property_with_param(in_ref, property, 0) }
+}
+
+/// Like [`get_property`], for properties that take an additional parameter.
+///
+/// # Safety
+/// `in_ref` must be a valid reference to an object that has properties (e.g. a camera).
+pub unsafe fn get_property_with_param<T: PropertyData>(
+    in_ref: EdsBaseRef,
+    property: PropertyId,
+    param: i32,
+) -> Result<T, EdsError> {
+    let (data_type, size) = unsafe { get_property_size(in_ref, property, param) }?;
+    check_property::<T>(data_type, size, size as usize)?;
+
+    let mut buffer = vec![0u8; size as usize];
+    unsafe {
+        EdsGetPropertyData(
+            in_ref,
+            property.code(),
+            param,
+            size,
+            buffer.as_mut_ptr() as *mut EdsVoid,
+        )
+        .res()
+    }?;
+
+    T::from_bytes(&buffer)
+}
+
+/// Encodes `value` and writes it to a property, checking the data type and size reported by the
+/// SDK first.
+///
+/// # Safety
+/// `in_ref` must be a valid reference to an object that has properties (e.g. a camera).
+pub unsafe fn set_property<T: PropertyData>(
+    in_ref: EdsBaseRef,
+    property: PropertyId,
+    value: &T,
+) -> Result<(), EdsError> {
+    unsafe { set_property_with_param(in_ref, property, 0, value) }
+}
+
+/// Like [`set_property`], for properties that take an additional parameter.
+///
+/// # Safety
+/// `in_ref` must be a valid reference to an object that has properties (e.g. a camera).
+pub unsafe fn set_property_with_param<T: PropertyData>(
+    in_ref: EdsBaseRef,
+    property: PropertyId,
+    param: i32,
+    value: &T,
+) -> Result<(), EdsError> {
+    let bytes = value.to_bytes();
+
+    let (data_type, size) = unsafe { get_property_size(in_ref, property, param) }?;
+    check_property::<T>(data_type, size, bytes.len())?;
+
+    unsafe {
+        EdsSetPropertyData(
+            in_ref,
+            property.code(),
+            param,
+            bytes.len() as u32,
+            bytes.as_ptr() as *const EdsVoid,
+        )
+        .res()
+    }
+}
+
+fn check_property<T: PropertyData>(
+    data_type: EdsDataType,
+    size: u32,
+    actual: usize,
+) -> Result<(), EdsError> {
+    if !T::accepts(data_type) {
+        return Err(EdsError::PropertyTypeMismatch(data_type));
+    }
+
+    match T::SIZE {
+        Some(expected) if expected != size as usize || expected != actual => {
+            Err(EdsError::PropertySizeMismatch {
+                expected: expected as u32,
+                actual: size,
+            })
+        }
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,1529 @@
+//! A pure Rust stand-in for the EDSDK, used instead of linking against the SDK when the
+//! `simulated` feature is enabled.
+//!
+//! Every `Eds*` function of [`crate::edsdk`] is implemented with the same signature. The simulated
+//! cameras keep their properties in memory, take pictures onto an in-memory card or transfer them
+//! to the host like a real camera does, and render generated jpegs for pictures and live view
+//! frames. Events are queued and dispatched from [`EdsGetEvent`], just like on macOS and Windows.
+//! Unless they are kept awake with `ExtendShutDownTimer`, they power off after
+//! `AutoPowerOffSetting` seconds and close the session. Like a real camera, they don't take
+//! pictures while a transfer to the host is neither completed nor cancelled.
+//!
+//! The number of cameras is read from `EDSDK_SIMULATED_CAMERAS` when the SDK is initialized and
+//! defaults to `1`.
+
+// the functions keep the names of the ones they replace, and with them their safety requirements
+#![allow(clippy::missing_safety_doc, non_snake_case)]
+
+use std::{
+    collections::{HashMap, VecDeque},
+    ffi::CStr,
+    fs::{File, OpenOptions},
+    io::{Read, Seek, SeekFrom, Write},
+    sync::{LazyLock, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use jpeg_encoder::{ColorType, Encoder};
+
+use crate::{
+    EdsAccess, EdsBaseRef, EdsCameraCommand, EdsCameraListRef, EdsCameraRef, EdsCapacity, EdsChar,
+    EdsDeviceInfo, EdsDirectoryItemInfo, EdsDirectoryItemRef, EdsError, EdsErrorCode,
+    EdsEvfImageRef, EdsEvfOutputDevice, EdsFileCreateDisposition, EdsFocusPoint, EdsImageInfo,
+    EdsImageQuality, EdsImageRef, EdsImageSource, EdsObjectEvent, EdsObjectEventHandler, EdsPoint,
+    EdsProgressCallback, EdsProgressOption, EdsPropertyDesc, EdsPropertyEvent,
+    EdsPropertyEventHandler, EdsPropertyId, EdsRect, EdsSaveTo, EdsSize, EdsStateEvent,
+    EdsStateEventHandler, EdsStreamRef, EdsTargetImageType, EdsVoid, EdsVolumeInfo, EdsVolumeRef,
+    FocusInfo, PropertyData, PropertyId, OBJECT_EVENT_ALL, PROPERTY_EVENT_ALL, STATE_EVENT_ALL,
+};
+
+const CAMERA_COUNT_VAR: &str = "EDSDK_SIMULATED_CAMERAS";
+
+// `kEdsDataType_*`
+const DATA_TYPE_STRING: u32 = 2;
+const DATA_TYPE_INT32: u32 = 8;
+const DATA_TYPE_UINT32: u32 = 9;
+const DATA_TYPE_BYTE_BLOCK: u32 = 14;
+const DATA_TYPE_POINT: u32 = 21;
+const DATA_TYPE_RECT: u32 = 22;
+const DATA_TYPE_UINT32_ARRAY: u32 = 36;
+const DATA_TYPE_FOCUS_INFO: u32 = 101;
+
+const PROPERTY_CHANGED: EdsPropertyEvent = 0x101;
+const DIR_ITEM_CREATED: EdsObjectEvent = 0x204;
+const DIR_ITEM_REQUEST_TRANSFER: EdsObjectEvent = 0x208;
+const SHUTDOWN: EdsStateEvent = 0x301;
+const JOB_STATUS_CHANGED: EdsStateEvent = 0x302;
+const WILL_SOON_SHUT_DOWN: EdsStateEvent = 0x303;
+
+/// The default of `AutoPowerOffSetting` in seconds
+const AUTO_POWER_OFF: u32 = 60;
+/// How long before powering off `WillSoonShutDown` is sent
+const SHUT_DOWN_WARNING: Duration = Duration::from_secs(10);
+
+const SENSOR_SIZE: EdsSize = EdsSize {
+    width: 6000,
+    height: 4000,
+};
+const PHOTO_SIZE: (u16, u16) = (1500, 1000);
+const EVF_SIZE: (u16, u16) = (960, 640);
+const THUMBNAIL_SIZE: (u16, u16) = (160, 120);
+
+const CARD_CAPACITY: u64 = 32 * 1024 * 1024 * 1024;
+const FOLDERS: [&str; 2] = ["DCIM", "100CANON"];
+/// How many times progress is reported during a download
+const PROGRESS_STEPS: usize = 10;
+
+static STATE: LazyLock<Mutex<State>> = LazyLock::new(|| Mutex::new(State::default()));
+
+#[derive(Default)]
+struct State {
+    initialized: bool,
+    next_ref: usize,
+    objects: HashMap<usize, Object>,
+    cameras: Vec<SimCamera>,
+    events: VecDeque<Event>,
+    /// Progress callbacks by stream reference
+    progress: HashMap<usize, Progress>,
+    /// Set by [`fail_next_download`]
+    fail_next_download: bool,
+}
+
+/// What a reference handed out by the simulation points to
+enum Object {
+    CameraList,
+    Camera(usize),
+    Volume(usize),
+    /// One of [`FOLDERS`] on the card of a camera
+    Folder {
+        camera: usize,
+        depth: usize,
+    },
+    /// A picture on the card of a camera
+    CardFile {
+        camera: usize,
+        id: u32,
+    },
+    /// A picture the camera wants to transfer to the host, until it's `finished` with
+    /// `EdsDownloadComplete` or `EdsDownloadCancel`
+    Transfer {
+        camera: usize,
+        file: CapturedFile,
+        finished: bool,
+    },
+    Stream(Stream),
+    EvfImage {
+        stream: usize,
+        properties: Properties,
+    },
+    /// An image file, only its size is known since the simulation doesn't decode jpegs
+    Image {
+        width: u32,
+        height: u32,
+    },
+}
+
+enum Stream {
+    Memory(Vec<u8>),
+    File(File),
+}
+
+#[derive(Clone)]
+struct CapturedFile {
+    id: u32,
+    name: String,
+    data: Vec<u8>,
+}
+
+struct Property {
+    data_type: u32,
+    data: Vec<u8>,
+}
+
+type Properties = HashMap<EdsPropertyId, Property>;
+
+/// A handler registered by the application, along with the event it was registered for
+struct Handler<F> {
+    event: u32,
+    handler: F,
+    context: Context,
+}
+
+impl<F: Copy> Handler<F> {
+    fn matching(handler: &Option<Self>, event: u32, all: u32) -> Option<(F, Context)> {
+        handler
+            .as_ref()
+            .filter(|handler| handler.event == all || handler.event == event)
+            .map(|handler| (handler.handler, Context(handler.context.0)))
+    }
+}
+
+struct Context(*mut EdsVoid);
+
+/// A progress callback registered with `EdsSetProgressCallback`
+struct Progress {
+    callback: EdsProgressCallback,
+    option: EdsProgressOption,
+    context: Context,
+}
+
+// SAFETY: the context is never dereferenced by the simulation, it is only handed back to the
+// handler it was registered with
+unsafe impl Send for Context {}
+
+struct SimCamera {
+    session_open: bool,
+    properties: Properties,
+    descs: HashMap<EdsPropertyId, Vec<i32>>,
+    card: Vec<CapturedFile>,
+    next_file: u32,
+    /// Transfers handed to the application that aren't finished, the camera is busy until then
+    transfers: usize,
+    evf_frame: u32,
+    /// When the auto power-off timer was last reset
+    last_activity: Instant,
+    shut_down_warned: bool,
+    object_handler: Option<Handler<EdsObjectEventHandler>>,
+    property_handler: Option<Handler<EdsPropertyEventHandler>>,
+    state_handler: Option<Handler<EdsStateEventHandler>>,
+}
+
+enum Event {
+    Object {
+        camera: usize,
+        event: EdsObjectEvent,
+        item: Object,
+    },
+    Property {
+        camera: usize,
+        event: EdsPropertyEvent,
+        property: EdsPropertyId,
+        param: u32,
+    },
+    State {
+        camera: usize,
+        event: EdsStateEvent,
+        data: u32,
+    },
+}
+
+impl State {
+    fn insert(&mut self, object: Object) -> EdsBaseRef {
+        self.next_ref += 1;
+        self.objects.insert(self.next_ref, object);
+        self.next_ref as EdsBaseRef
+    }
+
+    fn get(&self, raw: EdsBaseRef) -> Result<&Object, EdsError> {
+        self.objects
+            .get(&(raw as usize))
+            .ok_or(EdsError::InvalidHandle)
+    }
+
+    fn get_mut(&mut self, raw: EdsBaseRef) -> Result<&mut Object, EdsError> {
+        self.objects
+            .get_mut(&(raw as usize))
+            .ok_or(EdsError::InvalidHandle)
+    }
+
+    fn camera_index(&self, raw: EdsCameraRef) -> Result<usize, EdsError> {
+        match self.get(raw)? {
+            Object::Camera(index) => Ok(*index),
+            _ => Err(EdsError::InvalidHandle),
+        }
+    }
+
+    fn camera(&mut self, raw: EdsCameraRef) -> Result<&mut SimCamera, EdsError> {
+        let index = self.camera_index(raw)?;
+        Ok(&mut self.cameras[index])
+    }
+
+    /// A camera that has an open session
+    fn session(&mut self, raw: EdsCameraRef) -> Result<&mut SimCamera, EdsError> {
+        let camera = self.camera(raw)?;
+        if !camera.session_open {
+            return Err(EdsError::SessionNotOpen);
+        }
+        Ok(camera)
+    }
+
+    fn properties(&mut self, raw: EdsBaseRef) -> Result<&mut Properties, EdsError> {
+        match self.get(raw)? {
+            Object::Camera(_) => Ok(&mut self.session(raw)?.properties),
+            Object::EvfImage { .. } => match self.get_mut(raw)? {
+                Object::EvfImage { properties, .. } => Ok(properties),
+                _ => unreachable!(),
+            },
+            _ => Err(EdsError::InvalidHandle),
+        }
+    }
+
+    fn stream(&mut self, raw: EdsStreamRef) -> Result<&mut Stream, EdsError> {
+        match self.get_mut(raw)? {
+            Object::Stream(stream) => Ok(stream),
+            _ => Err(EdsError::InvalidHandle),
+        }
+    }
+
+    /// Lets the camera take pictures again once the last transfer is finished, items on the card
+    /// aren't transfers, so there's nothing to finish
+    fn finish_transfer(&mut self, raw: EdsDirectoryItemRef) -> Result<(), EdsError> {
+        let camera = match self.get_mut(raw)? {
+            Object::Transfer {
+                camera, finished, ..
+            } if !*finished => {
+                *finished = true;
+                *camera
+            }
+            _ => return self.file(raw).map(|_| ()),
+        };
+
+        self.cameras[camera].transfers -= 1;
+        Ok(())
+    }
+
+    /// The contents of a picture, either on the card or waiting to be transferred
+    fn file(&self, raw: EdsDirectoryItemRef) -> Result<&CapturedFile, EdsError> {
+        match self.get(raw)? {
+            Object::CardFile { camera, id } => self.cameras[*camera]
+                .card
+                .iter()
+                .find(|file| file.id == *id)
+                .ok_or(EdsError::FileNotFound),
+            Object::Transfer { file, .. } => Ok(file),
+            _ => Err(EdsError::InvalidHandle),
+        }
+    }
+
+    /// The references a parent hands out with `EdsGetChildAtIndex`
+    fn children(&self, raw: EdsBaseRef) -> Result<Vec<Object>, EdsError> {
+        Ok(match self.get(raw)? {
+            Object::CameraList => (0..self.cameras.len()).map(Object::Camera).collect(),
+            Object::Camera(index) => vec![Object::Volume(*index)],
+            Object::Volume(camera) => vec![Object::Folder {
+                camera: *camera,
+                depth: 0,
+            }],
+            Object::Folder { camera, depth } if depth + 1 < FOLDERS.len() => {
+                vec![Object::Folder {
+                    camera: *camera,
+                    depth: depth + 1,
+                }]
+            }
+            Object::Folder { camera, .. } => self.cameras[*camera]
+                .card
+                .iter()
+                .map(|file| Object::CardFile {
+                    camera: *camera,
+                    id: file.id,
+                })
+                .collect(),
+            _ => return Err(EdsError::InvalidHandle),
+        })
+    }
+}
+
+impl SimCamera {
+    fn new(index: usize) -> Self {
+        let mut properties = Properties::new();
+        let mut set = |property: PropertyId, data_type: u32, data: Vec<u8>| {
+            properties.insert(property.code(), Property { data_type, data });
+        };
+
+        set(
+            PropertyId::ProductName,
+            DATA_TYPE_STRING,
+            "Canon EOS Simulator".to_string().to_bytes(),
+        );
+        set(
+            PropertyId::BodyIdEx,
+            DATA_TYPE_STRING,
+            format!("SIM{:09}", index + 1).to_bytes(),
+        );
+        set(
+            PropertyId::FirmwareVersion,
+            DATA_TYPE_STRING,
+            "1.0.0".to_string().to_bytes(),
+        );
+        set(
+            PropertyId::BatteryLevel,
+            DATA_TYPE_UINT32,
+            100u32.to_bytes(),
+        );
+        set(
+            PropertyId::BatteryQuality,
+            DATA_TYPE_UINT32,
+            3u32.to_bytes(),
+        );
+        set(
+            PropertyId::SaveTo,
+            DATA_TYPE_UINT32,
+            EdsSaveTo::Camera.to_bytes(),
+        );
+        set(
+            PropertyId::ImageQuality,
+            DATA_TYPE_UINT32,
+            EdsImageQuality::JpegLargeFine.to_bytes(),
+        );
+        set(PropertyId::AeMode, DATA_TYPE_UINT32, 3u32.to_bytes());
+        set(PropertyId::AeModeSelect, DATA_TYPE_UINT32, 3u32.to_bytes());
+        set(PropertyId::IsoSpeed, DATA_TYPE_UINT32, 0x48u32.to_bytes());
+        set(PropertyId::Av, DATA_TYPE_UINT32, 0x30u32.to_bytes());
+        set(PropertyId::Tv, DATA_TYPE_UINT32, 0x68u32.to_bytes());
+        set(PropertyId::WhiteBalance, DATA_TYPE_INT32, 0i32.to_bytes());
+        set(
+            PropertyId::PictureStyle,
+            DATA_TYPE_UINT32,
+            0x81u32.to_bytes(),
+        );
+        set(
+            PropertyId::AvailableShots,
+            DATA_TYPE_UINT32,
+            999u32.to_bytes(),
+        );
+        set(PropertyId::LensStatus, DATA_TYPE_UINT32, 1u32.to_bytes());
+        set(
+            PropertyId::AutoPowerOffSetting,
+            DATA_TYPE_UINT32,
+            AUTO_POWER_OFF.to_bytes(),
+        );
+        set(
+            PropertyId::EvfOutputDevice,
+            DATA_TYPE_UINT32,
+            EdsEvfOutputDevice::TFT.to_bytes(),
+        );
+        set(PropertyId::EvfMode, DATA_TYPE_UINT32, 1u32.to_bytes());
+        set(PropertyId::EvfAfMode, DATA_TYPE_UINT32, 0u32.to_bytes());
+        set(
+            PropertyId::FocusInfo,
+            DATA_TYPE_FOCUS_INFO,
+            focus_info().to_bytes(),
+        );
+
+        let descs = HashMap::from([
+            (
+                PropertyId::IsoSpeed.code(),
+                vec![0x00, 0x48, 0x50, 0x58, 0x60, 0x68, 0x70, 0x78, 0x80],
+            ),
+            (
+                PropertyId::Av.code(),
+                vec![0x20, 0x23, 0x25, 0x28, 0x2b, 0x2d, 0x30, 0x33, 0x35, 0x38],
+            ),
+            (
+                PropertyId::Tv.code(),
+                vec![0x50, 0x58, 0x60, 0x68, 0x70, 0x78, 0x80, 0x88, 0x90],
+            ),
+            (
+                PropertyId::WhiteBalance.code(),
+                vec![0, 1, 2, 3, 4, 5, 8, 9],
+            ),
+            (
+                PropertyId::PictureStyle.code(),
+                vec![0x87, 0x81, 0x82, 0x83, 0x84, 0x85, 0x86],
+            ),
+        ]);
+
+        Self {
+            session_open: false,
+            properties,
+            descs,
+            card: Vec::new(),
+            next_file: 1,
+            evf_frame: 0,
+            transfers: 0,
+            last_activity: Instant::now(),
+            shut_down_warned: false,
+            object_handler: None,
+            property_handler: None,
+            state_handler: None,
+        }
+    }
+
+    fn property<T: PropertyData>(&self, property: PropertyId) -> Result<T, EdsError> {
+        let property = self
+            .properties
+            .get(&property.code())
+            .ok_or(EdsError::PropertiesUnavailable)?;
+        T::from_bytes(&property.data)
+    }
+
+    fn extend_shut_down_timer(&mut self) {
+        self.last_activity = Instant::now();
+        self.shut_down_warned = false;
+    }
+
+    /// Warns shortly before the auto power-off is due, and closes the session once it is
+    fn check_auto_power_off(&mut self, index: usize, events: &mut VecDeque<Event>) {
+        let timeout = self
+            .property::<u32>(PropertyId::AutoPowerOffSetting)
+            .unwrap_or(0);
+        if !self.session_open || timeout == 0 {
+            return;
+        }
+
+        let timeout = Duration::from_secs(timeout as u64);
+        let idle = self.last_activity.elapsed();
+        if idle >= timeout {
+            self.session_open = false;
+            events.push_back(Event::State {
+                camera: index,
+                event: SHUTDOWN,
+                data: 0,
+            });
+        } else if idle + SHUT_DOWN_WARNING >= timeout && !self.shut_down_warned {
+            self.shut_down_warned = true;
+            events.push_back(Event::State {
+                camera: index,
+                event: WILL_SOON_SHUT_DOWN,
+                data: (timeout - idle).as_secs() as u32,
+            });
+        }
+    }
+
+    /// Takes a picture and queues the events announcing it
+    fn capture(&mut self, index: usize, events: &mut VecDeque<Event>) -> Result<(), EdsError> {
+        if self.transfers > 0 {
+            return Err(EdsError::DeviceBusy);
+        }
+        self.extend_shut_down_timer();
+
+        let save_to = self.property::<u32>(PropertyId::SaveTo)?;
+        let job_status = |busy: bool| Event::State {
+            camera: index,
+            event: JOB_STATUS_CHANGED,
+            data: busy as u32,
+        };
+        events.push_back(job_status(true));
+
+        let id = self.next_file;
+        self.next_file += 1;
+
+        let (width, height) = PHOTO_SIZE;
+        let file = CapturedFile {
+            id,
+            name: format!("IMG_{:04}.JPG", id),
+            data: render_jpeg(width, height, id * 16).0,
+        };
+
+        if save_to & EdsSaveTo::Camera as u32 != 0 {
+            self.card.push(file.clone());
+        }
+
+        let (event, item) = if save_to & EdsSaveTo::Host as u32 != 0 {
+            (
+                DIR_ITEM_REQUEST_TRANSFER,
+                Object::Transfer {
+                    camera: index,
+                    file,
+                    finished: false,
+                },
+            )
+        } else {
+            (DIR_ITEM_CREATED, Object::CardFile { camera: index, id })
+        };
+        events.push_back(Event::Object {
+            camera: index,
+            event,
+            item,
+        });
+        events.push_back(job_status(false));
+
+        Ok(())
+    }
+}
+
+/// Three AF frames next to each other, with the center one selected and in focus
+fn focus_info() -> FocusInfo {
+    let frame = |x: i32, selected: bool| EdsFocusPoint {
+        valid: 1,
+        selected: selected as u32,
+        just_focus: selected as u32,
+        rect: EdsRect {
+            point: EdsPoint { x, y: 1800 },
+            size: EdsSize {
+                width: 400,
+                height: 400,
+            },
+        },
+        reserved: 0,
+    };
+
+    FocusInfo {
+        image_rect: EdsRect {
+            point: EdsPoint::default(),
+            size: SENSOR_SIZE,
+        },
+        points: vec![frame(1400, false), frame(2800, true), frame(4200, false)],
+        execute_mode: 0,
+    }
+}
+
+/// Renders a gradient that changes with `seed`, returns the jpeg and its `Y, R, G, B`
+/// histogram as reported for `kEdsPropID_Evf_Histogram`
+fn render_jpeg(width: u16, height: u16, seed: u32) -> (Vec<u8>, Vec<u32>) {
+    let (pixels, histogram) = render_pixels(width as u32, height as u32, seed);
+
+    let mut jpeg = Vec::new();
+    Encoder::new(&mut jpeg, 80)
+        .encode(&pixels, width, height, ColorType::Rgb)
+        .expect("encoding into memory can't fail");
+
+    (jpeg, histogram)
+}
+
+/// Like [`render_jpeg`], returns the RGB pixels instead
+fn render_pixels(width: u32, height: u32, seed: u32) -> (Vec<u8>, Vec<u32>) {
+    let mut pixels = Vec::with_capacity(width as usize * height as usize * 3);
+    let mut histogram = vec![0u32; 256 * 4];
+
+    for y in 0..height {
+        for x in 0..width {
+            let r = ((x * 255 / width + seed) % 256) as u8;
+            let g = (y * 255 / height) as u8;
+            let b = ((seed * 3) % 256) as u8;
+            let luminance = (r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000;
+
+            pixels.extend([r, g, b]);
+            histogram[luminance as usize * 4] += 1;
+            histogram[r as usize * 4 + 1] += 1;
+            histogram[g as usize * 4 + 2] += 1;
+            histogram[b as usize * 4 + 3] += 1;
+        }
+    }
+
+    (pixels, histogram)
+}
+
+/// The size of a jpeg, read from its start of frame segment
+fn jpeg_size(data: &[u8]) -> Option<(u32, u32)> {
+    if data.get(..2)? != [0xFF, 0xD8] {
+        return None;
+    }
+
+    let mut offset = 2;
+    while offset + 9 < data.len() {
+        if data[offset] != 0xFF {
+            return None;
+        }
+
+        let marker = data[offset + 1];
+        if matches!(marker, 0xC0..=0xC3) {
+            let height = u16::from_be_bytes([data[offset + 5], data[offset + 6]]);
+            let width = u16::from_be_bytes([data[offset + 7], data[offset + 8]]);
+            return Some((width as u32, height as u32));
+        }
+
+        let length = u16::from_be_bytes([data[offset + 2], data[offset + 3]]);
+        offset += 2 + length as usize;
+    }
+
+    None
+}
+
+/// The rect of a `width` x `height` picture letterboxed into a thumbnail
+fn thumbnail_rect(width: u32, height: u32) -> EdsRect {
+    let (thumbnail_width, thumbnail_height) = (THUMBNAIL_SIZE.0 as u32, THUMBNAIL_SIZE.1 as u32);
+    let (fitted_width, fitted_height) = if width * thumbnail_height > height * thumbnail_width {
+        (thumbnail_width, height * thumbnail_width / width)
+    } else {
+        (width * thumbnail_height / height, thumbnail_height)
+    };
+
+    EdsRect {
+        point: EdsPoint {
+            x: ((thumbnail_width - fitted_width) / 2) as i32,
+            y: ((thumbnail_height - fitted_height) / 2) as i32,
+        },
+        size: EdsSize {
+            width: fitted_width as i32,
+            height: fitted_height as i32,
+        },
+    }
+}
+
+fn read_stream(stream: &mut Stream) -> Result<Vec<u8>, EdsError> {
+    match stream {
+        Stream::Memory(buffer) => Ok(buffer.clone()),
+        Stream::File(file) => {
+            let mut data = Vec::new();
+            file.seek(SeekFrom::Start(0))
+                .and_then(|_| file.read_to_end(&mut data))
+                .map_err(|_| EdsError::StreamReadError)?;
+            Ok(data)
+        }
+    }
+}
+
+fn evf_properties(histogram: Vec<u32>) -> Properties {
+    let full_frame = EdsRect {
+        point: EdsPoint::default(),
+        size: SENSOR_SIZE,
+    };
+
+    [
+        (PropertyId::EvfZoom, DATA_TYPE_UINT32, 1u32.to_bytes()),
+        (
+            PropertyId::EvfZoomPosition,
+            DATA_TYPE_POINT,
+            EdsPoint::default().to_bytes(),
+        ),
+        (
+            PropertyId::EvfZoomRect,
+            DATA_TYPE_RECT,
+            full_frame.to_bytes(),
+        ),
+        (
+            PropertyId::EvfImagePosition,
+            DATA_TYPE_POINT,
+            EdsPoint::default().to_bytes(),
+        ),
+        (
+            PropertyId::EvfCoordinateSystem,
+            DATA_TYPE_BYTE_BLOCK,
+            SENSOR_SIZE.to_bytes(),
+        ),
+        (
+            PropertyId::EvfHistogram,
+            DATA_TYPE_UINT32_ARRAY,
+            histogram.to_bytes(),
+        ),
+    ]
+    .into_iter()
+    .map(|(property, data_type, data)| (property.code(), Property { data_type, data }))
+    .collect()
+}
+
+fn write_to_stream(stream: &mut Stream, data: &[u8]) -> Result<(), EdsError> {
+    match stream {
+        Stream::Memory(buffer) => {
+            buffer.extend_from_slice(data);
+            Ok(())
+        }
+        Stream::File(file) => file.write_all(data).map_err(|_| EdsError::StreamWriteError),
+    }
+}
+
+fn copy_str(dst: &mut [EdsChar], src: &str) {
+    let len = src.len().min(dst.len() - 1);
+    for (dst, src) in dst.iter_mut().zip(&src.as_bytes()[..len]) {
+        *dst = *src as EdsChar;
+    }
+    dst[len] = 0;
+}
+
+fn lock() -> MutexGuard<'static, State> {
+    STATE.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+/// Runs `f` on the initialized SDK and turns the result into an error code
+fn with_state(f: impl FnOnce(&mut State) -> Result<(), EdsError>) -> EdsErrorCode {
+    let mut state = lock();
+    if !state.initialized {
+        return EdsError::InvalidFnCall.into();
+    }
+
+    match f(&mut state) {
+        Ok(()) => EdsErrorCode::OK,
+        Err(err) => err.into(),
+    }
+}
+
+/// Writes `value` to an out pointer passed by the caller
+unsafe fn out<T>(ptr: *mut T, value: T) -> Result<(), EdsError> {
+    if ptr.is_null() {
+        return Err(EdsError::InvalidPointer);
+    }
+    unsafe { ptr.write(value) };
+    Ok(())
+}
+
+pub unsafe extern "C" fn EdsInitializeSDK() -> EdsErrorCode {
+    let mut state = lock();
+    if state.initialized {
+        return EdsErrorCode::OK;
+    }
+
+    let camera_count = std::env::var(CAMERA_COUNT_VAR)
+        .ok()
+        .and_then(|count| count.parse().ok())
+        .unwrap_or(1);
+
+    *state = State {
+        initialized: true,
+        cameras: (0..camera_count).map(SimCamera::new).collect(),
+        ..State::default()
+    };
+
+    EdsErrorCode::OK
+}
+
+pub unsafe extern "C" fn EdsTerminateSDK() -> EdsErrorCode {
+    *lock() = State::default();
+    EdsErrorCode::OK
+}
+
+pub unsafe extern "C" fn EdsRelease(base_ref: EdsBaseRef) -> EdsErrorCode {
+    with_state(|state| {
+        state.progress.remove(&(base_ref as usize));
+        state
+            .objects
+            .remove(&(base_ref as usize))
+            .map(|_| ())
+            .ok_or(EdsError::InvalidHandle)
+    })
+}
+
+pub unsafe extern "C" fn EdsGetChildCount(base_ref: EdsBaseRef, count: *mut u32) -> EdsErrorCode {
+    with_state(|state| {
+        let children = state.children(base_ref)?;
+        unsafe { out(count, children.len() as u32) }
+    })
+}
+
+pub unsafe extern "C" fn EdsGetChildAtIndex(
+    parent_ref: EdsBaseRef,
+    index: u32,
+    child_ref: *mut EdsBaseRef,
+) -> EdsErrorCode {
+    with_state(|state| {
+        let child = state
+            .children(parent_ref)?
+            .into_iter()
+            .nth(index as usize)
+            .ok_or(EdsError::InvalidIndex)?;
+        let child = state.insert(child);
+        unsafe { out(child_ref, child) }
+    })
+}
+
+pub unsafe extern "C" fn EdsGetCameraList(camera_list: *mut EdsCameraListRef) -> EdsErrorCode {
+    with_state(|state| {
+        let list = state.insert(Object::CameraList);
+        unsafe { out(camera_list, list) }
+    })
+}
+
+pub unsafe extern "C" fn EdsGetDeviceInfo(
+    camera_ref: EdsCameraRef,
+    device_info: *mut EdsDeviceInfo,
+) -> EdsErrorCode {
+    with_state(|state| {
+        let index = state.camera_index(camera_ref)?;
+
+        let mut info = EdsDeviceInfo::default();
+        copy_str(&mut info.sz_port_name, &format!("simulated:{}", index));
+        copy_str(&mut info.sz_device_description, "Canon EOS Simulator");
+        info.device_sub_type = 1;
+
+        unsafe { out(device_info, info) }
+    })
+}
+
+pub unsafe extern "C" fn EdsOpenSession(camera_ref: EdsCameraRef) -> EdsErrorCode {
+    with_state(|state| {
+        let camera = state.camera(camera_ref)?;
+        camera.session_open = true;
+        camera.extend_shut_down_timer();
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn EdsCloseSession(camera_ref: EdsCameraRef) -> EdsErrorCode {
+    with_state(|state| {
+        state.session(camera_ref)?.session_open = false;
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn EdsGetPropertySize(
+    in_ref: EdsBaseRef,
+    property_id: EdsPropertyId,
+    _param: i32,
+    out_data_type: *mut u32,
+    out_size: *mut u32,
+) -> EdsErrorCode {
+    with_state(|state| {
+        let property = state
+            .properties(in_ref)?
+            .get(&property_id)
+            .ok_or(EdsError::PropertiesUnavailable)?;
+        unsafe { out(out_data_type, property.data_type) }?;
+        unsafe { out(out_size, property.data.len() as u32) }
+    })
+}
+
+pub unsafe extern "C" fn EdsGetPropertyDesc(
+    in_ref: EdsBaseRef,
+    property_id: EdsPropertyId,
+    out_property_desc: *mut EdsPropertyDesc,
+) -> EdsErrorCode {
+    with_state(|state| {
+        let camera = state.session(in_ref)?;
+
+        let mut desc = EdsPropertyDesc::default();
+        if let Some(values) = camera.descs.get(&property_id) {
+            desc.num_elements = values.len() as i32;
+            desc.prop_desc[..values.len()].copy_from_slice(values);
+        }
+
+        unsafe { out(out_property_desc, desc) }
+    })
+}
+
+pub unsafe extern "C" fn EdsGetPropertyData(
+    in_ref: EdsBaseRef,
+    property_id: EdsPropertyId,
+    _param: i32,
+    size: u32,
+    out_data: *mut EdsVoid,
+) -> EdsErrorCode {
+    with_state(|state| {
+        let property = state
+            .properties(in_ref)?
+            .get(&property_id)
+            .ok_or(EdsError::PropertiesUnavailable)?;
+
+        if out_data.is_null() {
+            return Err(EdsError::InvalidPointer);
+        }
+        if (size as usize) < property.data.len() {
+            return Err(EdsError::InvalidLength);
+        }
+
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                property.data.as_ptr(),
+                out_data as *mut u8,
+                property.data.len(),
+            )
+        };
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn EdsSetPropertyData(
+    in_ref: EdsBaseRef,
+    property_id: EdsPropertyId,
+    _param: i32,
+    size: u32,
+    data: *const EdsVoid,
+) -> EdsErrorCode {
+    with_state(|state| {
+        if data.is_null() {
+            return Err(EdsError::InvalidPointer);
+        }
+        let data = unsafe { std::slice::from_raw_parts(data as *const u8, size as usize) };
+
+        let index = state.camera_index(in_ref)?;
+        let camera = state.session(in_ref)?;
+
+        if let Some(values) = camera.descs.get(&property_id) {
+            let value = i32::from_bytes(data)?;
+            if !values.contains(&value) {
+                return Err(EdsError::InvalidDevicePropValue);
+            }
+        }
+
+        let property = camera
+            .properties
+            .get_mut(&property_id)
+            .ok_or(EdsError::PropertiesUnavailable)?;
+        if property.data_type != DATA_TYPE_STRING && property.data.len() != data.len() {
+            return Err(EdsError::InvalidLength);
+        }
+
+        if property.data != data {
+            property.data = data.to_vec();
+            state.events.push_back(Event::Property {
+                camera: index,
+                event: PROPERTY_CHANGED,
+                property: property_id,
+                param: 0,
+            });
+        }
+
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn EdsSendCommand(
+    camera_ref: EdsCameraRef,
+    command: EdsCameraCommand,
+    param: i32,
+) -> EdsErrorCode {
+    with_state(|state| {
+        let index = state.camera_index(camera_ref)?;
+        let State {
+            cameras, events, ..
+        } = state;
+        let camera = &mut cameras[index];
+
+        if !camera.session_open {
+            return Err(EdsError::SessionNotOpen);
+        }
+
+        match (command, param) {
+            // TakePicture, or PressShutterButton Completely/CompletelyNonAf
+            (0x000, _) | (0x004, 0x00003 | 0x10003) => camera.capture(index, events),
+            // ExtendShutDownTimer
+            (0x001, _) => {
+                camera.extend_shut_down_timer();
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    })
+}
+
+pub unsafe extern "C" fn EdsGetEvent() -> EdsErrorCode {
+    // handlers call back into the SDK, so they are invoked without holding the lock
+    let dispatch = {
+        let mut state = lock();
+        if !state.initialized {
+            return EdsError::InvalidFnCall.into();
+        }
+
+        let State {
+            cameras, events, ..
+        } = &mut *state;
+        for (index, camera) in cameras.iter_mut().enumerate() {
+            camera.check_auto_power_off(index, events);
+        }
+
+        let events = state.events.drain(..).collect::<Vec<_>>();
+        let mut dispatch: Vec<Box<dyn FnOnce()>> = Vec::new();
+
+        for event in events {
+            match event {
+                Event::Object {
+                    camera,
+                    event,
+                    item,
+                } => {
+                    let Some((handler, context)) = Handler::matching(
+                        &state.cameras[camera].object_handler,
+                        event,
+                        OBJECT_EVENT_ALL,
+                    ) else {
+                        continue;
+                    };
+                    if event == DIR_ITEM_REQUEST_TRANSFER {
+                        state.cameras[camera].transfers += 1;
+                    }
+                    // the handler owns the reference, just like with the SDK
+                    let item = state.insert(item);
+                    dispatch.push(Box::new(move || {
+                        handler(event, item, context.0);
+                    }));
+                }
+                Event::Property {
+                    camera,
+                    event,
+                    property,
+                    param,
+                } => {
+                    let Some((handler, context)) = Handler::matching(
+                        &state.cameras[camera].property_handler,
+                        event,
+                        PROPERTY_EVENT_ALL,
+                    ) else {
+                        continue;
+                    };
+                    dispatch.push(Box::new(move || {
+                        handler(event, property, param, context.0);
+                    }));
+                }
+                Event::State {
+                    camera,
+                    event,
+                    data,
+                } => {
+                    let Some((handler, context)) = Handler::matching(
+                        &state.cameras[camera].state_handler,
+                        event,
+                        STATE_EVENT_ALL,
+                    ) else {
+                        continue;
+                    };
+                    dispatch.push(Box::new(move || {
+                        handler(event, data, context.0);
+                    }));
+                }
+            }
+        }
+
+        dispatch
+    };
+
+    for handler in dispatch {
+        handler();
+    }
+
+    EdsErrorCode::OK
+}
+
+pub unsafe extern "C" fn EdsSetObjectEventHandler(
+    camera_ref: EdsCameraRef,
+    event: EdsObjectEvent,
+    event_handler: Option<EdsObjectEventHandler>,
+    context: *mut EdsVoid,
+) -> EdsErrorCode {
+    with_state(|state| {
+        state.camera(camera_ref)?.object_handler = event_handler.map(|handler| Handler {
+            event,
+            handler,
+            context: Context(context),
+        });
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn EdsSetPropertyEventHandler(
+    camera_ref: EdsCameraRef,
+    event: EdsPropertyEvent,
+    event_handler: Option<EdsPropertyEventHandler>,
+    context: *mut EdsVoid,
+) -> EdsErrorCode {
+    with_state(|state| {
+        state.camera(camera_ref)?.property_handler = event_handler.map(|handler| Handler {
+            event,
+            handler,
+            context: Context(context),
+        });
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn EdsSetCameraStateEventHandler(
+    camera_ref: EdsCameraRef,
+    event: EdsStateEvent,
+    event_handler: Option<EdsStateEventHandler>,
+    context: *mut EdsVoid,
+) -> EdsErrorCode {
+    with_state(|state| {
+        state.camera(camera_ref)?.state_handler = event_handler.map(|handler| Handler {
+            event,
+            handler,
+            context: Context(context),
+        });
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn EdsGetVolumeInfo(
+    in_volume_ref: EdsVolumeRef,
+    out_volume_info: *mut EdsVolumeInfo,
+) -> EdsErrorCode {
+    with_state(|state| {
+        let Object::Volume(camera) = state.get(in_volume_ref)? else {
+            return Err(EdsError::InvalidHandle);
+        };
+        let used: u64 = state.cameras[*camera]
+            .card
+            .iter()
+            .map(|file| file.data.len() as u64)
+            .sum();
+
+        let mut info = EdsVolumeInfo {
+            storage_type: 2,
+            access: EdsAccess::ReadWrite as u32,
+            max_capacity: CARD_CAPACITY,
+            free_space_in_bytes: CARD_CAPACITY - used,
+            ..Default::default()
+        };
+        copy_str(&mut info.sz_volume_label, "SD");
+
+        unsafe { out(out_volume_info, info) }
+    })
+}
+
+pub unsafe extern "C" fn EdsFormatVolume(in_volume_ref: EdsVolumeRef) -> EdsErrorCode {
+    with_state(|state| {
+        let Object::Volume(camera) = *state.get(in_volume_ref)? else {
+            return Err(EdsError::InvalidHandle);
+        };
+        state.cameras[camera].card.clear();
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn EdsDeleteDirectoryItem(
+    in_dir_item_ref: EdsDirectoryItemRef,
+) -> EdsErrorCode {
+    with_state(|state| {
+        let Object::CardFile { camera, id } = state.get(in_dir_item_ref)? else {
+            return Err(EdsError::NotSupported);
+        };
+        let (camera, id) = (*camera, *id);
+
+        let card = &mut state.cameras[camera].card;
+        let position = card
+            .iter()
+            .position(|file| file.id == id)
+            .ok_or(EdsError::FileNotFound)?;
+        card.remove(position);
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn EdsGetDirectoryItemInfo(
+    in_dir_item_ref: EdsDirectoryItemRef,
+    out_info: *mut EdsDirectoryItemInfo,
+) -> EdsErrorCode {
+    with_state(|state| {
+        let mut info = EdsDirectoryItemInfo::default();
+
+        if let Object::Folder { depth, .. } = state.get(in_dir_item_ref)? {
+            info.is_folder = 1;
+            copy_str(&mut info.sz_file_name, FOLDERS[*depth]);
+        } else {
+            let file = state.file(in_dir_item_ref)?;
+            info.size = file.data.len() as u64;
+            // kEdsObjectFormat_Jpeg
+            info.format = 0x3801;
+            copy_str(&mut info.sz_file_name, &file.name);
+        }
+
+        unsafe { out(out_info, info) }
+    })
+}
+
+pub unsafe extern "C" fn EdsCreateFileStream(
+    in_file_name: *const EdsChar,
+    in_create_disposition: EdsFileCreateDisposition,
+    in_desired_access: EdsAccess,
+    out_stream: *mut EdsStreamRef,
+) -> EdsErrorCode {
+    with_state(|state| {
+        if in_file_name.is_null() {
+            return Err(EdsError::InvalidPointer);
+        }
+        let path = unsafe { CStr::from_ptr(in_file_name) }
+            .to_str()
+            .map_err(|_| EdsError::InvalidParameter)?;
+
+        let mut options = OpenOptions::new();
+        match in_desired_access {
+            EdsAccess::Read => options.read(true),
+            EdsAccess::Write => options.write(true),
+            EdsAccess::ReadWrite => options.read(true).write(true),
+            EdsAccess::Error => return Err(EdsError::InvalidParameter),
+        };
+        match in_create_disposition {
+            EdsFileCreateDisposition::CreateNew => options.create_new(true),
+            EdsFileCreateDisposition::CreateAlways => options.create(true).truncate(true),
+            EdsFileCreateDisposition::OpenExisting => &mut options,
+            EdsFileCreateDisposition::OpenAlways => options.create(true),
+            EdsFileCreateDisposition::TruncateExisting => options.truncate(true),
+        };
+
+        let file = options.open(path).map_err(|_| EdsError::FileOpenError)?;
+        let stream = state.insert(Object::Stream(Stream::File(file)));
+        unsafe { out(out_stream, stream) }
+    })
+}
+
+pub unsafe extern "C" fn EdsCreateMemoryStream(
+    in_buffer_size: u64,
+    out_stream: *mut EdsStreamRef,
+) -> EdsErrorCode {
+    with_state(|state| {
+        let buffer = Vec::with_capacity(in_buffer_size as usize);
+        let stream = state.insert(Object::Stream(Stream::Memory(buffer)));
+        unsafe { out(out_stream, stream) }
+    })
+}
+
+pub unsafe extern "C" fn EdsCreateMemoryStreamFromPointer(
+    _in_user_buffer: *mut EdsVoid,
+    _in_buffer_size: u64,
+    _out_stream: *mut EdsStreamRef,
+) -> EdsErrorCode {
+    with_state(|_| Err(EdsError::Unimplemented))
+}
+
+pub unsafe extern "C" fn EdsGetPointer(
+    in_stream: EdsStreamRef,
+    out_pointer: *mut *mut EdsVoid,
+) -> EdsErrorCode {
+    with_state(|state| match state.stream(in_stream)? {
+        Stream::Memory(buffer) => unsafe { out(out_pointer, buffer.as_mut_ptr() as *mut EdsVoid) },
+        Stream::File(_) => Err(EdsError::NotSupported),
+    })
+}
+
+pub unsafe extern "C" fn EdsGetLength(
+    in_stream: EdsStreamRef,
+    out_length: *mut u64,
+) -> EdsErrorCode {
+    with_state(|state| {
+        let length = match state.stream(in_stream)? {
+            Stream::Memory(buffer) => buffer.len() as u64,
+            Stream::File(file) => file.metadata().map_err(|_| EdsError::StreamIoError)?.len(),
+        };
+        unsafe { out(out_length, length) }
+    })
+}
+
+pub unsafe extern "C" fn EdsDownload(
+    in_dir_item_ref: EdsDirectoryItemRef,
+    in_read_size: u64,
+    in_stream_ref: EdsStreamRef,
+) -> EdsErrorCode {
+    let mut data = Vec::new();
+    let mut progress = None;
+    let mut fail = false;
+    let res = with_state(|state| {
+        data = state.file(in_dir_item_ref)?.data.clone();
+        data.truncate(in_read_size as usize);
+        // half of it makes it to the stream
+        fail = std::mem::take(&mut state.fail_next_download);
+        if fail {
+            data.truncate(data.len() / 2);
+        }
+        state.stream(in_stream_ref)?;
+        progress = state
+            .progress
+            .get(&(in_stream_ref as usize))
+            .map(|progress| {
+                (
+                    progress.callback,
+                    progress.option,
+                    Context(progress.context.0),
+                )
+            });
+        Ok(())
+    });
+    if res != EdsErrorCode::OK {
+        return res;
+    }
+
+    let failed = |res: EdsErrorCode| match res {
+        EdsErrorCode::OK if fail => EdsError::StreamWriteError.into(),
+        res => res,
+    };
+
+    let Some((callback, option, context)) = progress else {
+        return failed(with_state(|state| {
+            write_to_stream(state.stream(in_stream_ref)?, &data)
+        }));
+    };
+
+    // the callback may call back into the SDK, so it's invoked without holding the lock
+    let chunk_size = data.len().div_ceil(PROGRESS_STEPS).max(1);
+    let chunks = data.chunks(chunk_size).count();
+    for (index, chunk) in data.chunks(chunk_size).enumerate() {
+        let res = with_state(|state| write_to_stream(state.stream(in_stream_ref)?, chunk));
+        if res != EdsErrorCode::OK {
+            return res;
+        }
+
+        let done = index + 1 == chunks;
+        if option == EdsProgressOption::Periodically || (option == EdsProgressOption::Done && done)
+        {
+            let mut cancel = 0;
+            let res = callback(((index + 1) * 100 / chunks) as u32, context.0, &mut cancel);
+            if res != EdsErrorCode::OK {
+                return res;
+            }
+            if cancel != 0 {
+                return EdsError::OperationCancelled.into();
+            }
+        }
+    }
+
+    failed(EdsErrorCode::OK)
+}
+
+pub unsafe extern "C" fn EdsSetProgressCallback(
+    in_ref: EdsBaseRef,
+    in_progress_func: Option<EdsProgressCallback>,
+    in_progress_option: EdsProgressOption,
+    in_context: *mut EdsVoid,
+) -> EdsErrorCode {
+    with_state(|state| {
+        // progress is only reported for downloads into streams
+        state.stream(in_ref)?;
+
+        match in_progress_func {
+            Some(callback) if in_progress_option != EdsProgressOption::NoReport => {
+                state.progress.insert(
+                    in_ref as usize,
+                    Progress {
+                        callback,
+                        option: in_progress_option,
+                        context: Context(in_context),
+                    },
+                );
+            }
+            _ => {
+                state.progress.remove(&(in_ref as usize));
+            }
+        }
+        Ok(())
+    })
+}
+
+pub unsafe extern "C" fn EdsDownloadComplete(in_dir_item_ref: EdsDirectoryItemRef) -> EdsErrorCode {
+    with_state(|state| state.finish_transfer(in_dir_item_ref))
+}
+
+pub unsafe extern "C" fn EdsDownloadCancel(in_dir_item_ref: EdsDirectoryItemRef) -> EdsErrorCode {
+    with_state(|state| state.finish_transfer(in_dir_item_ref))
+}
+
+/// Makes the next `EdsDownload` fail halfway with [`EdsError::StreamWriteError`], as if the disk
+/// filled up, so applications can test how they clean up after a failed transfer
+pub fn fail_next_download() {
+    lock().fail_next_download = true;
+}
+
+pub unsafe extern "C" fn EdsSetCapacity(
+    camera_ref: EdsCameraRef,
+    _capacity: EdsCapacity,
+) -> EdsErrorCode {
+    with_state(|state| state.session(camera_ref).map(|_| ()))
+}
+
+pub unsafe extern "C" fn EdsCreateEvfImageRef(
+    in_stream: EdsStreamRef,
+    out_evf_image_ref: *mut EdsEvfImageRef,
+) -> EdsErrorCode {
+    with_state(|state| {
+        state.stream(in_stream)?;
+        let evf_image = state.insert(Object::EvfImage {
+            stream: in_stream as usize,
+            properties: Properties::new(),
+        });
+        unsafe { out(out_evf_image_ref, evf_image) }
+    })
+}
+
+pub unsafe extern "C" fn EdsDownloadEvfImage(
+    in_camera: EdsCameraRef,
+    in_evf_image_ref: EdsEvfImageRef,
+) -> EdsErrorCode {
+    with_state(|state| {
+        let camera = state.session(in_camera)?;
+        let output_device = camera.property::<EdsEvfOutputDevice>(PropertyId::EvfOutputDevice)?;
+        if !output_device.contains(EdsEvfOutputDevice::PC) {
+            return Err(EdsError::ObjectNotReady);
+        }
+
+        camera.evf_frame = camera.evf_frame.wrapping_add(1);
+        let (width, height) = EVF_SIZE;
+        let (jpeg, histogram) = render_jpeg(width, height, camera.evf_frame);
+
+        let Object::EvfImage { stream, properties } = state.get_mut(in_evf_image_ref)? else {
+            return Err(EdsError::InvalidHandle);
+        };
+        *properties = evf_properties(histogram);
+        let stream = *stream as EdsStreamRef;
+
+        match state.stream(stream)? {
+            Stream::Memory(buffer) => {
+                *buffer = jpeg;
+                Ok(())
+            }
+            stream => write_to_stream(stream, &jpeg),
+        }
+    })
+}
+
+pub unsafe extern "C" fn EdsDownloadThumbnail(
+    in_dir_item_ref: EdsDirectoryItemRef,
+    in_stream: EdsStreamRef,
+) -> EdsErrorCode {
+    with_state(|state| {
+        let id = state.file(in_dir_item_ref)?.id;
+        let (width, height) = THUMBNAIL_SIZE;
+        let (jpeg, _) = render_jpeg(width, height, id * 16);
+        write_to_stream(state.stream(in_stream)?, &jpeg)
+    })
+}
+
+pub unsafe extern "C" fn EdsCreateImageRef(
+    in_stream_ref: EdsStreamRef,
+    out_image_ref: *mut EdsImageRef,
+) -> EdsErrorCode {
+    with_state(|state| {
+        let data = read_stream(state.stream(in_stream_ref)?)?;
+        let (width, height) = jpeg_size(&data).ok_or(EdsError::FileFormatUnrecognized)?;
+
+        let image = state.insert(Object::Image { width, height });
+        unsafe { out(out_image_ref, image) }
+    })
+}
+
+pub unsafe extern "C" fn EdsGetImageInfo(
+    in_image_ref: EdsImageRef,
+    in_image_source: EdsImageSource,
+    out_image_info: *mut EdsImageInfo,
+) -> EdsErrorCode {
+    with_state(|state| {
+        let Object::Image { width, height } = *state.get(in_image_ref)? else {
+            return Err(EdsError::InvalidHandle);
+        };
+
+        let (width, height, effective_rect) = match in_image_source {
+            EdsImageSource::FullView => (
+                width,
+                height,
+                EdsRect {
+                    point: EdsPoint::default(),
+                    size: EdsSize {
+                        width: width as i32,
+                        height: height as i32,
+                    },
+                },
+            ),
+            EdsImageSource::Thumbnail => (
+                THUMBNAIL_SIZE.0 as u32,
+                THUMBNAIL_SIZE.1 as u32,
+                thumbnail_rect(width, height),
+            ),
+            // the simulated pictures are jpegs, those don't have RAW images or a preview
+            _ => return Err(EdsError::NotSupported),
+        };
+
+        let info = EdsImageInfo {
+            width,
+            height,
+            num_of_components: 3,
+            component_depth: 8,
+            effective_rect,
+        };
+        unsafe { out(out_image_info, info) }
+    })
+}
+
+pub unsafe extern "C" fn EdsGetImage(
+    in_image_ref: EdsImageRef,
+    in_image_source: EdsImageSource,
+    in_image_type: EdsTargetImageType,
+    _in_src_rect: EdsRect,
+    in_dst_size: EdsSize,
+    out_stream_ref: EdsStreamRef,
+) -> EdsErrorCode {
+    with_state(|state| {
+        let Object::Image { .. } = state.get(in_image_ref)? else {
+            return Err(EdsError::InvalidHandle);
+        };
+        if !matches!(
+            in_image_source,
+            EdsImageSource::FullView | EdsImageSource::Thumbnail
+        ) {
+            return Err(EdsError::NotSupported);
+        }
+        if in_image_type != EdsTargetImageType::Rgb
+            || in_dst_size.width <= 0
+            || in_dst_size.height <= 0
+        {
+            return Err(EdsError::InvalidParameter);
+        }
+
+        // a fresh gradient stands in for the decoded picture
+        let (pixels, _) = render_pixels(in_dst_size.width as u32, in_dst_size.height as u32, 0);
+        write_to_stream(state.stream(out_stream_ref)?, &pixels)
+    })
+}
@@ -0,0 +1,224 @@
+use std::{
+    ffi::CString,
+    marker::PhantomData,
+    mem::{size_of, size_of_val},
+    path::Path,
+    sync::Mutex,
+};
+
+use crate::{
+    callback::{progress_trampoline, ProgressContext},
+    get_property,
+    handle::OwnedRef,
+    EdsAccess, EdsCreateEvfImageRef, EdsCreateFileStream, EdsCreateMemoryStream, EdsError,
+    EdsEvfImageRef, EdsFileCreateDisposition, EdsGetLength, EdsGetPointer, EdsPoint,
+    EdsProgressOption, EdsRect, EdsSetProgressCallback, EdsSize, EdsStreamRef, EdsVoid,
+    PropertyData, PropertyId, Sdk,
+};
+
+/// A stream the SDK reads from or writes to.
+pub struct Stream<'sdk> {
+    raw: OwnedRef,
+    /// Dropped after `raw`, the SDK doesn't report progress for a released stream.
+    progress: Option<ProgressContext>,
+    _sdk: PhantomData<&'sdk Sdk>,
+}
+
+impl<'sdk> Stream<'sdk> {
+    pub fn create_file(
+        _sdk: &'sdk Sdk,
+        path: &Path,
+        disposition: EdsFileCreateDisposition,
+        access: EdsAccess,
+    ) -> Result<Self, EdsError> {
+        let file_name = CString::new(path.as_os_str().as_encoded_bytes())
+            .map_err(|_| EdsError::InvalidParameter)?;
+
+        let mut stream = std::ptr::null_mut() as EdsStreamRef;
+        unsafe { EdsCreateFileStream(file_name.as_ptr(), disposition, access, &mut stream).res() }?;
+
+        Ok(Self {
+            raw: unsafe { OwnedRef::from_raw(stream) },
+            progress: None,
+            _sdk: PhantomData,
+        })
+    }
+
+    /// Creates a stream backed by memory allocated by the SDK. The buffer grows as needed, so
+    /// `initial_size` can be `0`.
+    pub fn create_memory(_sdk: &'sdk Sdk, initial_size: u64) -> Result<Self, EdsError> {
+        let mut stream = std::ptr::null_mut() as EdsStreamRef;
+        unsafe { EdsCreateMemoryStream(initial_size, &mut stream).res() }?;
+
+        Ok(Self {
+            raw: unsafe { OwnedRef::from_raw(stream) },
+            progress: None,
+            _sdk: PhantomData,
+        })
+    }
+
+    pub fn as_raw(&self) -> EdsStreamRef {
+        self.raw.as_raw()
+    }
+
+    /// Calls `handler` with the percentage done while the SDK transfers data into the stream, e.g.
+    /// during [`crate::DirectoryItem::download`]. Returning `false` cancels the transfer, which
+    /// then fails with [`EdsError::OperationCancelled`]. Replaces the previous handler.
+    pub fn on_progress(
+        &mut self,
+        handler: impl FnMut(u32) -> bool + Send + 'static,
+    ) -> Result<(), EdsError> {
+        let context: ProgressContext = Box::new(Mutex::new(Box::new(handler)));
+        unsafe {
+            EdsSetProgressCallback(
+                self.as_raw(),
+                Some(progress_trampoline),
+                EdsProgressOption::Periodically,
+                &*context as *const _ as *mut EdsVoid,
+            )
+            .res()
+        }?;
+
+        // the previous handler isn't called anymore
+        self.progress = Some(context);
+        Ok(())
+    }
+
+    /// Number of bytes written to the stream so far.
+    pub fn len(&self) -> Result<u64, EdsError> {
+        let mut length = 0;
+        unsafe { EdsGetLength(self.as_raw(), &mut length).res() }?;
+        Ok(length)
+    }
+
+    pub fn is_empty(&self) -> Result<bool, EdsError> {
+        Ok(self.len()? == 0)
+    }
+
+    /// Copies the contents of a memory stream.
+    pub fn to_vec(&self) -> Result<Vec<u8>, EdsError> {
+        let length = self.len()? as usize;
+        if length == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut pointer = std::ptr::null_mut() as *mut EdsVoid;
+        unsafe { EdsGetPointer(self.as_raw(), &mut pointer).res() }?;
+        if pointer.is_null() {
+            return Err(EdsError::InvalidPointer);
+        }
+
+        Ok(unsafe { std::slice::from_raw_parts(pointer as *const u8, length) }.to_vec())
+    }
+}
+
+/// A live view image, downloaded into the stream it was created from with
+/// [`crate::Camera::download_evf_image`].
+pub struct EvfImage<'stream> {
+    raw: OwnedRef,
+    _stream: PhantomData<&'stream Stream<'stream>>,
+}
+
+impl<'stream> EvfImage<'stream> {
+    pub fn new(stream: &'stream Stream<'_>) -> Result<Self, EdsError> {
+        let mut evf_image = std::ptr::null_mut() as EdsEvfImageRef;
+        unsafe { EdsCreateEvfImageRef(stream.as_raw(), &mut evf_image).res() }?;
+
+        Ok(Self {
+            raw: unsafe { OwnedRef::from_raw(evf_image) },
+            _stream: PhantomData,
+        })
+    }
+
+    pub fn as_raw(&self) -> EdsEvfImageRef {
+        self.raw.as_raw()
+    }
+
+    /// Reads one of the `Evf*` properties describing the downloaded frame.
+    pub fn get_property<T: PropertyData>(&self, property: PropertyId) -> Result<T, EdsError> {
+        unsafe { get_property(self.as_raw(), property) }
+    }
+
+    /// The zoom factor, `1`, `5` or `10`.
+    pub fn zoom(&self) -> Result<u32, EdsError> {
+        self.get_property(PropertyId::EvfZoom)
+    }
+
+    /// The top left corner of the zoomed area, in [`Self::coordinate_system`] units.
+    pub fn zoom_position(&self) -> Result<EdsPoint, EdsError> {
+        self.get_property(PropertyId::EvfZoomPosition)
+    }
+
+    /// The zoomed area, in [`Self::coordinate_system`] units.
+    pub fn zoom_rect(&self) -> Result<EdsRect, EdsError> {
+        self.get_property(PropertyId::EvfZoomRect)
+    }
+
+    /// Where the frame is located on the sensor, in [`Self::coordinate_system`] units.
+    pub fn image_position(&self) -> Result<EdsPoint, EdsError> {
+        self.get_property(PropertyId::EvfImagePosition)
+    }
+
+    /// The size of the whole sensor area that positions and rects are relative to.
+    pub fn coordinate_system(&self) -> Result<EdsSize, EdsError> {
+        self.get_property(PropertyId::EvfCoordinateSystem)
+    }
+
+    /// Fails when the camera doesn't show the histogram, see [`PropertyId::EvfHistogramStatus`].
+    pub fn histogram(&self) -> Result<Histogram, EdsError> {
+        let values: Vec<u32> = self.get_property(PropertyId::EvfHistogram)?;
+        Histogram::from_interleaved(&values)
+    }
+}
+
+/// A live view histogram with 256 bins per channel.
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+pub struct Histogram {
+    pub y: Vec<u32>,
+    pub r: Vec<u32>,
+    pub g: Vec<u32>,
+    pub b: Vec<u32>,
+}
+
+impl Histogram {
+    /// Splits the `Y, R, G, B` tuples the SDK returns for [`PropertyId::EvfHistogram`].
+    fn from_interleaved(values: &[u32]) -> Result<Self, EdsError> {
+        if values.len() != 256 * 4 {
+            return Err(EdsError::PropertySizeMismatch {
+                expected: 256 * 4 * size_of::<u32>() as u32,
+                actual: size_of_val(values) as u32,
+            });
+        }
+
+        let channel = |offset: usize| values.iter().skip(offset).step_by(4).copied().collect();
+        Ok(Self {
+            y: channel(0),
+            r: channel(1),
+            g: channel(2),
+            b: channel(3),
+        })
+    }
+
+    /// The mean luminance of the frame, from `0.0` (black) to `255.0` (white).
+    pub fn mean_luminance(&self) -> Option<f64> {
+        let count: u64 = self.y.iter().map(|&count| count as u64).sum();
+        if count == 0 {
+            return None;
+        }
+
+        let total: u64 = self
+            .y
+            .iter()
+            .enumerate()
+            .map(|(level, &count)| level as u64 * count as u64)
+            .sum();
+        Some(total as f64 / count as f64)
+    }
+
+    /// The share of pixels in the brightest bin, a rough measure for blown out highlights.
+    pub fn clipped_highlights(&self) -> Option<f64> {
+        let count: u64 = self.y.iter().map(|&count| count as u64).sum();
+        let clipped = *self.y.last()? as u64;
+        (count > 0).then(|| clipped as f64 / count as f64)
+    }
+}
@@ -0,0 +1,422 @@
+use std::{fmt, str::FromStr};
+
+use crate::PropertyId;
+
+/// A label that doesn't belong to any value of the property it was parsed for.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ParseValueError {
+    pub label: String,
+}
+
+impl fmt::Display for ParseValueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown value {:?}", self.label)
+    }
+}
+
+impl std::error::Error for ParseValueError {}
+
+/// Parses the label [`IsoSpeed::label`] and friends give unknown codes, e.g. `unknown (0x1f)`.
+fn parse_unknown_label(label: &str) -> Option<u32> {
+    let hex = label.strip_prefix("unknown (0x")?.strip_suffix(')')?;
+    u32::from_str_radix(hex, 16).ok()
+}
+
+macro_rules! property_values {
+    ($(
+        $(#[$meta:meta])*
+        $ty:ident {
+            $($name:ident = $code:literal => $label:literal,)*
+        }
+    )*) => {
+        $(
+            $(#[$meta])*
+            #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+            pub enum $ty {
+                $($name,)*
+
+                /// A code that isn't known to these bindings.
+                Unknown(u32),
+            }
+
+            impl $ty {
+                pub fn from_code(code: u32) -> Self {
+                    match code {
+                        $($code => Self::$name,)*
+                        code => Self::Unknown(code),
+                    }
+                }
+
+                pub fn code(&self) -> u32 {
+                    match self {
+                        $(Self::$name => $code,)*
+                        Self::Unknown(code) => *code,
+                    }
+                }
+
+                pub fn label(&self) -> String {
+                    match self {
+                        $(Self::$name => $label.to_string(),)*
+                        Self::Unknown(code) => format!("unknown (0x{:x})", code),
+                    }
+                }
+
+                /// The value whose [`Self::label`] is `label`.
+                pub fn from_label(label: &str) -> Option<Self> {
+                    match label {
+                        $($label => Some(Self::$name),)*
+                        label => parse_unknown_label(label).map(Self::from_code),
+                    }
+                }
+            }
+
+            impl FromStr for $ty {
+                type Err = ParseValueError;
+
+                fn from_str(label: &str) -> Result<Self, Self::Err> {
+                    Self::from_label(label).ok_or_else(|| ParseValueError {
+                        label: label.to_string(),
+                    })
+                }
+            }
+        )*
+    };
+}
+
+property_values! {
+    /// Values of [`PropertyId::IsoSpeed`].
+    IsoSpeed {
+        Auto = 0x00 => "Auto",
+        Iso6 = 0x28 => "6",
+        Iso12 = 0x30 => "12",
+        Iso25 = 0x38 => "25",
+        Iso50 = 0x40 => "50",
+        Iso80 = 0x45 => "80",
+        Iso100 = 0x48 => "100",
+        Iso125 = 0x4b => "125",
+        Iso160 = 0x4d => "160",
+        Iso200 = 0x50 => "200",
+        Iso250 = 0x53 => "250",
+        Iso320 = 0x55 => "320",
+        Iso400 = 0x58 => "400",
+        Iso500 = 0x5b => "500",
+        Iso640 = 0x5d => "640",
+        Iso800 = 0x60 => "800",
+        Iso1000 = 0x63 => "1000",
+        Iso1250 = 0x65 => "1250",
+        Iso1600 = 0x68 => "1600",
+        Iso2000 = 0x6b => "2000",
+        Iso2500 = 0x6d => "2500",
+        Iso3200 = 0x70 => "3200",
+        Iso4000 = 0x73 => "4000",
+        Iso5000 = 0x75 => "5000",
+        Iso6400 = 0x78 => "6400",
+        Iso8000 = 0x7b => "8000",
+        Iso10000 = 0x7d => "10000",
+        Iso12800 = 0x80 => "12800",
+        Iso16000 = 0x83 => "16000",
+        Iso20000 = 0x85 => "20000",
+        Iso25600 = 0x88 => "25600",
+        Iso32000 = 0x8b => "32000",
+        Iso40000 = 0x8d => "40000",
+        Iso51200 = 0x90 => "51200",
+        Iso64000 = 0x93 => "64000",
+        Iso80000 = 0x95 => "80000",
+        Iso102400 = 0x98 => "102400",
+        Iso204800 = 0xa0 => "204800",
+        Iso409600 = 0xa8 => "409600",
+        Iso819200 = 0xb0 => "819200",
+    }
+
+    /// Values of [`PropertyId::Av`]. Variants ending in `Half` are half stop values.
+    Aperture {
+        F1 = 0x08 => "f/1",
+        F1_1 = 0x0b => "f/1.1",
+        F1_2Half = 0x0c => "f/1.2 (1/2)",
+        F1_2 = 0x0d => "f/1.2",
+        F1_4 = 0x10 => "f/1.4",
+        F1_6 = 0x13 => "f/1.6",
+        F1_8Half = 0x14 => "f/1.8 (1/2)",
+        F1_8 = 0x15 => "f/1.8",
+        F2 = 0x18 => "f/2",
+        F2_2 = 0x1b => "f/2.2",
+        F2_5Half = 0x1c => "f/2.5 (1/2)",
+        F2_5 = 0x1d => "f/2.5",
+        F2_8 = 0x20 => "f/2.8",
+        F3_2 = 0x23 => "f/3.2",
+        F3_4 = 0x85 => "f/3.4",
+        F3_5Half = 0x24 => "f/3.5 (1/2)",
+        F3_5 = 0x25 => "f/3.5",
+        F4 = 0x28 => "f/4",
+        F4_5 = 0x2b => "f/4.5",
+        F4_5Half = 0x2c => "f/4.5 (1/2)",
+        F5 = 0x2d => "f/5",
+        F5_6 = 0x30 => "f/5.6",
+        F6_3 = 0x33 => "f/6.3",
+        F6_7Half = 0x34 => "f/6.7 (1/2)",
+        F7_1 = 0x35 => "f/7.1",
+        F8 = 0x38 => "f/8",
+        F9 = 0x3b => "f/9",
+        F9_5Half = 0x3c => "f/9.5 (1/2)",
+        F10 = 0x3d => "f/10",
+        F11 = 0x40 => "f/11",
+        F13 = 0x43 => "f/13",
+        F13Half = 0x44 => "f/13 (1/2)",
+        F14 = 0x45 => "f/14",
+        F16 = 0x48 => "f/16",
+        F18 = 0x4b => "f/18",
+        F19Half = 0x4c => "f/19 (1/2)",
+        F20 = 0x4d => "f/20",
+        F22 = 0x50 => "f/22",
+        F25 = 0x53 => "f/25",
+        F27Half = 0x54 => "f/27 (1/2)",
+        F29 = 0x55 => "f/29",
+        F32 = 0x58 => "f/32",
+        F36 = 0x5b => "f/36",
+        F38Half = 0x5c => "f/38 (1/2)",
+        F40 = 0x5d => "f/40",
+        F45 = 0x60 => "f/45",
+        F51 = 0x63 => "f/51",
+        F54Half = 0x64 => "f/54 (1/2)",
+        F57 = 0x65 => "f/57",
+        F64 = 0x68 => "f/64",
+        F72 = 0x6b => "f/72",
+        F76Half = 0x6c => "f/76 (1/2)",
+        F80 = 0x6d => "f/80",
+        F91 = 0x70 => "f/91",
+    }
+
+    /// Values of [`PropertyId::Tv`]. Variants ending in `Half` are half stop values.
+    ShutterSpeed {
+        Bulb = 0x0c => "Bulb",
+        Sec30 = 0x10 => "30\"",
+        Sec25 = 0x13 => "25\"",
+        Sec20Half = 0x14 => "20\" (1/2)",
+        Sec20 = 0x15 => "20\"",
+        Sec15 = 0x18 => "15\"",
+        Sec13 = 0x1b => "13\"",
+        Sec10Half = 0x1c => "10\" (1/2)",
+        Sec10 = 0x1d => "10\"",
+        Sec8 = 0x20 => "8\"",
+        Sec6 = 0x23 => "6\"",
+        Sec6Half = 0x24 => "6\" (1/2)",
+        Sec5 = 0x25 => "5\"",
+        Sec4 = 0x28 => "4\"",
+        Sec3_2 = 0x2b => "3\"2",
+        Sec3Half = 0x2c => "3\" (1/2)",
+        Sec2_5 = 0x2d => "2\"5",
+        Sec2 = 0x30 => "2\"",
+        Sec1_6 = 0x33 => "1\"6",
+        Sec1_5Half = 0x34 => "1\"5 (1/2)",
+        Sec1_3 = 0x35 => "1\"3",
+        Sec1 = 0x38 => "1\"",
+        Sec0_8 = 0x3b => "0\"8",
+        Sec0_7Half = 0x3c => "0\"7 (1/2)",
+        Sec0_6 = 0x3d => "0\"6",
+        Sec0_5 = 0x40 => "0\"5",
+        Sec0_4 = 0x43 => "0\"4",
+        Sec0_3Half = 0x44 => "0\"3 (1/2)",
+        Sec0_3 = 0x45 => "0\"3",
+        OneOver4 = 0x48 => "1/4",
+        OneOver5 = 0x4b => "1/5",
+        OneOver6Half = 0x4c => "1/6 (1/2)",
+        OneOver6 = 0x4d => "1/6",
+        OneOver8 = 0x50 => "1/8",
+        OneOver10 = 0x53 => "1/10",
+        OneOver10Half = 0x54 => "1/10 (1/2)",
+        OneOver13 = 0x55 => "1/13",
+        OneOver15 = 0x58 => "1/15",
+        OneOver20 = 0x5b => "1/20",
+        OneOver20Half = 0x5c => "1/20 (1/2)",
+        OneOver25 = 0x5d => "1/25",
+        OneOver30 = 0x60 => "1/30",
+        OneOver40 = 0x63 => "1/40",
+        OneOver45Half = 0x64 => "1/45 (1/2)",
+        OneOver50 = 0x65 => "1/50",
+        OneOver60 = 0x68 => "1/60",
+        OneOver80 = 0x6b => "1/80",
+        OneOver90Half = 0x6c => "1/90 (1/2)",
+        OneOver100 = 0x6d => "1/100",
+        OneOver125 = 0x70 => "1/125",
+        OneOver160 = 0x73 => "1/160",
+        OneOver180Half = 0x74 => "1/180 (1/2)",
+        OneOver200 = 0x75 => "1/200",
+        OneOver250 = 0x78 => "1/250",
+        OneOver320 = 0x7b => "1/320",
+        OneOver350Half = 0x7c => "1/350 (1/2)",
+        OneOver400 = 0x7d => "1/400",
+        OneOver500 = 0x80 => "1/500",
+        OneOver640 = 0x83 => "1/640",
+        OneOver750Half = 0x84 => "1/750 (1/2)",
+        OneOver800 = 0x85 => "1/800",
+        OneOver1000 = 0x88 => "1/1000",
+        OneOver1250 = 0x8b => "1/1250",
+        OneOver1500Half = 0x8c => "1/1500 (1/2)",
+        OneOver1600 = 0x8d => "1/1600",
+        OneOver2000 = 0x90 => "1/2000",
+        OneOver2500 = 0x93 => "1/2500",
+        OneOver3000Half = 0x94 => "1/3000 (1/2)",
+        OneOver3200 = 0x95 => "1/3200",
+        OneOver4000 = 0x98 => "1/4000",
+        OneOver5000 = 0x9b => "1/5000",
+        OneOver6000Half = 0x9c => "1/6000 (1/2)",
+        OneOver6400 = 0x9d => "1/6400",
+        OneOver8000 = 0xa0 => "1/8000",
+        OneOver10000 = 0xa3 => "1/10000",
+        OneOver12800 = 0xa5 => "1/12800",
+        OneOver16000 = 0xa8 => "1/16000",
+    }
+
+    /// Values of [`PropertyId::WhiteBalance`].
+    WhiteBalance {
+        Auto = 0 => "Auto (ambience priority)",
+        Daylight = 1 => "Daylight",
+        Cloudy = 2 => "Cloudy",
+        Tungsten = 3 => "Tungsten",
+        Fluorescent = 4 => "Fluorescent",
+        Flash = 5 => "Flash",
+        Manual = 6 => "Manual",
+        Shade = 8 => "Shade",
+        ColorTemperature = 9 => "Color temperature",
+        PcSet1 = 10 => "Custom PC-1",
+        PcSet2 = 11 => "Custom PC-2",
+        PcSet3 = 12 => "Custom PC-3",
+        Manual2 = 15 => "Manual 2",
+        Manual3 = 16 => "Manual 3",
+        Manual4 = 18 => "Manual 4",
+        Manual5 = 19 => "Manual 5",
+        PcSet4 = 20 => "Custom PC-4",
+        PcSet5 = 21 => "Custom PC-5",
+        AutoWhitePriority = 23 => "Auto (white priority)",
+        Click = 0xffffffff => "Click",
+        Pasted = 0xfffffffe => "Pasted",
+    }
+
+    /// Values of [`PropertyId::PictureStyle`].
+    PictureStyle {
+        Standard = 0x81 => "Standard",
+        Portrait = 0x82 => "Portrait",
+        Landscape = 0x83 => "Landscape",
+        Neutral = 0x84 => "Neutral",
+        Faithful = 0x85 => "Faithful",
+        Monochrome = 0x86 => "Monochrome",
+        Auto = 0x87 => "Auto",
+        FineDetail = 0x88 => "Fine detail",
+        User1 = 0x21 => "User defined 1",
+        User2 = 0x22 => "User defined 2",
+        User3 = 0x23 => "User defined 3",
+        PcSet1 = 0x41 => "Computer setting 1",
+        PcSet2 = 0x42 => "Computer setting 2",
+        PcSet3 = 0x43 => "Computer setting 3",
+    }
+
+    /// Values of [`PropertyId::AeMode`] and [`PropertyId::AeModeSelect`], the shooting mode and
+    /// the mode dial position.
+    AeMode {
+        Program = 0x00 => "P",
+        Tv = 0x01 => "Tv",
+        Av = 0x02 => "Av",
+        Manual = 0x03 => "M",
+        Bulb = 0x04 => "Bulb",
+        ADep = 0x05 => "A-DEP",
+        Dep = 0x06 => "DEP",
+        Custom = 0x07 => "C1",
+        Lock = 0x08 => "Lock",
+        Green = 0x09 => "Auto",
+        NightPortrait = 0x0a => "Night portrait",
+        Sports = 0x0b => "Sports",
+        Portrait = 0x0c => "Portrait",
+        Landscape = 0x0d => "Landscape",
+        Closeup = 0x0e => "Close-up",
+        FlashOff = 0x0f => "Flash off",
+        Custom2 = 0x10 => "C2",
+        Custom3 = 0x11 => "C3",
+        CreativeAuto = 0x13 => "Creative auto",
+        Movie = 0x14 => "Movie",
+        PhotoInMovie = 0x15 => "Photo in movie",
+        SceneIntelligentAuto = 0x16 => "Scene intelligent auto",
+        Scn = 0x19 => "SCN",
+        Flexible = 0x37 => "Fv",
+    }
+
+    /// Values of [`PropertyId::BatteryQuality`], how worn out the battery is.
+    BatteryQuality {
+        DegradedLow = 0x00 => "Heavily degraded",
+        DegradedHalf = 0x01 => "Degraded",
+        SlightDegradation = 0x02 => "Slightly degraded",
+        NoDegradation = 0x03 => "No degradation",
+    }
+}
+
+/// A property value decoded according to the property it belongs to.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum PropertyValue {
+    IsoSpeed(IsoSpeed),
+    Av(Aperture),
+    Tv(ShutterSpeed),
+    WhiteBalance(WhiteBalance),
+    PictureStyle(PictureStyle),
+    AeMode(AeMode),
+    BatteryQuality(BatteryQuality),
+
+    /// A value of a property without a known set of values.
+    Other(u32),
+}
+
+impl PropertyValue {
+    pub fn decode(property: PropertyId, code: u32) -> Self {
+        match property {
+            PropertyId::IsoSpeed => Self::IsoSpeed(IsoSpeed::from_code(code)),
+            PropertyId::Av => Self::Av(Aperture::from_code(code)),
+            PropertyId::Tv => Self::Tv(ShutterSpeed::from_code(code)),
+            PropertyId::WhiteBalance => Self::WhiteBalance(WhiteBalance::from_code(code)),
+            PropertyId::PictureStyle => Self::PictureStyle(PictureStyle::from_code(code)),
+            PropertyId::AeMode | PropertyId::AeModeSelect => Self::AeMode(AeMode::from_code(code)),
+            PropertyId::BatteryQuality => Self::BatteryQuality(BatteryQuality::from_code(code)),
+            _ => Self::Other(code),
+        }
+    }
+
+    /// The value of `property` whose [`Self::label`] is `label`, the reverse of [`Self::decode`]
+    /// and [`Self::label`].
+    pub fn from_label(property: PropertyId, label: &str) -> Result<Self, ParseValueError> {
+        Ok(match property {
+            PropertyId::IsoSpeed => Self::IsoSpeed(label.parse()?),
+            PropertyId::Av => Self::Av(label.parse()?),
+            PropertyId::Tv => Self::Tv(label.parse()?),
+            PropertyId::WhiteBalance => Self::WhiteBalance(label.parse()?),
+            PropertyId::PictureStyle => Self::PictureStyle(label.parse()?),
+            PropertyId::AeMode | PropertyId::AeModeSelect => Self::AeMode(label.parse()?),
+            PropertyId::BatteryQuality => Self::BatteryQuality(label.parse()?),
+            _ => Self::Other(label.parse().map_err(|_| ParseValueError {
+                label: label.to_string(),
+            })?),
+        })
+    }
+
+    pub fn code(&self) -> u32 {
+        match self {
+            Self::IsoSpeed(value) => value.code(),
+            Self::Av(value) => value.code(),
+            Self::Tv(value) => value.code(),
+            Self::WhiteBalance(value) => value.code(),
+            Self::PictureStyle(value) => value.code(),
+            Self::AeMode(value) => value.code(),
+            Self::BatteryQuality(value) => value.code(),
+            Self::Other(code) => *code,
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Self::IsoSpeed(value) => value.label(),
+            Self::Av(value) => value.label(),
+            Self::Tv(value) => value.label(),
+            Self::WhiteBalance(value) => value.label(),
+            Self::PictureStyle(value) => value.label(),
+            Self::AeMode(value) => value.label(),
+            Self::BatteryQuality(value) => value.label(),
+            Self::Other(code) => code.to_string(),
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use std::marker::PhantomData;
+
+use crate::{
+    directory::{children, collect_files},
+    handle::OwnedRef,
+    DirectoryEntry, DirectoryItem, EdsError, EdsFormatVolume, EdsGetVolumeInfo, EdsVolumeInfo,
+    EdsVolumeRef, Sdk,
+};
+
+/// A storage card of a camera, see [`crate::Camera::volumes`].
+pub struct Volume<'sdk> {
+    raw: OwnedRef,
+    _sdk: PhantomData<&'sdk Sdk>,
+}
+
+impl<'sdk> Volume<'sdk> {
+    /// # Safety
+    /// `raw` must be a valid volume reference that the caller owns.
+    pub unsafe fn from_raw(raw: EdsVolumeRef) -> Self {
+        Self {
+            raw: unsafe { OwnedRef::from_raw(raw) },
+            _sdk: PhantomData,
+        }
+    }
+
+    pub fn as_raw(&self) -> EdsVolumeRef {
+        self.raw.as_raw()
+    }
+
+    pub fn info(&self) -> Result<EdsVolumeInfo, EdsError> {
+        let mut info = EdsVolumeInfo::default();
+        unsafe { EdsGetVolumeInfo(self.as_raw(), &mut info).res() }?;
+        Ok(info)
+    }
+
+    /// The items in the root folder of the volume.
+    pub fn children(&self) -> Result<Vec<DirectoryItem<'sdk>>, EdsError> {
+        unsafe { children(self.as_raw()) }
+    }
+
+    /// Every file on the volume, folders are descended into but not returned.
+    pub fn files(&self) -> Result<Vec<DirectoryEntry<'sdk>>, EdsError> {
+        let mut files = Vec::new();
+        collect_files("", self.children()?, &mut files)?;
+        Ok(files)
+    }
+
+    /// Deletes everything on the volume.
+    pub fn format(&self) -> Result<(), EdsError> {
+        unsafe { EdsFormatVolume(self.as_raw()).res() }
+    }
+}
@@ -0,0 +1,240 @@
+//! Runs the safe API against the simulated SDK, `cargo test --features simulated`
+
+#![cfg(feature = "simulated")]
+
+use std::{
+    sync::{
+        mpsc::{self, Receiver},
+        Mutex, MutexGuard, OnceLock,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use edsdk::{
+    Camera, CameraCommand, EdsCapacity, EdsError, EdsEvfOutputDevice, EdsSaveTo, EvfImage,
+    ObjectEvent, PropertyId, PropertyValue, Sdk, ShutterButton, StateEvent, Stream,
+};
+
+/// Event handlers must be `'static`, so the SDK stays initialized for all tests
+static SDK: OnceLock<Sdk> = OnceLock::new();
+
+/// The simulated cameras are shared by every test, so they take turns
+static CAMERA: Mutex<()> = Mutex::new(());
+
+fn sdk() -> &'static Sdk {
+    SDK.get_or_init(|| Sdk::init().expect("Failed to initialize the simulated SDK"))
+}
+
+/// Opens a session on the first camera, the session is closed when the camera is dropped
+fn open_camera() -> (MutexGuard<'static, ()>, Camera<'static>) {
+    let guard = CAMERA.lock().unwrap_or_else(|err| err.into_inner());
+
+    let mut camera = sdk()
+        .camera_list()
+        .and_then(|camera_list| camera_list.first())
+        .expect("Failed to get the simulated camera");
+    camera.open_session().expect("Failed to open a session");
+
+    (guard, camera)
+}
+
+/// Pumps events until `receiver` gets something
+fn wait_for<T>(receiver: &Receiver<T>) -> T {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    loop {
+        sdk().get_event().expect("Failed to get events");
+
+        if let Ok(value) = receiver.try_recv() {
+            return value;
+        }
+        assert!(Instant::now() < deadline, "No event arrived");
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+fn is_jpeg(data: &[u8]) -> bool {
+    data.starts_with(&[0xff, 0xd8]) && data.ends_with(&[0xff, 0xd9])
+}
+
+#[test]
+fn opens_and_closes_sessions() {
+    let (_guard, mut camera) = open_camera();
+    assert!(camera.is_session_open());
+
+    let description = camera.describe().unwrap();
+    assert_eq!(description.port_name, "simulated:0");
+
+    camera.close_session().unwrap();
+    assert!(!camera.is_session_open());
+    assert_eq!(
+        camera.set_property(PropertyId::IsoSpeed, &0x48u32),
+        Err(EdsError::SessionNotOpen)
+    );
+
+    camera.open_session().unwrap();
+    assert!(camera.is_session_open());
+}
+
+#[test]
+fn round_trips_properties() {
+    let (_guard, camera) = open_camera();
+
+    let values = camera.available_values(PropertyId::IsoSpeed).unwrap();
+    let value = *values.last().expect("No ISO values available");
+    let original = camera.get_property::<u32>(PropertyId::IsoSpeed).unwrap();
+
+    camera
+        .set_property(PropertyId::IsoSpeed, &value.code())
+        .unwrap();
+    assert_eq!(
+        camera.get_property::<u32>(PropertyId::IsoSpeed).unwrap(),
+        value.code()
+    );
+    assert_eq!(
+        PropertyValue::from_label(PropertyId::IsoSpeed, &value.label()),
+        Ok(value)
+    );
+
+    // values the camera doesn't offer are refused
+    assert_eq!(
+        camera.set_property(PropertyId::IsoSpeed, &0xffu32),
+        Err(EdsError::InvalidDevicePropValue)
+    );
+
+    camera
+        .set_property(PropertyId::IsoSpeed, &original)
+        .unwrap();
+}
+
+#[test]
+fn transfers_pictures_to_the_host() {
+    let (_guard, camera) = open_camera();
+
+    camera
+        .set_property(PropertyId::SaveTo, &EdsSaveTo::Host)
+        .unwrap();
+    camera
+        .set_capacity(EdsCapacity {
+            number_of_free_clusters: 0x7FFFFFFF,
+            bytes_per_sector: 0x1000,
+            reset: 1,
+        })
+        .unwrap();
+
+    let (sender, receiver) = mpsc::channel();
+    camera
+        .on_object_event(move |event, item| {
+            if let (ObjectEvent::DirItemRequestTransfer, Some(item)) = (event, item) {
+                let jpeg = item.download_to_vec(sdk());
+                item.download_complete().unwrap();
+                let _ = sender.send(jpeg);
+            }
+        })
+        .unwrap();
+
+    camera
+        .send_command(CameraCommand::PressShutterButton(ShutterButton::Completely))
+        .unwrap();
+    camera
+        .send_command(CameraCommand::PressShutterButton(ShutterButton::Off))
+        .unwrap();
+
+    let jpeg = wait_for(&receiver).expect("Failed to download the picture");
+    assert!(is_jpeg(&jpeg));
+
+    camera
+        .set_property(PropertyId::SaveTo, &EdsSaveTo::Camera)
+        .unwrap();
+}
+
+#[test]
+fn downloads_live_view_frames() {
+    let (_guard, camera) = open_camera();
+
+    let stream = Stream::create_memory(sdk(), 0).unwrap();
+    let evf_image = EvfImage::new(&stream).unwrap();
+
+    // nothing to download until the PC is an output device
+    assert_eq!(
+        camera.download_evf_image(&evf_image),
+        Err(EdsError::ObjectNotReady)
+    );
+
+    let output_device = camera
+        .get_property::<EdsEvfOutputDevice>(PropertyId::EvfOutputDevice)
+        .unwrap();
+    camera
+        .set_property(
+            PropertyId::EvfOutputDevice,
+            &(output_device | EdsEvfOutputDevice::PC),
+        )
+        .unwrap();
+
+    camera.download_evf_image(&evf_image).unwrap();
+    assert!(is_jpeg(&stream.to_vec().unwrap()));
+    assert!(evf_image.histogram().is_ok());
+
+    camera
+        .set_property(PropertyId::EvfOutputDevice, &output_device)
+        .unwrap();
+}
+
+#[test]
+fn shuts_down_after_auto_power_off() {
+    let (_guard, mut camera) = open_camera();
+
+    let original = camera
+        .get_property::<u32>(PropertyId::AutoPowerOffSetting)
+        .unwrap();
+    camera
+        .set_property(PropertyId::AutoPowerOffSetting, &1u32)
+        .unwrap();
+
+    let (sender, receiver) = mpsc::channel();
+    camera
+        .on_state_event(move |event| {
+            if event == StateEvent::Shutdown {
+                let _ = sender.send(());
+            }
+        })
+        .unwrap();
+
+    wait_for(&receiver);
+    assert_eq!(
+        camera.send_command(CameraCommand::ExtendShutDownTimer),
+        Err(EdsError::SessionNotOpen)
+    );
+
+    // the camera already closed the session on its end, it's back once a new one is opened
+    let _ = camera.close_session();
+    camera.open_session().unwrap();
+    camera
+        .set_property(PropertyId::AutoPowerOffSetting, &original)
+        .unwrap();
+}
+
+#[test]
+fn finds_cameras_by_body_id() {
+    let _guard = CAMERA.lock().unwrap_or_else(|err| err.into_inner());
+    let camera_list = sdk().camera_list().unwrap();
+
+    let body_id = camera_list.describe().unwrap()[0]
+        .body_id
+        .clone()
+        .expect("The simulated camera has no body ID");
+
+    let camera = camera_list.find_by_body_id(&body_id).unwrap();
+    assert!(camera.is_session_open());
+
+    assert_eq!(
+        camera_list.find_by_body_id("not a serial").err(),
+        Some(EdsError::DeviceNotFound)
+    );
+}
+
+#[test]
+fn initializes_a_single_sdk() {
+    sdk();
+    assert_eq!(Sdk::init().err(), Some(EdsError::InternalError));
+}
@@ -0,0 +1,117 @@
+use std::time::Duration;
+
+use tauri::async_runtime::Sender;
+
+use crate::{
+    camera::{
+        CameraEvent, CameraRef, CameraSetting, CameraStatus, CaptureError, CaptureUpdate, CardFile,
+        LiveViewFrame, SettingValue,
+    },
+    config::{Backend, Config},
+    replay::ReplayCamera,
+};
+
+#[cfg(feature = "gphoto2")]
+use crate::gphoto2::Gphoto2Camera;
+
+/// Errors of a backend, only ever shown to the user
+pub type BackendError = Box<dyn std::error::Error + Send + Sync>;
+
+/// How often the shutter is released for a single photo while the camera reports errors that are
+/// likely to pass, like being busy or charging the flash
+pub const MAX_CAPTURE_ATTEMPTS: u32 = 3;
+
+/// How long to wait before releasing the shutter again
+pub const CAPTURE_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Tells the photos apart, handed out by the camera thread
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CaptureId(pub u64);
+
+/// A camera the booth can take photos with. Every method is called on the camera thread, see
+/// [`crate::camera_thread::CameraHandle`]
+pub trait CameraBackend {
+    /// Releases the shutter once, the photo is reported to `respond_to` once it's downloaded.
+    /// Retrying is up to the camera thread, it must not be waited for here
+    fn take_picture(
+        &mut self,
+        id: CaptureId,
+        respond_to: Sender<CaptureUpdate>,
+    ) -> Result<(), CaptureError>;
+
+    /// Gives up on the photo `id`, it timed out. The photos taken after it are still expected
+    fn abandon_capture(&mut self, _id: CaptureId) {}
+
+    /// The photos that failed after [`Self::take_picture`] returned, they aren't answered by the
+    /// backend, so the camera thread can release the shutter for them again
+    fn take_failed_captures(&mut self) -> Vec<(CaptureId, CaptureError)> {
+        Vec::new()
+    }
+
+    /// The photos the backend answered since the last call, the camera thread stops waiting for
+    /// them
+    fn take_completed_captures(&mut self) -> Vec<CaptureId>;
+
+    /// Whether a photo was taken and its transfer is still outstanding
+    fn is_capturing(&self) -> bool;
+
+    /// Handles what the camera reported since the last call, returns the events for the frontend
+    fn poll_events(&mut self) -> Result<Vec<CameraEvent>, BackendError>;
+
+    /// Resets the camera's auto power-off timer
+    fn keep_alive(&mut self) -> Result<(), BackendError> {
+        Ok(())
+    }
+
+    /// Fetches the current live view frame along with its metadata
+    fn live_view_frame(&mut self) -> Result<LiveViewFrame, BackendError>;
+
+    /// Reads the battery, storage and mode
+    fn status(&self) -> CameraStatus;
+
+    /// The values `setting` can currently be set to, these depend on the mode and lens
+    fn available_values(&self, setting: CameraSetting) -> Result<Vec<SettingValue>, BackendError>;
+
+    /// Sets `setting` to `code`, one of [`Self::available_values`]
+    fn set_setting(&mut self, setting: CameraSetting, code: u32) -> Result<(), BackendError>;
+
+    /// Lists the files on all cards, including shots that were never transferred to the host
+    fn list_card_files(&self) -> Result<Vec<CardFile>, BackendError> {
+        Err(NOT_SUPPORTED.into())
+    }
+
+    /// Downloads a file from the card into the camera photo directory, returns the file name
+    fn download_card_file(&mut self, _file: &CardFile) -> Result<String, BackendError> {
+        Err(NOT_SUPPORTED.into())
+    }
+
+    fn delete_card_file(&mut self, _file: &CardFile) -> Result<(), BackendError> {
+        Err(NOT_SUPPORTED.into())
+    }
+
+    /// Deletes everything on all cards
+    fn format_cards(&mut self) -> Result<(), BackendError> {
+        Err(NOT_SUPPORTED.into())
+    }
+}
+
+const NOT_SUPPORTED: &str = "Not supported by this camera";
+
+/// Opens the camera chosen in `config`
+pub fn open(
+    enable_live_view: bool,
+    config: &Config,
+) -> Result<Box<dyn CameraBackend>, BackendError> {
+    Ok(match &config.backend {
+        Backend::Edsdk => Box::new(CameraRef::init(enable_live_view, config)?),
+        Backend::Replay { path } => Box::new(ReplayCamera::open(path)?),
+        #[cfg(feature = "gphoto2")]
+        Backend::Gphoto2 { model, port } => {
+            Box::new(Gphoto2Camera::open(model.as_deref(), port.as_deref())?)
+        }
+        #[cfg(not(feature = "gphoto2"))]
+        Backend::Gphoto2 { .. } => {
+            return Err("This build has no gphoto2 backend, enable the gphoto2 feature".into())
+        }
+    })
+}
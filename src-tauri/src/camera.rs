@@ -1,118 +1,442 @@
 use std::{
-    ffi::CString,
-    // os::unix::ffi::OsStrExt,
-    ptr::null_mut,
-    sync::{LazyLock, Mutex},
-    thread,
-    time::{Duration, SystemTime},
+    collections::VecDeque,
+    fmt, fs,
+    path::Path,
+    sync::{atomic::AtomicBool, Arc, LazyLock, Mutex, OnceLock},
+    time::SystemTime,
 };
 
 use chrono::{DateTime, Local};
 use edsdk::{
-    EdsBaseRef, EdsCameraListRef, EdsCameraRef, EdsCapacity, EdsCloseSession, EdsCreateEvfImageRef,
-    EdsCreateFileStream, EdsDeviceInfo, EdsDirectoryItemInfo, EdsDirectoryItemRef, EdsDownload,
-    EdsDownloadComplete, EdsDownloadEvfImage, EdsError, EdsEvfImageRef, EdsEvfOutputDevice,
-    EdsGetCameraList, EdsGetChildAtIndex, EdsGetChildCount, EdsGetDeviceInfo,
-    EdsGetDirectoryItemInfo, EdsGetEvent, EdsGetPropertyData, EdsImageQuality, EdsInitializeSDK,
-    EdsObjectEvent, EdsOpenSession, EdsRelease, EdsSaveTo, EdsSendCommand, EdsSetCapacity,
-    EdsSetObjectEventHandler, EdsSetPropertyData, EdsStreamRef, EdsTerminateSDK, EdsVoid,
+    Camera, CameraCommand, DirectoryItem, EdsAccess, EdsCapacity, EdsError, EdsEvfOutputDevice,
+    EdsFileCreateDisposition, EdsImageSource, EdsRect, EdsSaveTo, EvfImage, FocusInfo, Histogram,
+    Image, ObjectEvent, PropertyEvent, PropertyId, PropertyValue, Sdk, ShutterButton, StateEvent,
+    Stream,
 };
-use tauri::{async_runtime::Sender, AppHandle, Emitter};
+use jpeg_encoder::{ColorType, Encoder};
+use serde::{Deserialize, Serialize};
+use tauri::async_runtime::Sender;
 
 use crate::{
-    path::{CAMERA_PHOTO_DIR, EVF_IMAGE},
-    CAMERA,
+    backend::{BackendError, CameraBackend, CaptureId},
+    config::Config,
+    path::{CAMERA_PHOTO_DIR, UNSOLICITED_PHOTO_DIR},
 };
 
-/// Global channel to receive the filename from the callback
-static PHOTO_RESULT_SENDER: LazyLock<Mutex<Option<Sender<Result<String, String>>>>> =
+/// The SDK stays initialized for as long as the app is running
+static SDK: OnceLock<Sdk> = OnceLock::new();
+
+/// Only a single `Sdk` can exist, so threads take turns initializing it
+static SDK_INIT: Mutex<()> = Mutex::new(());
+
+/// The SDK, initialized on first use. A failed initialization is tried again on the next call
+pub fn sdk() -> Result<&'static Sdk, EdsError> {
+    if let Some(sdk) = SDK.get() {
+        return Ok(sdk);
+    }
+
+    let _init = SDK_INIT.lock().unwrap_or_else(|err| err.into_inner());
+    if let Some(sdk) = SDK.get() {
+        return Ok(sdk);
+    }
+    let sdk = Sdk::init()?;
+    Ok(SDK.get_or_init(|| sdk))
+}
+
+type PhotoResult = Arc<Mutex<Captures>>;
+
+/// The photos the event handlers answer
+#[derive(Default)]
+struct Captures {
+    /// In the order the shutter was released, the photos and errors arrive in that order as well
+    pending: VecDeque<PendingCapture>,
+    /// Photos the camera reported an error for, the camera thread decides whether to retry them
+    failed: Vec<(CaptureId, CaptureError)>,
+    /// Photos the event handlers answered, the camera thread stops waiting for them
+    completed: Vec<CaptureId>,
+}
+
+/// A photo that was asked for but didn't arrive yet
+struct PendingCapture {
+    id: CaptureId,
+    respond_to: Sender<CaptureUpdate>,
+}
+
+/// The longest side of the capture preview in pixels
+const PREVIEW_SIZE: u32 = 640;
+
+/// The thumbnail of the latest photo as jpeg, the frontend fetches it through the
+/// `get_capture_preview` command
+pub static CAPTURE_PREVIEW: LazyLock<Mutex<Option<Vec<u8>>>> = LazyLock::new(|| Mutex::new(None));
+
+/// What `take_photo` hears about the photo it's waiting for
+pub enum CaptureUpdate {
+    /// The thumbnail is in [`CAPTURE_PREVIEW`], the photo itself is still being downloaded
+    Preview,
+    /// The percentage of the photo downloaded so far
+    Progress(u32),
+    /// The file name of the photo, or why taking it failed
+    Done(Result<String, CaptureError>),
+}
+
+/// Why a photo couldn't be taken, the frontend tells the guests what to do based on `kind`
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum CaptureError {
+    /// The camera couldn't focus, e.g. because nobody stands in front of it
+    FocusFailed,
+    /// The camera is still busy, e.g. writing the previous photo
+    Busy,
+    /// No photo arrived within the capture timeout
+    Timeout,
+    /// There is no camera, or it went away before the photo arrived
+    Disconnected,
+    /// The photo was taken but couldn't be saved
+    DownloadFailed {
+        message: String,
+    },
+    Failed {
+        message: String,
+    },
+}
+
+impl fmt::Display for CaptureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FocusFailed => write!(f, "The camera couldn't focus"),
+            Self::Busy => write!(f, "The camera is busy"),
+            Self::Timeout => write!(f, "No photo arrived in time"),
+            Self::Disconnected => write!(f, "No camera connected"),
+            Self::DownloadFailed { message } => write!(f, "Failed to download: {}", message),
+            Self::Failed { message } => write!(f, "Failed to take picture: {}", message),
+        }
+    }
+}
+
+impl CaptureError {
+    /// Releasing the shutter again a moment later is likely to work
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::Busy)
+    }
+}
+
+impl std::error::Error for CaptureError {}
+
+impl From<EdsError> for CaptureError {
+    fn from(err: EdsError) -> Self {
+        match err {
+            EdsError::TakePictureAfNg => Self::FocusFailed,
+            err if err.is_retryable() => Self::Busy,
+            err if err.is_disconnected() => Self::Disconnected,
+            err => Self::Failed {
+                message: err.to_string(),
+            },
+        }
+    }
+}
+
+/// Whether the camera thread currently has a camera, `camera-connected` and
+/// `camera-disconnected` are emitted when this changes
+pub static CAMERA_CONNECTED: AtomicBool = AtomicBool::new(false);
+
+/// The latest camera status, refreshed by the camera thread
+pub static CAMERA_STATUS: LazyLock<Mutex<Option<CameraStatus>>> =
     LazyLock::new(|| Mutex::new(None));
 
-pub struct CameraRef {
-    camera: EdsCameraRef,
+/// Battery levels at or below this percentage are reported as low
+pub const LOW_BATTERY_LEVEL: u32 = 20;
+
+/// `BatteryLevel` when the camera runs on AC power
+const BATTERY_LEVEL_AC: u32 = 0xffffffff;
+
+/// The latest live view frame, the jpeg is fetched by the frontend through the `get_evf_frame`
+/// command
+pub static EVF_FRAME: LazyLock<Mutex<Option<LiveViewFrame>>> = LazyLock::new(|| Mutex::new(None));
+
+/// Camera events forwarded to the frontend, the payload is identified by the event name
+#[derive(Clone, Debug, Serialize)]
+#[serde(untagged)]
+pub enum CameraEvent {
+    Shutdown,
+    WillSoonShutDown {
+        seconds: u32,
+    },
+    BulbExposureTime {
+        seconds: u32,
+    },
+    PropertyChanged {
+        property: String,
+        param: u32,
+    },
+    /// A photo nobody asked for, saved in the unsolicited photo directory
+    UnsolicitedPhoto {
+        #[serde(rename = "fileName")]
+        file_name: String,
+    },
 }
 
-impl From<EdsCameraRef> for CameraRef {
-    fn from(camera: EdsCameraRef) -> Self {
-        Self { camera }
+impl CameraEvent {
+    pub fn event_name(&self) -> &'static str {
+        match self {
+            Self::Shutdown => "camera-shutdown",
+            Self::WillSoonShutDown { .. } => "camera-will-soon-shut-down",
+            Self::BulbExposureTime { .. } => "camera-bulb-exposure-time",
+            Self::PropertyChanged { .. } => "camera-property-changed",
+            Self::UnsolicitedPhoto { .. } => "camera-unsolicited-photo",
+        }
     }
 }
 
-impl CameraRef {
-    pub fn init(enable_live_view: bool) -> Result<Self, EdsError> {
-        unsafe { Self::_init(enable_live_view) }
+/// Camera settings the frontend can query the available values of
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CameraSetting {
+    Iso,
+    Av,
+    Tv,
+    WhiteBalance,
+    PictureStyle,
+}
+
+impl From<CameraSetting> for PropertyId {
+    fn from(setting: CameraSetting) -> Self {
+        match setting {
+            CameraSetting::Iso => PropertyId::IsoSpeed,
+            CameraSetting::Av => PropertyId::Av,
+            CameraSetting::Tv => PropertyId::Tv,
+            CameraSetting::WhiteBalance => PropertyId::WhiteBalance,
+            CameraSetting::PictureStyle => PropertyId::PictureStyle,
+        }
     }
+}
 
-    unsafe fn _init(enable_live_view: bool) -> Result<Self, EdsError> {
-        unsafe { EdsInitializeSDK().res() }?;
+/// A value a camera setting can be set to, `code` is the raw property value
+#[derive(Clone, Debug, Serialize)]
+pub struct SettingValue {
+    pub code: u32,
+    pub label: String,
+}
 
-        let mut camera_list = null_mut() as EdsCameraListRef;
-        unsafe { EdsGetCameraList(&mut camera_list).res() }?;
+impl From<PropertyValue> for SettingValue {
+    fn from(value: PropertyValue) -> Self {
+        Self {
+            code: value.code(),
+            label: value.label(),
+        }
+    }
+}
 
-        println!("camera list: {:?}, getting child count", camera_list);
+/// A file on one of the camera's cards, `path` is relative to the root of `volume`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CardFile {
+    pub volume: String,
+    pub path: String,
+    pub size: u64,
+}
 
-        let mut num_cameras = 0;
-        unsafe { EdsGetChildCount(camera_list, &mut num_cameras).res() }?;
+/// A rect in the coordinate system of the live view, see [`LiveViewFrame::coordinate_system`]
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
 
-        println!("Found {} cameras", num_cameras);
+impl From<EdsRect> for Rect {
+    fn from(rect: EdsRect) -> Self {
+        Self {
+            x: rect.point.x,
+            y: rect.point.y,
+            width: rect.size.width,
+            height: rect.size.height,
+        }
+    }
+}
 
-        if num_cameras == 0 {
-            println!("No cameras found.");
-            return Err(EdsError::NoCameraFound);
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct Size {
+    pub width: i32,
+    pub height: i32,
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AfFrame {
+    pub rect: Rect,
+    pub selected: bool,
+    pub in_focus: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveViewHistogram {
+    pub y: Vec<u32>,
+    pub r: Vec<u32>,
+    pub g: Vec<u32>,
+    pub b: Vec<u32>,
+    pub mean_luminance: Option<f64>,
+    pub clipped_highlights: Option<f64>,
+}
+
+impl From<Histogram> for LiveViewHistogram {
+    fn from(histogram: Histogram) -> Self {
+        Self {
+            mean_luminance: histogram.mean_luminance(),
+            clipped_highlights: histogram.clipped_highlights(),
+            y: histogram.y,
+            r: histogram.r,
+            g: histogram.g,
+            b: histogram.b,
         }
+    }
+}
 
-        println!("Using first camera");
+/// A live view frame and what the camera reports about it. Metadata the camera doesn't support is
+/// left out, the jpeg isn't serialized and is fetched through the `get_evf_frame` command instead
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveViewFrame {
+    #[serde(skip)]
+    pub jpeg: Vec<u8>,
+    pub histogram: Option<LiveViewHistogram>,
+    pub zoom: Option<u32>,
+    pub zoom_rect: Option<Rect>,
+    /// Where the frame is located on the sensor
+    pub image_position: Option<Point>,
+    /// The size of the sensor area all rects are relative to
+    pub coordinate_system: Option<Size>,
+    pub af_frames: Vec<AfFrame>,
+}
 
-        let mut camera = null_mut() as EdsBaseRef;
-        unsafe { EdsGetChildAtIndex(camera_list, 0, &mut camera).res() }?;
+/// What an operator needs to know to keep the booth running. Values the camera doesn't report are
+/// left out
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CameraStatus {
+    /// In percent, left out on AC power
+    pub battery_level: Option<u32>,
+    pub ac_power: bool,
+    pub low_battery: bool,
+    /// How worn out the battery is, e.g. "Slightly degraded"
+    pub battery_quality: Option<String>,
+    /// Free bytes on all cards
+    pub free_space: Option<u64>,
+    /// How many more photos fit on the cards at the current image quality
+    pub available_shots: Option<u32>,
+    pub lens_attached: Option<bool>,
+    /// The mode dial position, e.g. "M"
+    pub mode: Option<String>,
+}
 
-        let mut device_info = EdsDeviceInfo::default();
-        unsafe { EdsGetDeviceInfo(camera, &mut device_info).res() }?;
+/// A Canon camera talked to through the EDSDK, along with its event handlers
+pub struct CameraRef {
+    camera: Camera<'static>,
+    photo_result: PhotoResult,
+    /// Camera events collected by the event handlers, emitted by the camera thread
+    pending_events: Arc<Mutex<Vec<CameraEvent>>>,
+}
 
-        println!("Description: {}", device_info.sz_device_description());
+impl CameraRef {
+    pub fn init(enable_live_view: bool, config: &Config) -> Result<Self, EdsError> {
+        let camera_list = sdk()?.camera_list()?;
+
+        // describing reads the body IDs, the camera is picked from the same descriptions
+        let cameras = camera_list.describe_cameras()?;
+        println!("Found {} cameras", cameras.len());
+
+        if cameras.is_empty() {
+            println!("No cameras found.");
+            return Err(EdsError::NoCameraFound);
+        }
+
+        for (index, (_, description)) in cameras.iter().enumerate() {
+            println!(
+                "{}: {} ({}), body ID {}, firmware {}",
+                index,
+                description
+                    .product_name
+                    .as_ref()
+                    .unwrap_or(&description.description),
+                description.port_name,
+                description.body_id.as_deref().unwrap_or("unknown"),
+                description.firmware_version.as_deref().unwrap_or("unknown")
+            );
+        }
+
+        let mut camera = match &config.camera_serial {
+            Some(serial) => {
+                println!("Using camera {}", serial);
+                cameras
+                    .into_iter()
+                    .find(|(_, description)| {
+                        description.body_id.as_deref().map(str::trim) == Some(serial.trim())
+                    })
+                    .ok_or(EdsError::DeviceNotFound)?
+                    .0
+            }
+            None => {
+                println!("Using first camera");
+                cameras.into_iter().next().ok_or(EdsError::NoCameraFound)?.0
+            }
+        };
 
         println!("opening session");
-        unsafe { EdsOpenSession(camera).res() }?;
+        camera.open_session()?;
 
-        unsafe { EdsSetObjectEventHandler(camera, 0x200, event_handler, 0 as *mut EdsVoid).res() }?;
+        let photo_result = PhotoResult::default();
+        let pending_events = Arc::new(Mutex::new(Vec::new()));
 
-        println!("save to size: {}", std::mem::size_of::<EdsSaveTo>());
+        camera.on_object_event({
+            let photo_result = photo_result.clone();
+            let pending_events = pending_events.clone();
+            move |event, item| handle_object_event(&photo_result, &pending_events, event, item)
+        })?;
+        camera.on_property_event({
+            let pending_events = pending_events.clone();
+            move |event| handle_property_event(&pending_events, event)
+        })?;
+        camera.on_state_event({
+            let photo_result = photo_result.clone();
+            let pending_events = pending_events.clone();
+            move |event| handle_state_event(&photo_result, &pending_events, event)
+        })?;
 
         println!("setting save to");
-        let save_to = EdsSaveTo::Host;
-        unsafe {
-            EdsSetPropertyData(
-                camera,
-                0xb,
-                0,
-                std::mem::size_of::<EdsSaveTo>() as u32,
-                &save_to as *const EdsSaveTo as *const EdsVoid,
-            )
-            .res()
-        }?;
+        camera.set_property(PropertyId::SaveTo, &EdsSaveTo::Host)?;
 
         println!("setting capacity");
-        let capacity = EdsCapacity {
+        camera.set_capacity(EdsCapacity {
             number_of_free_clusters: 0x7FFFFFFF,
             bytes_per_sector: 0x1000,
             reset: 1,
-        };
-        unsafe { EdsSetCapacity(camera, capacity).res() }?;
-
-        println!("setting image quality");
-        let quality = EdsImageQuality::JpegSmall;
-        unsafe {
-            EdsSetPropertyData(
-                camera,
-                0x100,
-                0,
-                std::mem::size_of::<EdsImageQuality>() as u32,
-                &quality as *const EdsImageQuality as *const EdsVoid,
-            )
-            .res()
-        }?;
+        })?;
+
+        println!(
+            "setting image quality to {}",
+            config.image_quality.description()
+        );
+        camera.set_property(PropertyId::ImageQuality, &config.image_quality)?;
 
-        let camera: CameraRef = camera.into();
+        if let Some(seconds) = config.auto_power_off {
+            println!("setting auto power off to {} seconds", seconds);
+            // not every body allows this over USB, the keep-alive works regardless
+            if let Err(err) = camera.set_property(PropertyId::AutoPowerOffSetting, &seconds) {
+                println!("Failed to set auto power off: {}", err);
+            }
+        }
+
+        let camera = CameraRef {
+            camera,
+            photo_result,
+            pending_events,
+        };
 
         if enable_live_view {
             camera.enable_evf_live_view()?;
@@ -121,235 +445,640 @@ impl CameraRef {
         Ok(camera)
     }
 
-    pub fn take_picture(&self, respond_to: Sender<Result<String, String>>) {
-        let camera = self.camera;
+    fn send_command(&self, command: CameraCommand) -> Result<(), EdsError> {
+        self.camera.send_command(command)
+    }
+
+    fn release_shutter(&self) -> Result<(), EdsError> {
+        let res = self.send_command(CameraCommand::PressShutterButton(ShutterButton::Completely));
+        let _ = self.send_command(CameraCommand::PressShutterButton(ShutterButton::Off));
+        res
+    }
+
+    fn find_card_file(&self, file: &CardFile) -> Result<DirectoryItem<'static>, EdsError> {
+        for volume in self.camera.volumes()? {
+            if volume.info()?.sz_volume_label() != file.volume {
+                continue;
+            }
 
-        *PHOTO_RESULT_SENDER.lock().unwrap() = Some(respond_to);
+            if let Some(entry) = volume
+                .files()?
+                .into_iter()
+                .find(|entry| entry.path == file.path)
+            {
+                return Ok(entry.item);
+            }
+        }
 
-        let err = unsafe { EdsSendCommand(camera, 4, 3) }; // 4 = shutter command, 3 = shutter completely
-        unsafe { EdsSendCommand(camera, 4, 0) }; // 0 = shutter off
-        assert!(err.is_ok());
+        Err(EdsError::FileNotFound)
     }
 
-    pub fn get_evf_image(&self) -> Result<(), EdsError> {
-        let camera = self.camera;
+    pub fn enable_evf_live_view(&self) -> Result<(), EdsError> {
+        let mut current_live_view = self.get_evf_output_device()?;
+        current_live_view.insert(EdsEvfOutputDevice::PC);
 
-        // open file stream
-        let mut stream = null_mut() as EdsStreamRef;
-        let file_name = CString::new(EVF_IMAGE.get().unwrap().as_os_str().as_encoded_bytes()).unwrap();
-        unsafe {
-            EdsCreateFileStream(
-                file_name.as_ptr(),
-                edsdk::EdsFileCreateDisposition::CreateAlways,
-                edsdk::EdsAccess::ReadWrite,
-                &mut stream,
-            )
-            .res()
-        }?;
-        /*
-        unsafe {
-            EdsCreateMemoryStreamFromPointer(
-                self.evf_buffer.as_mut_ptr() as *mut EdsVoid,
-                self.evf_buffer.len() as u64,
-                &mut stream,
-            )
-            .res()
-        }?;*/
+        self.set_evf_output_device(current_live_view)?;
 
-        // get evf image ref
-        let mut evf_image = null_mut() as EdsEvfImageRef;
-        unsafe { EdsCreateEvfImageRef(stream, &mut evf_image).res() }?;
+        Ok(())
+    }
 
-        // download evf image
-        unsafe { EdsDownloadEvfImage(camera, evf_image).res() }?;
+    fn set_evf_output_device(&self, evf_output: EdsEvfOutputDevice) -> Result<(), EdsError> {
+        self.camera
+            .set_property(PropertyId::EvfOutputDevice, &evf_output)
+    }
 
-        // release file stream
-        unsafe { EdsRelease(stream).res() }?;
+    fn get_evf_output_device(&self) -> Result<EdsEvfOutputDevice, EdsError> {
+        self.camera.get_property(PropertyId::EvfOutputDevice)
+    }
+}
 
-        // relase evf image
-        unsafe { EdsRelease(evf_image).res() }?;
+impl CameraBackend for CameraRef {
+    /// Releases the shutter, the object event handler reports the photo to `respond_to`. It's
+    /// queued first, the SDK may deliver the photo before the command returns
+    fn take_picture(
+        &mut self,
+        id: CaptureId,
+        respond_to: Sender<CaptureUpdate>,
+    ) -> Result<(), CaptureError> {
+        self.photo_result
+            .lock()
+            .unwrap()
+            .pending
+            .push_back(PendingCapture { id, respond_to });
 
+        if let Err(err) = self.release_shutter() {
+            // no photo is coming, unless the event handlers answered it already
+            let mut photo_result = self.photo_result.lock().unwrap();
+            if let Some(index) = photo_result
+                .pending
+                .iter()
+                .position(|pending| pending.id == id)
+            {
+                photo_result.pending.remove(index);
+            }
+            return Err(err.into());
+        }
         Ok(())
     }
 
-    pub fn enable_evf_live_view(&self) -> Result<(), EdsError> {
-        let mut current_live_view = Self::get_evf_output_device(self.camera)?;
-        current_live_view.insert(EdsEvfOutputDevice::PC);
+    /// The photo counts as unsolicited if it arrives later
+    fn abandon_capture(&mut self, id: CaptureId) {
+        self.photo_result
+            .lock()
+            .unwrap()
+            .pending
+            .retain(|pending| pending.id != id);
+    }
 
-        Self::set_evf_output_device(self.camera, current_live_view)?;
+    fn take_failed_captures(&mut self) -> Vec<(CaptureId, CaptureError)> {
+        std::mem::take(&mut self.photo_result.lock().unwrap().failed)
+    }
 
-        Ok(())
+    fn take_completed_captures(&mut self) -> Vec<CaptureId> {
+        std::mem::take(&mut self.photo_result.lock().unwrap().completed)
     }
 
-    fn set_evf_output_device(
-        camera: EdsCameraRef,
-        evf_output: EdsEvfOutputDevice,
-    ) -> Result<(), EdsError> {
-        unsafe {
-            EdsSetPropertyData(
-                camera,
-                0x500,
-                0,
-                std::mem::size_of::<EdsEvfOutputDevice>() as u32,
-                &evf_output as *const EdsEvfOutputDevice as *const EdsVoid,
-            )
-            .res()
-        }?;
-        Ok(())
+    fn is_capturing(&self) -> bool {
+        self.photo_result
+            .try_lock()
+            .map_or(true, |photo_result| !photo_result.pending.is_empty())
     }
 
-    fn get_evf_output_device(camera: EdsCameraRef) -> Result<EdsEvfOutputDevice, EdsError> {
-        let mut output_device = EdsEvfOutputDevice::default();
-        unsafe {
-            EdsGetPropertyData(
-                camera,
-                0x500,
-                0,
-                std::mem::size_of::<EdsEvfOutputDevice>() as u32,
-                &mut output_device as *mut EdsEvfOutputDevice as *mut EdsVoid,
-            )
-            .res()
-        }?;
-        Ok(output_device)
+    /// Pumps the SDK events, the event handlers collect the camera events
+    fn poll_events(&mut self) -> Result<Vec<CameraEvent>, BackendError> {
+        sdk()?.get_event()?;
+        Ok(std::mem::take(&mut *self.pending_events.lock().unwrap()))
+    }
+
+    fn keep_alive(&mut self) -> Result<(), BackendError> {
+        Ok(self.send_command(CameraCommand::ExtendShutDownTimer)?)
+    }
+
+    fn status(&self) -> CameraStatus {
+        let battery_level = self
+            .camera
+            .get_property::<u32>(PropertyId::BatteryLevel)
+            .ok();
+        let ac_power = battery_level == Some(BATTERY_LEVEL_AC);
+        let battery_level = battery_level.filter(|_| !ac_power);
+
+        let label = |property: PropertyId| {
+            self.camera
+                .get_property::<u32>(property)
+                .ok()
+                .map(|code| PropertyValue::decode(property, code).label())
+        };
+
+        let free_space = self.camera.volumes().ok().map(|volumes| {
+            volumes
+                .iter()
+                .filter_map(|volume| volume.info().ok())
+                .map(|info| info.free_space_in_bytes)
+                .sum()
+        });
+
+        CameraStatus {
+            battery_level,
+            ac_power,
+            low_battery: battery_level.is_some_and(|level| level <= LOW_BATTERY_LEVEL),
+            battery_quality: label(PropertyId::BatteryQuality),
+            free_space,
+            available_shots: self.camera.get_property(PropertyId::AvailableShots).ok(),
+            lens_attached: self
+                .camera
+                .get_property::<u32>(PropertyId::LensStatus)
+                .ok()
+                .map(|status| status != 0),
+            mode: label(PropertyId::AeModeSelect).or_else(|| label(PropertyId::AeMode)),
+        }
+    }
+
+    fn live_view_frame(&mut self) -> Result<LiveViewFrame, BackendError> {
+        let stream = Stream::create_memory(sdk()?, 0)?;
+
+        let evf_image = EvfImage::new(&stream)?;
+        self.camera.download_evf_image(&evf_image)?;
+
+        let af_frames = self
+            .camera
+            .get_property::<FocusInfo>(PropertyId::FocusInfo)
+            .map(|focus_info| {
+                focus_info
+                    .points
+                    .iter()
+                    .filter(|point| point.valid != 0)
+                    .map(|point| AfFrame {
+                        rect: point.rect.into(),
+                        selected: point.selected != 0,
+                        in_focus: point.just_focus & 0x1 != 0,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(LiveViewFrame {
+            jpeg: stream.to_vec()?,
+            histogram: evf_image.histogram().ok().map(LiveViewHistogram::from),
+            zoom: evf_image.zoom().ok(),
+            zoom_rect: evf_image.zoom_rect().ok().map(Rect::from),
+            image_position: evf_image.image_position().ok().map(|point| Point {
+                x: point.x,
+                y: point.y,
+            }),
+            coordinate_system: evf_image.coordinate_system().ok().map(|size| Size {
+                width: size.width,
+                height: size.height,
+            }),
+            af_frames,
+        })
+    }
+
+    fn available_values(&self, setting: CameraSetting) -> Result<Vec<SettingValue>, BackendError> {
+        Ok(self
+            .camera
+            .available_values(setting.into())?
+            .into_iter()
+            .map(SettingValue::from)
+            .collect())
+    }
+
+    fn set_setting(&mut self, setting: CameraSetting, code: u32) -> Result<(), BackendError> {
+        Ok(self.camera.set_property(setting.into(), &code)?)
+    }
+
+    fn list_card_files(&self) -> Result<Vec<CardFile>, BackendError> {
+        let mut files = Vec::new();
+
+        for volume in self.camera.volumes()? {
+            let label = volume.info()?.sz_volume_label().to_string();
+
+            for entry in volume.files()? {
+                files.push(CardFile {
+                    volume: label.clone(),
+                    path: entry.path,
+                    size: entry.item.info()?.size,
+                });
+            }
+        }
+
+        Ok(files)
+    }
+
+    fn download_card_file(&mut self, file: &CardFile) -> Result<String, BackendError> {
+        let item = self
+            .find_card_file(file)
+            .map_err(|err| format!("Failed to find {}: {}", file.path, err))?;
+
+        // card names repeat across bodies and once the counter is reset
+        let camera_dir = CAMERA_PHOTO_DIR
+            .get()
+            .expect("CAMERA_PHOTO_DIR not initialized");
+        let file_name = unique_file_name(
+            camera_dir,
+            file.path.rsplit('/').next().unwrap_or(&file.path),
+        );
+        download_to_file(&item, camera_dir, &file_name, |_| {})?;
+
+        Ok(file_name)
+    }
+
+    fn delete_card_file(&mut self, file: &CardFile) -> Result<(), BackendError> {
+        Ok(self.find_card_file(file)?.delete()?)
+    }
+
+    fn format_cards(&mut self) -> Result<(), BackendError> {
+        for volume in self.camera.volumes()? {
+            volume.format()?;
+        }
+
+        Ok(())
     }
 }
 
 impl Drop for CameraRef {
     fn drop(&mut self) {
-        // reset evf output
-        if let Ok(mut current_live_view) = Self::get_evf_output_device(self.camera) {
+        // reset evf output, the session is closed when the camera is dropped
+        if let Ok(mut current_live_view) = self.get_evf_output_device() {
             current_live_view.remove(EdsEvfOutputDevice::PC);
-            let _ = Self::set_evf_output_device(self.camera, current_live_view);
+            let _ = self.set_evf_output_device(current_live_view);
         }
+    }
+}
 
-        unsafe { EdsCloseSession(self.camera) };
-        unsafe { EdsTerminateSDK() };
-    }
-}
-
-unsafe impl Send for CameraRef {}
-unsafe impl Sync for CameraRef {}
-
-#[no_mangle]
-extern "C" fn event_handler(
-    event: EdsObjectEvent,
-    in_ref: EdsBaseRef,
-    _context: *mut EdsVoid,
-) -> EdsError {
-    println!("received event: {:x}", event);
-    match event {
-        // DirItemCreated | DirItemRequestTransfer
-        0x204 | 0x208 => {
-            let Some(sender) = PHOTO_RESULT_SENDER.lock().unwrap().take() else {
-                return EdsError::Ok;
-            };
-
-            println!("received DirItemCreated/DirItemRequestTransfer event");
-            let directory_item = in_ref as EdsDirectoryItemRef;
-
-            println!("getting directory info");
-            let mut dir_item_info = EdsDirectoryItemInfo::default();
-            let err = unsafe { EdsGetDirectoryItemInfo(directory_item, &mut dir_item_info) };
-            if !err.is_ok() {
-                sender
-                    .blocking_send(Err(format!("Failed to get directory info: {:?}", err)))
-                    .unwrap();
-                return err;
-            }
+fn handle_property_event(pending_events: &Mutex<Vec<CameraEvent>>, event: PropertyEvent) {
+    if let PropertyEvent::PropertyChanged { property, param } = event {
+        pending_events
+            .lock()
+            .unwrap()
+            .push(CameraEvent::PropertyChanged {
+                property: format!("{:?}", property),
+                param,
+            });
+    }
+}
 
-            // Get the camera photo directory and create the full path
-            let camera_dir = CAMERA_PHOTO_DIR
-                .get()
-                .expect("CAMERA_PHOTO_DIR not initialized");
-
-            let now: DateTime<Local> = SystemTime::now().into();
-            let file_name = format!("{}.jpeg", now.format("%d-%m-%Y %H-%M-%S"));
-
-            let full_path = camera_dir.join(&file_name);
-            let full_path_str = full_path.to_string_lossy().to_string();
-
-            println!("Creating stream to save file at: {}", full_path_str);
-            let mut stream = null_mut() as EdsStreamRef;
-            let c_path = match CString::new(full_path_str.clone()) {
-                Ok(p) => p,
-                Err(e) => {
-                    sender
-                        .blocking_send(Err(format!("Invalid path string: {}", e)))
-                        .unwrap();
-                    return EdsError::Ok;
-                }
-            };
-
-            let err = unsafe {
-                EdsCreateFileStream(
-                    c_path.as_ptr(),
-                    edsdk::EdsFileCreateDisposition::CreateAlways,
-                    edsdk::EdsAccess::ReadWrite,
-                    &mut stream,
-                )
-            };
-            if !err.is_ok() {
-                sender
-                    .blocking_send(Err(format!("Failed to create file stream: {:?}", err)))
-                    .unwrap();
-                return err;
-            }
+fn handle_state_event(
+    photo_result: &PhotoResult,
+    pending_events: &Mutex<Vec<CameraEvent>>,
+    event: StateEvent,
+) {
+    println!("received state event: {:?}", event);
 
-            let err = unsafe { EdsDownload(directory_item, dir_item_info.size, stream) };
-            if !err.is_ok() {
-                unsafe { EdsRelease(stream) };
-                sender
-                    .blocking_send(Err(format!("Failed to download: {:?}", err)))
-                    .unwrap();
-                return err;
+    let camera_event = match event {
+        // the transfer events will never arrive, the camera thread answers the photos once it
+        // closes the camera
+        StateEvent::Shutdown => CameraEvent::Shutdown,
+        // errors are reported in order as well, so they belong to the oldest photo still waiting
+        StateEvent::CaptureError(err) => {
+            let mut photo_result = photo_result.lock().unwrap();
+            match photo_result.pending.pop_front() {
+                Some(pending) => photo_result.failed.push((pending.id, err.into())),
+                None => println!("Failed to take a picture nobody asked for: {}", err),
             }
+            return;
+        }
+        StateEvent::WillSoonShutDown { seconds } => CameraEvent::WillSoonShutDown { seconds },
+        StateEvent::BulbExposureTime { seconds } => CameraEvent::BulbExposureTime { seconds },
+        _ => return,
+    };
 
-            let err = unsafe { EdsDownloadComplete(directory_item) };
-            if !err.is_ok() {
-                unsafe { EdsRelease(stream) };
-                sender
-                    .blocking_send(Err(format!("Failed to complete download: {:?}", err)))
-                    .unwrap();
-                return err;
-            }
+    pending_events.lock().unwrap().push(camera_event);
+}
 
-            let err = unsafe { EdsRelease(stream) };
-            if !err.is_ok() {
-                sender
-                    .blocking_send(Err(format!("Failed to release stream: {:?}", err)))
-                    .unwrap();
-                return err;
-            }
+fn handle_object_event(
+    photo_result: &PhotoResult,
+    pending_events: &Mutex<Vec<CameraEvent>>,
+    event: ObjectEvent,
+    directory_item: Option<&DirectoryItem>,
+) {
+    println!("received event: {:?}", event);
+
+    // a photo saved to the card only, e.g. while the host isn't a save target, nothing is coming
+    // for it, so it must not answer a photo that's still being transferred
+    if event == ObjectEvent::DirItemCreated {
+        println!("Photo saved to the card only");
+        return;
+    }
 
-            println!("Photo saved successfully: {}", file_name);
-            sender.blocking_send(Ok(file_name)).unwrap();
+    let (ObjectEvent::DirItemRequestTransfer, Some(directory_item)) = (event, directory_item)
+    else {
+        return;
+    };
 
-            EdsError::Ok
+    let pending = photo_result.lock().unwrap().pending.pop_front();
+    let Some(PendingCapture {
+        id,
+        respond_to: sender,
+    }) = pending
+    else {
+        save_unsolicited_photo(pending_events, directory_item);
+        return;
+    };
+
+    // the thumbnail is there long before the photo finished downloading
+    match extract_preview(directory_item) {
+        Ok(jpeg) => {
+            *CAPTURE_PREVIEW.lock().unwrap() = Some(jpeg);
+            let _ = sender.blocking_send(CaptureUpdate::Preview);
         }
-        _ => EdsError::Ok,
-    }
-}
-
-pub fn camera_event_thread(app: AppHandle, enable_live_view: bool) {
-    loop {
-        let err = unsafe { EdsGetEvent() };
-        assert!(err.is_ok());
-
-        // only take live image if we are not currently expecting a photo result
-        if enable_live_view
-            && PHOTO_RESULT_SENDER
-                .try_lock()
-                .is_ok_and(|sender| sender.is_none())
-        {
-            if let Some(cam) = CAMERA.blocking_lock().as_ref() {
-                if cam.get_evf_image().is_ok() {
-                    let _ = app.emit("evf-update", serde_json::Value::Null);
-                }
-            } else {
-                break;
-            }
+        Err(err) => println!("No preview available: {}", err),
+    }
+
+    let result = download_photo(directory_item, &sender)
+        .map_err(|message| CaptureError::DownloadFailed { message });
+
+    if let Ok(file_name) = &result {
+        println!("Photo saved successfully: {}", file_name);
+    }
+    let _ = sender.blocking_send(CaptureUpdate::Done(result));
+    photo_result.lock().unwrap().completed.push(id);
+}
+
+/// Saves a photo nobody asked for, e.g. because someone pressed the shutter button on the camera
+/// or it arrived after its capture timed out, and reports it as `camera-unsolicited-photo`
+fn save_unsolicited_photo(
+    pending_events: &Mutex<Vec<CameraEvent>>,
+    directory_item: &DirectoryItem,
+) {
+    let dir = UNSOLICITED_PHOTO_DIR
+        .get()
+        .expect("UNSOLICITED_PHOTO_DIR not initialized");
+    let file_name = photo_file_name(dir);
+
+    match download_to_file(directory_item, dir, &file_name, |_| {}) {
+        Ok(()) => {
+            println!("Unsolicited photo saved: {}", file_name);
+            pending_events
+                .lock()
+                .unwrap()
+                .push(CameraEvent::UnsolicitedPhoto { file_name });
+        }
+        Err(err) => println!("Failed to save unsolicited photo: {}", err),
+    }
+}
+
+/// The name a photo taken now is saved as in `dir`, photos taken within the same second are
+/// numbered
+pub fn photo_file_name(dir: &Path) -> String {
+    let now: DateTime<Local> = SystemTime::now().into();
+    unique_file_name(dir, &format!("{}.jpeg", now.format("%d-%m-%Y %H-%M-%S")))
+}
+
+/// `file_name`, numbered if a file of that name already exists in `dir`, e.g. `IMG_0001 (2).JPG`
+pub fn unique_file_name(dir: &Path, file_name: &str) -> String {
+    let (stem, extension) = match file_name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem, format!(".{}", extension)),
+        _ => (file_name, String::new()),
+    };
+
+    let mut unique = file_name.to_string();
+    let mut number = 1;
+    while dir.join(&unique).exists() {
+        number += 1;
+        unique = format!("{} ({}){}", stem, number, extension);
+    }
+    unique
+}
+
+/// Decodes the thumbnail embedded in `directory_item`, RAW files included, and encodes it as jpeg
+fn extract_preview(directory_item: &DirectoryItem) -> Result<Vec<u8>, String> {
+    let sdk = sdk().map_err(|err| format!("SDK not initialized: {}", err))?;
+
+    let stream = Stream::create_memory(sdk, 0)
+        .map_err(|err| format!("Failed to create memory stream: {}", err))?;
+    directory_item
+        .download_thumbnail(&stream)
+        .map_err(|err| format!("Failed to download thumbnail: {}", err))?;
+
+    let thumbnail = Image::new(&stream)
+        .and_then(|image| image.scaled_rgb(sdk, EdsImageSource::Thumbnail, PREVIEW_SIZE))
+        .map_err(|err| format!("Failed to decode thumbnail: {}", err))?;
+
+    let mut jpeg = Vec::new();
+    Encoder::new(&mut jpeg, 85)
+        .encode(
+            &thumbnail.pixels,
+            thumbnail.width as u16,
+            thumbnail.height as u16,
+            ColorType::Rgb,
+        )
+        .map_err(|err| format!("Failed to encode thumbnail: {}", err))?;
+
+    Ok(jpeg)
+}
+
+fn download_photo(
+    directory_item: &DirectoryItem,
+    sender: &Sender<CaptureUpdate>,
+) -> Result<String, String> {
+    let camera_dir = CAMERA_PHOTO_DIR
+        .get()
+        .expect("CAMERA_PHOTO_DIR not initialized");
+    let file_name = photo_file_name(camera_dir);
+
+    let sender = sender.clone();
+    download_to_file(directory_item, camera_dir, &file_name, move |percent| {
+        // progress is informational, rather skip an update than stall the download
+        let _ = sender.try_send(CaptureUpdate::Progress(percent));
+    })?;
+
+    Ok(file_name)
+}
+
+/// Downloads `directory_item` into `dir` as `file_name`, `on_progress` is called with the
+/// percentage downloaded so far. A failed download is cancelled, the camera stays busy until a
+/// transfer is either completed or cancelled, and the partial file is removed
+fn download_to_file(
+    directory_item: &DirectoryItem,
+    dir: &Path,
+    file_name: &str,
+    mut on_progress: impl FnMut(u32) + Send + 'static,
+) -> Result<(), String> {
+    println!("getting directory info");
+    let dir_item_info = directory_item
+        .info()
+        .map_err(|err| format!("Failed to get directory info: {}", err))?;
+
+    let full_path = dir.join(file_name);
+
+    println!("Creating stream to save file at: {}", full_path.display());
+    let mut stream = sdk()
+        .and_then(|sdk| {
+            Stream::create_file(
+                sdk,
+                &full_path,
+                EdsFileCreateDisposition::CreateAlways,
+                EdsAccess::ReadWrite,
+            )
+        })
+        .map_err(|err| format!("Failed to create file stream: {}", err))?;
+
+    if let Err(err) = stream.on_progress(move |percent| {
+        on_progress(percent);
+        true
+    }) {
+        println!("No download progress available: {}", err);
+    }
+
+    let res = directory_item
+        .download(dir_item_info.size, &stream)
+        .map_err(|err| format!("Failed to download: {}", err))
+        .and_then(|()| {
+            directory_item
+                .download_complete()
+                .map_err(|err| format!("Failed to complete download: {}", err))
+        });
+
+    if res.is_err() {
+        if let Err(err) = directory_item.download_cancel() {
+            println!("Failed to cancel download: {}", err);
+        }
+        // the file is only closed once the stream is released
+        drop(stream);
+        if let Err(err) = fs::remove_file(&full_path) {
+            println!("Failed to remove {}: {}", full_path.display(), err);
+        }
+    }
+    res
+}
+
+/// Runs the event handlers against the simulated SDK, `cargo test --features simulated`
+#[cfg(all(test, feature = "simulated"))]
+mod tests {
+    use std::{
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use tauri::async_runtime::channel;
+
+    use super::*;
+    use crate::{
+        config::Backend,
+        test_support::{answer, poll_until, TestCamera},
+    };
+
+    /// The simulated camera answers right away
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    fn open_camera() -> TestCamera<CameraRef> {
+        let config = Config {
+            backend: Backend::Edsdk,
+            image_quality: edsdk::EdsImageQuality::JpegSmall,
+            camera_serial: None,
+            auto_power_off: None,
+            capture_timeout: Duration::from_secs(30),
+        };
+        TestCamera::open(|| {
+            CameraRef::init(false, &config).expect("Failed to open the simulated camera")
+        })
+    }
+
+    fn unsolicited_photo(events: Vec<CameraEvent>) -> Option<String> {
+        events.into_iter().find_map(|event| match event {
+            CameraEvent::UnsolicitedPhoto { file_name } => Some(file_name),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn answers_photos_in_the_order_they_were_taken() {
+        let mut camera = open_camera();
+
+        // roomy enough for every update, nobody reads them while the events are pumped
+        let (tx, mut first) = channel(256);
+        camera.take_picture(CaptureId(0), tx).unwrap();
+        let (tx, mut second) = channel(256);
+        camera.take_picture(CaptureId(1), tx).unwrap();
+
+        let first = poll_until(&mut *camera, TIMEOUT, |_| answer(&mut first)).unwrap();
+        let second = poll_until(&mut *camera, TIMEOUT, |_| answer(&mut second)).unwrap();
+        assert_ne!(first, second);
+
+        let camera_dir = CAMERA_PHOTO_DIR.get().unwrap();
+        assert!(camera_dir.join(first).exists());
+        assert!(camera_dir.join(second).exists());
+        assert!(!camera.is_capturing());
+    }
+
+    #[test]
+    fn saves_photos_nobody_asked_for() {
+        let mut camera = open_camera();
+
+        camera.release_shutter().unwrap();
+        let file_name = poll_until(&mut *camera, TIMEOUT, unsolicited_photo);
+
+        let dir = UNSOLICITED_PHOTO_DIR.get().unwrap();
+        assert!(dir.join(file_name).exists());
+    }
+
+    #[test]
+    fn saves_abandoned_photos_as_unsolicited() {
+        let mut camera = open_camera();
+
+        let (tx, mut on_time) = channel(256);
+        camera.take_picture(CaptureId(0), tx).unwrap();
+        let (tx, mut late) = channel(256);
+        camera.take_picture(CaptureId(1), tx).unwrap();
+        camera.abandon_capture(CaptureId(1));
+
+        // only the abandoned capture is forgotten, the one before it still gets its photo
+        poll_until(&mut *camera, TIMEOUT, |_| answer(&mut on_time)).unwrap();
+        let file_name = poll_until(&mut *camera, TIMEOUT, unsolicited_photo);
+
+        assert!(UNSOLICITED_PHOTO_DIR
+            .get()
+            .unwrap()
+            .join(file_name)
+            .exists());
+        assert!(answer(&mut late).is_none());
+        assert!(!camera.is_capturing());
+    }
+
+    #[test]
+    fn cancels_failed_downloads() {
+        let mut camera = open_camera();
+        let camera_dir = CAMERA_PHOTO_DIR.get().unwrap();
+        let photos = fs::read_dir(camera_dir).unwrap().count();
+
+        edsdk::fail_next_download();
+        let (tx, mut rx) = channel(256);
+        camera.take_picture(CaptureId(0), tx).unwrap();
+
+        let result = poll_until(&mut *camera, TIMEOUT, |_| answer(&mut rx));
+        assert!(matches!(result, Err(CaptureError::DownloadFailed { .. })));
+        assert_eq!(fs::read_dir(camera_dir).unwrap().count(), photos);
+
+        // the transfer was cancelled, so the camera isn't stuck in it
+        let (tx, mut rx) = channel(256);
+        camera.take_picture(CaptureId(1), tx).unwrap();
+        poll_until(&mut *camera, TIMEOUT, |_| answer(&mut rx)).unwrap();
+    }
+
+    #[test]
+    fn photos_saved_to_the_card_only_answer_nothing() {
+        let mut camera = open_camera();
+        camera
+            .camera
+            .set_property(PropertyId::SaveTo, &EdsSaveTo::Camera)
+            .unwrap();
+
+        let (tx, mut rx) = channel(256);
+        camera.take_picture(CaptureId(0), tx).unwrap();
+
+        let deadline = Instant::now() + Duration::from_millis(500);
+        while Instant::now() < deadline {
+            camera.poll_events().unwrap();
+            thread::sleep(Duration::from_millis(10));
         }
 
-        thread::sleep(Duration::from_millis(100));
+        assert!(answer(&mut rx).is_none());
+        assert!(camera.is_capturing());
+
+        camera.abandon_capture(CaptureId(0));
+        camera
+            .camera
+            .set_property(PropertyId::SaveTo, &EdsSaveTo::Host)
+            .unwrap();
     }
 }
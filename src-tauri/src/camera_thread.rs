@@ -0,0 +1,942 @@
+use std::{
+    sync::{
+        atomic::Ordering,
+        mpsc::{self, RecvTimeoutError},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use tauri::{
+    async_runtime::{channel, Sender},
+    AppHandle, Emitter,
+};
+
+use crate::{
+    backend::{
+        self, BackendError, CameraBackend, CaptureId, CAPTURE_RETRY_DELAY, MAX_CAPTURE_ATTEMPTS,
+    },
+    camera::{
+        CameraEvent, CameraSetting, CaptureError, CaptureUpdate, CardFile, SettingValue,
+        CAMERA_CONNECTED, CAMERA_STATUS, EVF_FRAME,
+    },
+    config::Config,
+};
+
+/// How often the camera thread polls events and fetches a live view frame
+const EVENT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How often the battery, storage and mode are read
+const STATUS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How often the camera's auto power-off timer is reset, shorter than the shortest setting
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How often opening the camera is retried while there is none
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Messages to the camera thread, each carries the channel the answer is sent back on
+enum Request {
+    /// Releases the shutter at `at`, right away if that has passed
+    TakePicture {
+        at: Instant,
+        respond_to: Sender<CaptureUpdate>,
+    },
+    AvailableValues(CameraSetting, Sender<Result<Vec<SettingValue>, String>>),
+    SetSetting(CameraSetting, u32, Sender<Result<(), String>>),
+    ListCardFiles(Sender<Result<Vec<CardFile>, String>>),
+    DownloadCardFile(CardFile, Sender<Result<String, String>>),
+    DeleteCardFile(CardFile, Sender<Result<(), String>>),
+    FormatCards(Sender<Result<(), String>>),
+    /// Closes the session, the answer is sent once the camera is released
+    Shutdown(mpsc::Sender<()>),
+}
+
+/// Talks to the camera through the thread owning it. Every backend call, the event pump included,
+/// happens on that thread, so the camera itself never has to be shared
+#[derive(Clone)]
+pub struct CameraHandle {
+    requests: mpsc::Sender<Request>,
+}
+
+impl CameraHandle {
+    /// Starts the camera thread, which opens the camera as soon as there is one
+    pub fn spawn(app: AppHandle, enable_live_view: bool, config: Config) -> Result<Self, String> {
+        let (requests, receiver) = mpsc::channel();
+
+        thread::Builder::new()
+            .name("camera".to_string())
+            .spawn(move || {
+                let connection = Connection::new(app.clone(), enable_live_view, config);
+                run(app, connection, receiver, enable_live_view);
+            })
+            .map_err(|err| format!("Failed to start the camera thread: {}", err))?;
+
+        Ok(Self { requests })
+    }
+
+    /// Takes `count` photos `interval` apart and returns their file names in order,
+    /// `on_update` is called with the index of the photo and its preview and download progress
+    /// updates until then. The camera thread answers each within the capture timeout, the first
+    /// photo that fails fails the whole burst
+    pub async fn capture(
+        &self,
+        count: u32,
+        interval: Duration,
+        mut on_update: impl FnMut(usize, CaptureUpdate),
+    ) -> Result<Vec<String>, CaptureError> {
+        let start = Instant::now();
+
+        // all photos are scheduled up front, so the interval doesn't depend on the downloads
+        let mut receivers = Vec::new();
+        for index in 0..count {
+            let (tx, rx) = channel(16);
+            self.send(Request::TakePicture {
+                at: start + interval * index,
+                respond_to: tx,
+            })
+            .map_err(|message| CaptureError::Failed { message })?;
+            receivers.push(rx);
+        }
+
+        // photos arrive in the order they were taken, dropping the remaining receivers cancels
+        // the photos not taken yet
+        let mut file_names = Vec::new();
+        for (index, mut rx) in receivers.into_iter().enumerate() {
+            loop {
+                let update = rx.recv().await.ok_or(CaptureError::Disconnected)?;
+
+                match update {
+                    CaptureUpdate::Done(result) => {
+                        file_names.push(result?);
+                        break;
+                    }
+                    update => on_update(index, update),
+                }
+            }
+        }
+
+        Ok(file_names)
+    }
+
+    /// The values `setting` can currently be set to
+    pub async fn available_values(
+        &self,
+        setting: CameraSetting,
+    ) -> Result<Vec<SettingValue>, String> {
+        self.request(|tx| Request::AvailableValues(setting, tx))
+            .await?
+    }
+
+    /// Sets `setting` to the raw value `code`
+    pub async fn set_setting(&self, setting: CameraSetting, code: u32) -> Result<(), String> {
+        self.request(|tx| Request::SetSetting(setting, code, tx))
+            .await?
+    }
+
+    pub async fn list_card_files(&self) -> Result<Vec<CardFile>, String> {
+        self.request(Request::ListCardFiles).await?
+    }
+
+    /// Downloads a file from the card into the camera photo directory, returns the file name
+    pub async fn download_card_file(&self, file: CardFile) -> Result<String, String> {
+        self.request(|tx| Request::DownloadCardFile(file, tx))
+            .await?
+    }
+
+    pub async fn delete_card_file(&self, file: CardFile) -> Result<(), String> {
+        self.request(|tx| Request::DeleteCardFile(file, tx)).await?
+    }
+
+    /// Deletes everything on all cards
+    pub async fn format_cards(&self) -> Result<(), String> {
+        self.request(Request::FormatCards).await?
+    }
+
+    /// Stops the camera thread and waits until the session is closed
+    pub fn shutdown(&self) {
+        let (tx, rx) = mpsc::channel();
+        if self.requests.send(Request::Shutdown(tx)).is_ok() {
+            let _ = rx.recv();
+        }
+    }
+
+    async fn request<T>(&self, request: impl FnOnce(Sender<T>) -> Request) -> Result<T, String> {
+        let (tx, mut rx) = channel(1);
+        self.send(request(tx))?;
+        rx.recv().await.ok_or_else(thread_stopped)
+    }
+
+    fn send(&self, request: Request) -> Result<(), String> {
+        self.requests.send(request).map_err(|_| thread_stopped())
+    }
+}
+
+fn thread_stopped() -> String {
+    "The camera thread has stopped".to_string()
+}
+
+fn not_connected() -> String {
+    "No camera connected".to_string()
+}
+
+/// A photo of a burst whose shutter isn't released yet, or a photo whose shutter is released
+/// again after an error that's likely to pass
+struct ScheduledCapture {
+    id: CaptureId,
+    at: Instant,
+    /// How often the shutter was released for it so far
+    attempts: u32,
+    respond_to: Sender<CaptureUpdate>,
+}
+
+/// A photo the camera thread waits for on behalf of [`CameraHandle::capture`]
+struct PendingCapture {
+    id: CaptureId,
+    deadline: Instant,
+    attempts: u32,
+    respond_to: Sender<CaptureUpdate>,
+}
+
+/// The photos asked for through [`CameraHandle::capture`], from scheduling them until they're
+/// answered. Each call takes the camera, if there is one, so photos that are due without a camera
+/// fail right away
+struct CaptureQueue {
+    /// Sorted by the time they're taken at
+    scheduled: Vec<ScheduledCapture>,
+    /// Answered with an error if the camera goes away or the photo takes too long
+    pending: Vec<PendingCapture>,
+    next_id: u64,
+    timeout: Duration,
+}
+
+impl CaptureQueue {
+    fn new(timeout: Duration) -> Self {
+        Self {
+            scheduled: Vec::new(),
+            pending: Vec::new(),
+            next_id: 0,
+            timeout,
+        }
+    }
+
+    /// Takes the photo at `at`, or right away if that has passed
+    fn schedule_picture(
+        &mut self,
+        camera: Option<&mut Box<dyn CameraBackend>>,
+        at: Instant,
+        respond_to: Sender<CaptureUpdate>,
+    ) {
+        let id = CaptureId(self.next_id);
+        self.next_id += 1;
+
+        let capture = ScheduledCapture {
+            id,
+            at,
+            attempts: 0,
+            respond_to,
+        };
+        if at <= Instant::now() {
+            self.take_picture(camera, capture);
+        } else {
+            self.insert(capture);
+        }
+    }
+
+    fn insert(&mut self, capture: ScheduledCapture) {
+        let index = self
+            .scheduled
+            .partition_point(|scheduled| scheduled.at <= capture.at);
+        self.scheduled.insert(index, capture);
+    }
+
+    /// Releases the shutter for the scheduled photos that are due, unless nobody waits for them
+    /// anymore
+    fn take_due_pictures(&mut self, mut camera: Option<&mut Box<dyn CameraBackend>>) {
+        let now = Instant::now();
+        let due = self.scheduled.partition_point(|capture| capture.at <= now);
+
+        for capture in self.scheduled.drain(..due).collect::<Vec<_>>() {
+            if !capture.respond_to.is_closed() {
+                self.take_picture(camera.as_deref_mut(), capture);
+            }
+        }
+    }
+
+    fn take_picture(
+        &mut self,
+        camera: Option<&mut Box<dyn CameraBackend>>,
+        capture: ScheduledCapture,
+    ) {
+        let ScheduledCapture {
+            id,
+            attempts,
+            respond_to,
+            ..
+        } = capture;
+        let attempts = attempts + 1;
+
+        let res = match camera {
+            Some(camera) => camera.take_picture(id, respond_to.clone()),
+            None => Err(CaptureError::Disconnected),
+        };
+
+        match res {
+            Ok(()) => self.pending.push(PendingCapture {
+                id,
+                deadline: Instant::now() + self.timeout,
+                attempts,
+                respond_to,
+            }),
+            Err(err) => self.fail(id, attempts, respond_to, err),
+        }
+    }
+
+    /// Releases the shutter again a moment later if `err` is likely to pass, the camera thread
+    /// keeps running in the meantime. Otherwise the photo is answered with `err`
+    fn fail(
+        &mut self,
+        id: CaptureId,
+        attempts: u32,
+        respond_to: Sender<CaptureUpdate>,
+        err: CaptureError,
+    ) {
+        if err.is_retryable() && attempts < MAX_CAPTURE_ATTEMPTS {
+            println!("Failed to take picture, retrying: {}", err);
+            self.insert(ScheduledCapture {
+                id,
+                at: Instant::now() + CAPTURE_RETRY_DELAY,
+                attempts,
+                respond_to,
+            });
+        } else {
+            let _ = respond_to.blocking_send(CaptureUpdate::Done(Err(err)));
+        }
+    }
+
+    /// Stops waiting for the photos the camera answered, and retries or answers the ones it
+    /// reported an error for after their shutter was released
+    fn handle_finished_captures(&mut self, camera: &mut dyn CameraBackend) {
+        let completed = camera.take_completed_captures();
+        self.pending
+            .retain(|capture| !completed.contains(&capture.id));
+
+        self.handle_failed_captures(camera);
+    }
+
+    fn handle_failed_captures(&mut self, camera: &mut dyn CameraBackend) {
+        for (id, err) in camera.take_failed_captures() {
+            let Some(index) = self.pending.iter().position(|capture| capture.id == id) else {
+                continue;
+            };
+            let capture = self.pending.remove(index);
+            self.fail(capture.id, capture.attempts, capture.respond_to, err);
+        }
+    }
+
+    /// Fails the photos that didn't arrive within the capture timeout, the camera forgets them so
+    /// a late photo doesn't answer a later request. The photos taken after them keep waiting
+    fn expire(&mut self, mut camera: Option<&mut Box<dyn CameraBackend>>) {
+        let now = Instant::now();
+        let (expired, pending) = self
+            .pending
+            .drain(..)
+            .partition::<Vec<_>, _>(|capture| capture.deadline <= now);
+        self.pending = pending;
+
+        for capture in expired {
+            println!("No photo arrived within {} seconds", self.timeout.as_secs());
+            if let Some(camera) = camera.as_deref_mut() {
+                camera.abandon_capture(capture.id);
+            }
+            let _ = capture
+                .respond_to
+                .blocking_send(CaptureUpdate::Done(Err(CaptureError::Timeout)));
+        }
+    }
+
+    /// Answers every photo with `err`, scheduled or not
+    fn fail_all(&mut self, err: CaptureError) {
+        let scheduled = self.scheduled.drain(..).map(|capture| capture.respond_to);
+        let pending = self.pending.drain(..).map(|capture| capture.respond_to);
+        for respond_to in scheduled.chain(pending) {
+            let _ = respond_to.blocking_send(CaptureUpdate::Done(Err(err.clone())));
+        }
+    }
+}
+
+/// Opens the camera, notices when it's gone and opens it again. Opening sets the camera up from
+/// scratch, the settings changed through [`CameraHandle::set_setting`] are applied on top
+struct Connection {
+    app: AppHandle,
+    enable_live_view: bool,
+    config: Config,
+    camera: Option<Box<dyn CameraBackend>>,
+    captures: CaptureQueue,
+    last_attempt: Option<Instant>,
+    /// Only logged when it changes, the camera is retried every few seconds
+    last_error: Option<String>,
+    settings: Vec<(CameraSetting, u32)>,
+}
+
+impl Connection {
+    fn new(app: AppHandle, enable_live_view: bool, config: Config) -> Self {
+        Self {
+            app,
+            enable_live_view,
+            captures: CaptureQueue::new(config.capture_timeout),
+            config,
+            camera: None,
+            last_attempt: None,
+            last_error: None,
+            settings: Vec::new(),
+        }
+    }
+
+    /// Tries to open the camera if there is none and it's time for another attempt, returns
+    /// whether it was opened
+    fn connect_if_due(&mut self) -> bool {
+        if self.camera.is_some()
+            || self
+                .last_attempt
+                .is_some_and(|last_attempt| last_attempt.elapsed() < RECONNECT_INTERVAL)
+        {
+            return false;
+        }
+        self.last_attempt = Some(Instant::now());
+
+        let mut camera = match backend::open(self.enable_live_view, &self.config) {
+            Ok(camera) => camera,
+            Err(err) => {
+                let err = err.to_string();
+                if self.last_error.as_ref() != Some(&err) {
+                    println!("No camera available, retrying: {}", err);
+                    self.last_error = Some(err);
+                }
+                return false;
+            }
+        };
+
+        for (setting, code) in &self.settings {
+            if let Err(err) = camera.set_setting(*setting, *code) {
+                println!("Failed to restore {:?}: {}", setting, err);
+            }
+        }
+
+        println!("Camera connected");
+        self.camera = Some(camera);
+        self.last_error = None;
+        CAMERA_CONNECTED.store(true, Ordering::SeqCst);
+        let _ = self.app.emit("camera-connected", ());
+        true
+    }
+
+    fn disconnect(&mut self) {
+        let Some(mut camera) = self.camera.take() else {
+            return;
+        };
+
+        println!("Camera disconnected");
+        // the photos it answered already aren't failed again
+        self.captures.handle_finished_captures(&mut *camera);
+        self.captures.fail_all(CaptureError::Disconnected);
+        CAMERA_CONNECTED.store(false, Ordering::SeqCst);
+        *CAMERA_STATUS.lock().unwrap() = None;
+        *EVF_FRAME.lock().unwrap() = None;
+        let _ = self.app.emit("camera-disconnected", ());
+    }
+
+    /// Answers the photos that failed or timed out and releases the shutter for the due ones
+    fn update_captures(&mut self) {
+        if let Some(camera) = self.camera.as_deref_mut() {
+            self.captures.handle_finished_captures(camera);
+        }
+        self.captures.expire(self.camera.as_mut());
+        self.captures.take_due_pictures(self.camera.as_mut());
+    }
+
+    fn set_setting(&mut self, setting: CameraSetting, code: u32) -> Result<(), BackendError> {
+        self.camera
+            .as_deref_mut()
+            .ok_or_else(not_connected)?
+            .set_setting(setting, code)?;
+
+        self.settings.retain(|(saved, _)| *saved != setting);
+        self.settings.push((setting, code));
+        Ok(())
+    }
+}
+
+/// Answers requests as they arrive and polls camera events in between, the backend's event
+/// handlers run on this thread as well
+fn run(
+    app: AppHandle,
+    mut connection: Connection,
+    requests: mpsc::Receiver<Request>,
+    enable_live_view: bool,
+) {
+    let mut next_tick = Instant::now();
+    let mut last_keep_alive = Instant::now();
+    let mut last_status: Option<Instant> = None;
+
+    let shutdown = loop {
+        match requests.recv_timeout(next_tick.saturating_duration_since(Instant::now())) {
+            Ok(Request::Shutdown(done)) => break Some(done),
+            Ok(request) => {
+                handle_request(&mut connection, request);
+                continue;
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            // every handle is gone
+            Err(RecvTimeoutError::Disconnected) => break None,
+        }
+        next_tick = Instant::now() + EVENT_INTERVAL;
+
+        connection.update_captures();
+        if connection.connect_if_due() {
+            last_keep_alive = Instant::now();
+            last_status = None;
+        }
+        let Some(camera) = connection.camera.as_deref_mut() else {
+            continue;
+        };
+
+        // a backend that can't deliver events anymore lost its camera
+        let events = match camera.poll_events() {
+            Ok(events) => events,
+            Err(err) => {
+                println!("Failed to get events: {}", err);
+                connection.disconnect();
+                continue;
+            }
+        };
+
+        let mut keep_alive = last_keep_alive.elapsed() >= KEEP_ALIVE_INTERVAL;
+        let mut shut_down = false;
+        for event in events {
+            match event {
+                CameraEvent::WillSoonShutDown { seconds } => {
+                    println!(
+                        "Camera will shut down in {} seconds, keeping it awake",
+                        seconds
+                    );
+                    keep_alive = true;
+                }
+                // sent when the camera is unplugged or turned off as well
+                CameraEvent::Shutdown => shut_down = true,
+                _ => {}
+            }
+            let _ = app.emit(event.event_name(), &event);
+        }
+
+        if shut_down {
+            connection.disconnect();
+            continue;
+        }
+
+        if keep_alive {
+            if let Err(err) = camera.keep_alive() {
+                println!("Failed to keep the camera awake: {}", err);
+            }
+            last_keep_alive = Instant::now();
+        }
+
+        // the camera is busy enough while a photo is transferred
+        if !camera.is_capturing()
+            && last_status.is_none_or(|last_status| last_status.elapsed() >= STATUS_INTERVAL)
+        {
+            update_status(&app, camera);
+            last_status = Some(Instant::now());
+        }
+
+        // only take live image if we are not currently expecting a photo result
+        if enable_live_view && !camera.is_capturing() {
+            if let Ok(frame) = camera.live_view_frame() {
+                let _ = app.emit("evf-update", &frame);
+                *EVF_FRAME.lock().unwrap() = Some(frame);
+            }
+        }
+    };
+
+    println!("Stopping camera thread, dropping camera");
+    drop(connection);
+
+    if let Some(done) = shutdown {
+        let _ = done.send(());
+    }
+}
+
+/// Reads the status and emits `camera-status` if it changed
+fn update_status(app: &AppHandle, camera: &dyn CameraBackend) {
+    let status = camera.status();
+
+    let mut current = CAMERA_STATUS.lock().unwrap();
+    if current.as_ref() == Some(&status) {
+        return;
+    }
+
+    if status.low_battery && !current.as_ref().is_some_and(|current| current.low_battery) {
+        println!(
+            "Camera battery is low: {}%",
+            status.battery_level.unwrap_or_default()
+        );
+    }
+
+    let _ = app.emit("camera-status", &status);
+    *current = Some(status);
+}
+
+fn handle_request(connection: &mut Connection, request: Request) {
+    match request {
+        Request::TakePicture { at, respond_to } => {
+            connection
+                .captures
+                .schedule_picture(connection.camera.as_mut(), at, respond_to);
+            return;
+        }
+        Request::SetSetting(setting, code, respond_to) => {
+            let _ = respond_to.blocking_send(to_string(connection.set_setting(setting, code)));
+            return;
+        }
+        _ => {}
+    }
+
+    let Some(camera) = connection.camera.as_deref_mut() else {
+        respond_not_connected(request);
+        return;
+    };
+
+    match request {
+        Request::AvailableValues(setting, respond_to) => {
+            let _ = respond_to.blocking_send(to_string(camera.available_values(setting)));
+        }
+        Request::ListCardFiles(respond_to) => {
+            let _ = respond_to.blocking_send(to_string(camera.list_card_files()));
+        }
+        Request::DownloadCardFile(file, respond_to) => {
+            let _ = respond_to.blocking_send(to_string(camera.download_card_file(&file)));
+        }
+        Request::DeleteCardFile(file, respond_to) => {
+            let _ = respond_to.blocking_send(to_string(camera.delete_card_file(&file)));
+        }
+        Request::FormatCards(respond_to) => {
+            let _ = respond_to.blocking_send(to_string(camera.format_cards()));
+        }
+        Request::TakePicture { .. } | Request::SetSetting(..) | Request::Shutdown(_) => {
+            unreachable!("handled by the camera thread")
+        }
+    }
+}
+
+fn respond_not_connected(request: Request) {
+    match request {
+        Request::AvailableValues(_, respond_to) => {
+            let _ = respond_to.blocking_send(Err(not_connected()));
+        }
+        Request::ListCardFiles(respond_to) => {
+            let _ = respond_to.blocking_send(Err(not_connected()));
+        }
+        Request::DownloadCardFile(_, respond_to) => {
+            let _ = respond_to.blocking_send(Err(not_connected()));
+        }
+        Request::DeleteCardFile(_, respond_to) => {
+            let _ = respond_to.blocking_send(Err(not_connected()));
+        }
+        Request::FormatCards(respond_to) => {
+            let _ = respond_to.blocking_send(Err(not_connected()));
+        }
+        Request::TakePicture { .. } | Request::SetSetting(..) | Request::Shutdown(_) => {
+            unreachable!("handled by the camera thread")
+        }
+    }
+}
+
+/// Backend errors are only shown to the user, so they cross the thread as strings
+fn to_string<T>(result: Result<T, BackendError>) -> Result<T, String> {
+    result.map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, collections::VecDeque, rc::Rc};
+
+    use tauri::async_runtime::Receiver;
+
+    use super::*;
+    use crate::camera::{CameraEvent, CameraStatus, LiveViewFrame};
+
+    /// What the fake camera was asked to do, and what it answers
+    #[derive(Default)]
+    struct FakeState {
+        /// The photos the shutter was released for, in order
+        taken: Vec<CaptureId>,
+        /// The results of the next releases, the ones after succeed
+        results: VecDeque<Result<(), CaptureError>>,
+        failed: Vec<(CaptureId, CaptureError)>,
+        completed: Vec<CaptureId>,
+        abandoned: Vec<CaptureId>,
+    }
+
+    struct FakeCamera(Rc<RefCell<FakeState>>);
+
+    impl CameraBackend for FakeCamera {
+        fn take_picture(
+            &mut self,
+            id: CaptureId,
+            _respond_to: Sender<CaptureUpdate>,
+        ) -> Result<(), CaptureError> {
+            let mut state = self.0.borrow_mut();
+            state.taken.push(id);
+            state.results.pop_front().unwrap_or(Ok(()))
+        }
+
+        fn abandon_capture(&mut self, id: CaptureId) {
+            self.0.borrow_mut().abandoned.push(id);
+        }
+
+        fn take_failed_captures(&mut self) -> Vec<(CaptureId, CaptureError)> {
+            std::mem::take(&mut self.0.borrow_mut().failed)
+        }
+
+        fn take_completed_captures(&mut self) -> Vec<CaptureId> {
+            std::mem::take(&mut self.0.borrow_mut().completed)
+        }
+
+        fn is_capturing(&self) -> bool {
+            false
+        }
+
+        fn poll_events(&mut self) -> Result<Vec<CameraEvent>, BackendError> {
+            Ok(Vec::new())
+        }
+
+        fn live_view_frame(&mut self) -> Result<LiveViewFrame, BackendError> {
+            Err("No live view".into())
+        }
+
+        fn status(&self) -> CameraStatus {
+            unreachable!("the capture queue doesn't read the status")
+        }
+
+        fn available_values(
+            &self,
+            _setting: CameraSetting,
+        ) -> Result<Vec<SettingValue>, BackendError> {
+            Ok(Vec::new())
+        }
+
+        fn set_setting(&mut self, _setting: CameraSetting, _code: u32) -> Result<(), BackendError> {
+            Ok(())
+        }
+    }
+
+    fn fake_camera() -> (Rc<RefCell<FakeState>>, Box<dyn CameraBackend>) {
+        let state = Rc::new(RefCell::new(FakeState::default()));
+        (state.clone(), Box::new(FakeCamera(state)))
+    }
+
+    fn queue() -> CaptureQueue {
+        CaptureQueue::new(Duration::from_secs(30))
+    }
+
+    /// The result the photo was answered with, if it was
+    fn answer(rx: &mut Receiver<CaptureUpdate>) -> Option<Result<String, CaptureError>> {
+        match rx.try_recv() {
+            Ok(CaptureUpdate::Done(result)) => Some(result),
+            _ => None,
+        }
+    }
+
+    /// Makes the scheduled photos due right away
+    fn make_due(queue: &mut CaptureQueue) {
+        for capture in &mut queue.scheduled {
+            capture.at = Instant::now();
+        }
+    }
+
+    #[test]
+    fn takes_burst_photos_in_order_of_their_time() {
+        let (state, mut camera) = fake_camera();
+        let mut queue = queue();
+        let start = Instant::now();
+
+        let mut receivers = Vec::new();
+        for offset in [100, 50, 3_600_000] {
+            let (tx, rx) = channel(16);
+            let at = start + Duration::from_millis(offset);
+            queue.schedule_picture(Some(&mut camera), at, tx);
+            receivers.push(rx);
+        }
+        assert!(state.borrow().taken.is_empty());
+
+        thread::sleep(Duration::from_millis(150));
+        queue.take_due_pictures(Some(&mut camera));
+
+        assert_eq!(state.borrow().taken, [CaptureId(1), CaptureId(0)]);
+        assert_eq!(queue.pending.len(), 2);
+        assert_eq!(queue.scheduled.len(), 1);
+    }
+
+    #[test]
+    fn skips_photos_nobody_waits_for() {
+        let (state, mut camera) = fake_camera();
+        let mut queue = queue();
+
+        let (tx, rx) = channel(16);
+        queue.schedule_picture(
+            Some(&mut camera),
+            Instant::now() + Duration::from_secs(1),
+            tx,
+        );
+        drop(rx);
+
+        make_due(&mut queue);
+        queue.take_due_pictures(Some(&mut camera));
+
+        assert!(state.borrow().taken.is_empty());
+        assert!(queue.scheduled.is_empty());
+    }
+
+    #[test]
+    fn fails_photos_without_a_camera() {
+        let mut queue = queue();
+
+        let (tx, mut rx) = channel(16);
+        queue.schedule_picture(None, Instant::now(), tx);
+
+        assert_eq!(answer(&mut rx), Some(Err(CaptureError::Disconnected)));
+    }
+
+    #[test]
+    fn retries_a_busy_camera_later() {
+        let (state, mut camera) = fake_camera();
+        state
+            .borrow_mut()
+            .results
+            .push_back(Err(CaptureError::Busy));
+        let mut queue = queue();
+
+        let (tx, mut rx) = channel(16);
+        queue.schedule_picture(Some(&mut camera), Instant::now(), tx);
+
+        // the retry waits for its time instead of blocking the camera thread
+        assert_eq!(state.borrow().taken, [CaptureId(0)]);
+        assert_eq!(queue.scheduled.len(), 1);
+        assert!(queue.scheduled[0].at > Instant::now());
+        assert!(answer(&mut rx).is_none());
+
+        make_due(&mut queue);
+        queue.take_due_pictures(Some(&mut camera));
+
+        assert_eq!(state.borrow().taken, [CaptureId(0), CaptureId(0)]);
+        assert_eq!(queue.pending.len(), 1);
+        assert_eq!(queue.pending[0].attempts, 2);
+    }
+
+    #[test]
+    fn gives_up_after_the_last_attempt() {
+        let (state, mut camera) = fake_camera();
+        for _ in 0..MAX_CAPTURE_ATTEMPTS {
+            state
+                .borrow_mut()
+                .results
+                .push_back(Err(CaptureError::Busy));
+        }
+        let mut queue = queue();
+
+        let (tx, mut rx) = channel(16);
+        queue.schedule_picture(Some(&mut camera), Instant::now(), tx);
+        while !queue.scheduled.is_empty() {
+            make_due(&mut queue);
+            queue.take_due_pictures(Some(&mut camera));
+        }
+
+        assert_eq!(state.borrow().taken.len(), MAX_CAPTURE_ATTEMPTS as usize);
+        assert_eq!(answer(&mut rx), Some(Err(CaptureError::Busy)));
+    }
+
+    #[test]
+    fn reports_a_failed_focus_right_away() {
+        let (state, mut camera) = fake_camera();
+        state
+            .borrow_mut()
+            .results
+            .push_back(Err(CaptureError::FocusFailed));
+        let mut queue = queue();
+
+        let (tx, mut rx) = channel(16);
+        queue.schedule_picture(Some(&mut camera), Instant::now(), tx);
+
+        assert_eq!(answer(&mut rx), Some(Err(CaptureError::FocusFailed)));
+        assert!(queue.scheduled.is_empty());
+    }
+
+    #[test]
+    fn retries_errors_the_camera_reports_later() {
+        let (state, mut camera) = fake_camera();
+        let mut queue = queue();
+
+        let (tx, mut rx) = channel(16);
+        queue.schedule_picture(Some(&mut camera), Instant::now(), tx);
+        let (tx, mut other) = channel(16);
+        queue.schedule_picture(Some(&mut camera), Instant::now(), tx);
+
+        state
+            .borrow_mut()
+            .failed
+            .push((CaptureId(0), CaptureError::Busy));
+        queue.handle_finished_captures(camera.as_mut());
+
+        assert_eq!(queue.scheduled.len(), 1);
+        assert_eq!(queue.scheduled[0].id, CaptureId(0));
+        assert_eq!(queue.pending.len(), 1);
+        assert_eq!(queue.pending[0].id, CaptureId(1));
+        assert!(answer(&mut rx).is_none());
+        assert!(answer(&mut other).is_none());
+    }
+
+    #[test]
+    fn expires_only_late_photos() {
+        let (state, mut camera) = fake_camera();
+        let mut queue = queue();
+
+        let (tx, mut late) = channel(16);
+        queue.schedule_picture(Some(&mut camera), Instant::now(), tx);
+        let (tx, mut on_time) = channel(16);
+        queue.schedule_picture(Some(&mut camera), Instant::now(), tx);
+
+        queue.pending[0].deadline = Instant::now();
+        queue.expire(Some(&mut camera));
+
+        assert_eq!(answer(&mut late), Some(Err(CaptureError::Timeout)));
+        assert!(answer(&mut on_time).is_none());
+        assert_eq!(state.borrow().abandoned, [CaptureId(0)]);
+        assert_eq!(queue.pending.len(), 1);
+        assert_eq!(queue.pending[0].id, CaptureId(1));
+    }
+
+    #[test]
+    fn forgets_photos_the_camera_answered() {
+        let (state, mut camera) = fake_camera();
+        let mut queue = queue();
+
+        // the burst's receivers stay open until the last photo is answered
+        let (tx, mut answered) = channel(16);
+        queue.schedule_picture(Some(&mut camera), Instant::now(), tx);
+        let (tx, mut late) = channel(16);
+        queue.schedule_picture(Some(&mut camera), Instant::now(), tx);
+
+        state.borrow_mut().completed.push(CaptureId(0));
+        queue.handle_finished_captures(camera.as_mut());
+
+        for capture in &mut queue.pending {
+            capture.deadline = Instant::now();
+        }
+        queue.expire(Some(&mut camera));
+
+        assert!(answer(&mut answered).is_none());
+        assert_eq!(answer(&mut late), Some(Err(CaptureError::Timeout)));
+        assert_eq!(state.borrow().abandoned, [CaptureId(1)]);
+        assert!(queue.pending.is_empty());
+    }
+}
@@ -0,0 +1,23 @@
+use crate::{camera::CardFile, commands::camera};
+
+#[tauri::command]
+pub async fn list_card_files() -> Result<Vec<CardFile>, String> {
+    camera()?.list_card_files().await
+}
+
+/// Downloads a file from the card into the camera photo directory, returns the file name
+#[tauri::command]
+pub async fn download_card_file(file: CardFile) -> Result<String, String> {
+    camera()?.download_card_file(file).await
+}
+
+#[tauri::command]
+pub async fn delete_card_file(file: CardFile) -> Result<(), String> {
+    camera()?.delete_card_file(file).await
+}
+
+/// Deletes everything on the camera's cards
+#[tauri::command]
+pub async fn format_cards() -> Result<(), String> {
+    camera()?.format_cards().await
+}
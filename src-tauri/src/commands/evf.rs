@@ -0,0 +1,14 @@
+use tauri::ipc::Response;
+
+use crate::camera::EVF_FRAME;
+
+/// Returns the latest live view frame as raw jpeg bytes
+#[tauri::command]
+pub fn get_evf_frame() -> Result<Response, String> {
+    EVF_FRAME
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|frame| Response::new(frame.jpeg.clone()))
+        .ok_or_else(|| "No live view frame available".to_string())
+}
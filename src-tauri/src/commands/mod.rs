@@ -1,5 +1,25 @@
-mod mail;
-pub use mail::*;
-
-mod photo;
-pub use photo::*;
+mod card;
+pub use card::*;
+
+mod evf;
+pub use evf::*;
+
+mod mail;
+pub use mail::*;
+
+mod photo;
+pub use photo::*;
+
+mod settings;
+pub use settings::*;
+
+mod status;
+pub use status::*;
+
+use crate::{camera_thread::CameraHandle, CAMERA};
+
+fn camera() -> Result<&'static CameraHandle, String> {
+    CAMERA
+        .get()
+        .ok_or_else(|| "No camera connected".to_string())
+}
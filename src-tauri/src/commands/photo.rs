@@ -1,14 +1,61 @@
-use tauri::async_runtime::channel;
+use std::time::Duration;
 
-use crate::CAMERA;
+use serde::Serialize;
+use tauri::{ipc::Response, AppHandle, Emitter};
 
+use crate::{
+    camera::{CaptureError, CaptureUpdate, CAPTURE_PREVIEW},
+    commands::camera,
+};
+
+/// The payload of `capture-progress`
+#[derive(Clone, Serialize)]
+struct CaptureProgress {
+    percent: u32,
+    /// Which photo of the burst is downloaded
+    index: usize,
+}
+
+/// Takes `count` photos, one by default, `interval` milliseconds apart and returns their file
+/// names in order. `capture-preview` is emitted with the index of the photo as soon as its
+/// thumbnail is available through `get_capture_preview`, `capture-progress` while it's
+/// downloaded. Errors are a [`CaptureError`] tagged with its `kind`
 #[tauri::command]
-pub async fn take_photo() -> Result<String, String> {
-    let (tx, mut rx) = channel(1);
+pub async fn take_photo(
+    app: AppHandle,
+    count: Option<u32>,
+    interval: Option<u64>,
+) -> Result<Vec<String>, CaptureError> {
+    let camera = camera().map_err(|_| CaptureError::Disconnected)?;
 
-    let _ = CAMERA.lock().await.as_ref().unwrap().take_picture(tx);
+    let count = count.unwrap_or(1).max(1);
+    let interval = Duration::from_millis(interval.unwrap_or_default());
 
-    let result = rx.recv().await.expect("Channel has hung up");
+    let result = camera
+        .capture(count, interval, |index, update| match update {
+            CaptureUpdate::Preview => {
+                let _ = app.emit("capture-preview", index);
+            }
+            CaptureUpdate::Progress(percent) => {
+                let _ = app.emit("capture-progress", CaptureProgress { percent, index });
+            }
+            CaptureUpdate::Done(_) => {}
+        })
+        .await;
 
+    if let Err(err) = &result {
+        println!("Failed to take photo: {}", err);
+    }
     result
 }
+
+/// Returns the thumbnail of the latest photo as raw jpeg bytes
+#[tauri::command]
+pub fn get_capture_preview() -> Result<Response, String> {
+    CAPTURE_PREVIEW
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|jpeg| Response::new(jpeg.clone()))
+        .ok_or_else(|| "No capture preview available".to_string())
+}
@@ -0,0 +1,16 @@
+use crate::{
+    camera::{CameraSetting, SettingValue},
+    commands::camera,
+};
+
+/// Returns the values the camera currently accepts for `setting`
+#[tauri::command]
+pub async fn get_available_values(setting: CameraSetting) -> Result<Vec<SettingValue>, String> {
+    camera()?.available_values(setting).await
+}
+
+/// Sets `setting` to `code`, one of the codes returned by `get_available_values`
+#[tauri::command]
+pub async fn set_setting(setting: CameraSetting, code: u32) -> Result<(), String> {
+    camera()?.set_setting(setting, code).await
+}
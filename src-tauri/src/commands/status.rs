@@ -0,0 +1,20 @@
+use std::sync::atomic::Ordering;
+
+use crate::camera::{CameraStatus, CAMERA_CONNECTED, CAMERA_STATUS};
+
+/// Returns the latest battery, storage and mode status, `camera-status` is emitted when it changes
+#[tauri::command]
+pub fn camera_status() -> Result<CameraStatus, String> {
+    CAMERA_STATUS
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or_else(|| "No camera status available".to_string())
+}
+
+/// Whether a camera is connected, `camera-connected` and `camera-disconnected` are emitted when
+/// this changes
+#[tauri::command]
+pub fn camera_connected() -> bool {
+    CAMERA_CONNECTED.load(Ordering::SeqCst)
+}
@@ -0,0 +1,104 @@
+use std::{path::PathBuf, time::Duration};
+
+use edsdk::EdsImageQuality;
+
+/// Where photos and live view frames come from
+#[derive(Clone, Debug)]
+pub enum Backend {
+    /// A Canon camera, the default
+    Edsdk,
+    /// Replays recorded images instead of talking to a camera, for demos and frontend work
+    /// without the hardware. `path` is a folder of jpegs, each capture takes the next one, or an
+    /// mjpeg file that's looped as the live view
+    Replay { path: PathBuf },
+    /// Any camera libgphoto2 supports, needs the `gphoto2` feature. `model` and `port` select a
+    /// driver instead of the first camera found, e.g. `Directory Browse` on `disk:/path`
+    Gphoto2 {
+        model: Option<String>,
+        port: Option<String>,
+    },
+}
+
+/// Camera settings read from the `PHOTOBOOTH_*` environment variables
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// `PHOTOBOOTH_BACKEND`, `edsdk`, `replay` or `gphoto2`. `replay` reads
+    /// `PHOTOBOOTH_REPLAY_PATH`, `gphoto2` reads `PHOTOBOOTH_GPHOTO2_MODEL` and
+    /// `PHOTOBOOTH_GPHOTO2_PORT`
+    pub backend: Backend,
+
+    /// `PHOTOBOOTH_IMAGE_QUALITY`, a variant name of [`EdsImageQuality`] like `JpegLargeFine`
+    pub image_quality: EdsImageQuality,
+
+    /// `PHOTOBOOTH_CAMERA_SERIAL`, the body ID of the camera to use as printed by
+    /// `edsdk-cli list`. The first camera is used if it's not set
+    pub camera_serial: Option<String>,
+
+    /// `PHOTOBOOTH_AUTO_POWER_OFF`, the seconds after which an idle camera powers off, `0`
+    /// disables it. The camera's setting is kept if it's not set. The app keeps the camera awake
+    /// either way, this is the fallback when it's left alone
+    pub auto_power_off: Option<u32>,
+
+    /// `PHOTOBOOTH_CAPTURE_TIMEOUT`, the seconds to wait for a photo after releasing the shutter,
+    /// `30` if it's not set
+    pub capture_timeout: Duration,
+}
+
+impl Config {
+    pub fn from_env() -> Result<Self, String> {
+        let backend = match std::env::var("PHOTOBOOTH_BACKEND").as_deref() {
+            Ok("edsdk") | Err(_) => Backend::Edsdk,
+            Ok("replay") => Backend::Replay {
+                path: std::env::var("PHOTOBOOTH_REPLAY_PATH")
+                    .map_err(|_| "PHOTOBOOTH_REPLAY_PATH is required by the replay backend")?
+                    .into(),
+            },
+            Ok("gphoto2") => Backend::Gphoto2 {
+                model: non_empty_var("PHOTOBOOTH_GPHOTO2_MODEL"),
+                port: non_empty_var("PHOTOBOOTH_GPHOTO2_PORT"),
+            },
+            Ok(value) => return Err(format!("Invalid PHOTOBOOTH_BACKEND: {}", value)),
+        };
+
+        let image_quality = match std::env::var("PHOTOBOOTH_IMAGE_QUALITY") {
+            Ok(value) => value
+                .parse()
+                .map_err(|_| format!("Invalid PHOTOBOOTH_IMAGE_QUALITY: {}", value))?,
+            Err(_) => EdsImageQuality::JpegSmall,
+        };
+
+        let camera_serial = non_empty_var("PHOTOBOOTH_CAMERA_SERIAL");
+
+        let auto_power_off = match std::env::var("PHOTOBOOTH_AUTO_POWER_OFF") {
+            Ok(value) => Some(
+                value
+                    .parse()
+                    .map_err(|_| format!("Invalid PHOTOBOOTH_AUTO_POWER_OFF: {}", value))?,
+            ),
+            Err(_) => None,
+        };
+
+        let capture_timeout = match std::env::var("PHOTOBOOTH_CAPTURE_TIMEOUT") {
+            Ok(value) => Duration::from_secs(
+                value
+                    .parse()
+                    .map_err(|_| format!("Invalid PHOTOBOOTH_CAPTURE_TIMEOUT: {}", value))?,
+            ),
+            Err(_) => Duration::from_secs(30),
+        };
+
+        Ok(Self {
+            backend,
+            image_quality,
+            camera_serial,
+            auto_power_off,
+            capture_timeout,
+        })
+    }
+}
+
+fn non_empty_var(name: &str) -> Option<String> {
+    std::env::var(name)
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+}
@@ -0,0 +1,303 @@
+//! The parts of libgphoto2 (2.5) the backend uses
+
+use std::ffi::{c_char, c_int, c_uint, c_ulong, c_void};
+
+pub const GP_OK: c_int = 0;
+pub const GP_ERROR_TIMEOUT: c_int = -10;
+pub const GP_ERROR_IO_READ: c_int = -34;
+pub const GP_ERROR_IO_WRITE: c_int = -35;
+pub const GP_ERROR_IO_USB_FIND: c_int = -52;
+pub const GP_ERROR_MODEL_NOT_FOUND: c_int = -105;
+pub const GP_ERROR_CAMERA_BUSY: c_int = -110;
+
+#[repr(C)]
+pub struct GPContext {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct Camera {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct CameraFile {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct CameraWidget {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct CameraList {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct CameraAbilitiesList {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct GPPortInfoList {
+    _private: [u8; 0],
+}
+
+pub type GPPortInfo = *mut c_void;
+
+#[repr(C)]
+pub struct CameraFilePath {
+    pub name: [c_char; 128],
+    pub folder: [c_char; 1024],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct CameraAbilities {
+    pub model: [c_char; 128],
+    pub status: c_int,
+    pub port: c_int,
+    pub speed: [c_int; 64],
+    pub operations: c_int,
+    pub file_operations: c_int,
+    pub folder_operations: c_int,
+    pub usb_vendor: c_int,
+    pub usb_product: c_int,
+    pub usb_class: c_int,
+    pub usb_subclass: c_int,
+    pub usb_protocol: c_int,
+    pub library: [c_char; 1024],
+    pub id: [c_char; 1024],
+    pub device_type: c_int,
+    pub reserved: [c_int; 7],
+}
+
+/// Which of the [`CameraStorageInformation`] fields are valid
+pub const GP_STORAGEINFO_FREESPACEKBYTES: c_uint = 1 << 7;
+pub const GP_STORAGEINFO_FREESPACEIMAGES: c_uint = 1 << 8;
+
+#[repr(C)]
+pub struct CameraStorageInformation {
+    pub fields: c_uint,
+    pub basedir: [c_char; 256],
+    pub label: [c_char; 256],
+    pub description: [c_char; 256],
+    pub storage_type: c_int,
+    pub fs_type: c_int,
+    pub access: c_int,
+    pub capacity_kbytes: u64,
+    pub free_kbytes: u64,
+    pub free_images: u64,
+}
+
+/// Which of the [`CameraFileInfoFile`] fields are valid
+pub const GP_FILE_INFO_SIZE: c_int = 1 << 2;
+
+#[repr(C)]
+pub struct CameraFileInfoPreview {
+    pub fields: c_int,
+    pub status: c_int,
+    pub size: u64,
+    pub mime_type: [c_char; 64],
+    pub width: u32,
+    pub height: u32,
+}
+
+#[repr(C)]
+pub struct CameraFileInfoFile {
+    pub fields: c_int,
+    pub status: c_int,
+    pub size: u64,
+    pub mime_type: [c_char; 64],
+    pub width: u32,
+    pub height: u32,
+    pub permissions: c_int,
+    pub mtime: i64,
+}
+
+#[repr(C)]
+pub struct CameraFileInfoAudio {
+    pub fields: c_int,
+    pub status: c_int,
+    pub size: u64,
+    pub mime_type: [c_char; 64],
+}
+
+#[repr(C)]
+pub struct CameraFileInfo {
+    pub preview: CameraFileInfoPreview,
+    pub file: CameraFileInfoFile,
+    pub audio: CameraFileInfoAudio,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CameraCaptureType {
+    Image = 0,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CameraFileType {
+    Preview = 0,
+    Normal = 1,
+}
+
+/// Filled in by libgphoto2, so plain integers rather than enums
+pub type CameraEventType = c_int;
+pub const GP_EVENT_TIMEOUT: CameraEventType = 1;
+pub const GP_EVENT_FILE_ADDED: CameraEventType = 2;
+
+pub type CameraWidgetType = c_int;
+pub const GP_WIDGET_TEXT: CameraWidgetType = 2;
+pub const GP_WIDGET_RADIO: CameraWidgetType = 5;
+pub const GP_WIDGET_MENU: CameraWidgetType = 6;
+
+#[link(name = "gphoto2")]
+extern "C" {
+    pub fn gp_context_new() -> *mut GPContext;
+    pub fn gp_context_unref(context: *mut GPContext);
+
+    pub fn gp_result_as_string(result: c_int) -> *const c_char;
+
+    pub fn gp_camera_new(camera: *mut *mut Camera) -> c_int;
+    pub fn gp_camera_set_abilities(camera: *mut Camera, abilities: CameraAbilities) -> c_int;
+    pub fn gp_camera_set_port_info(camera: *mut Camera, info: GPPortInfo) -> c_int;
+    pub fn gp_camera_init(camera: *mut Camera, context: *mut GPContext) -> c_int;
+    pub fn gp_camera_exit(camera: *mut Camera, context: *mut GPContext) -> c_int;
+    pub fn gp_camera_unref(camera: *mut Camera) -> c_int;
+
+    pub fn gp_camera_capture(
+        camera: *mut Camera,
+        capture_type: CameraCaptureType,
+        path: *mut CameraFilePath,
+        context: *mut GPContext,
+    ) -> c_int;
+    pub fn gp_camera_capture_preview(
+        camera: *mut Camera,
+        file: *mut CameraFile,
+        context: *mut GPContext,
+    ) -> c_int;
+    pub fn gp_camera_wait_for_event(
+        camera: *mut Camera,
+        timeout: c_int,
+        event_type: *mut CameraEventType,
+        event_data: *mut *mut c_void,
+        context: *mut GPContext,
+    ) -> c_int;
+    pub fn gp_camera_get_storageinfo(
+        camera: *mut Camera,
+        storage_info: *mut *mut CameraStorageInformation,
+        count: *mut c_int,
+        context: *mut GPContext,
+    ) -> c_int;
+
+    pub fn gp_camera_folder_list_files(
+        camera: *mut Camera,
+        folder: *const c_char,
+        list: *mut CameraList,
+        context: *mut GPContext,
+    ) -> c_int;
+    pub fn gp_camera_folder_list_folders(
+        camera: *mut Camera,
+        folder: *const c_char,
+        list: *mut CameraList,
+        context: *mut GPContext,
+    ) -> c_int;
+    pub fn gp_camera_file_get(
+        camera: *mut Camera,
+        folder: *const c_char,
+        file: *const c_char,
+        file_type: CameraFileType,
+        camera_file: *mut CameraFile,
+        context: *mut GPContext,
+    ) -> c_int;
+    pub fn gp_camera_file_get_info(
+        camera: *mut Camera,
+        folder: *const c_char,
+        file: *const c_char,
+        info: *mut CameraFileInfo,
+        context: *mut GPContext,
+    ) -> c_int;
+    pub fn gp_camera_file_delete(
+        camera: *mut Camera,
+        folder: *const c_char,
+        file: *const c_char,
+        context: *mut GPContext,
+    ) -> c_int;
+
+    pub fn gp_camera_get_single_config(
+        camera: *mut Camera,
+        name: *const c_char,
+        widget: *mut *mut CameraWidget,
+        context: *mut GPContext,
+    ) -> c_int;
+    pub fn gp_camera_set_single_config(
+        camera: *mut Camera,
+        name: *const c_char,
+        widget: *mut CameraWidget,
+        context: *mut GPContext,
+    ) -> c_int;
+
+    pub fn gp_widget_get_type(
+        widget: *mut CameraWidget,
+        widget_type: *mut CameraWidgetType,
+    ) -> c_int;
+    pub fn gp_widget_get_value(widget: *mut CameraWidget, value: *mut c_void) -> c_int;
+    pub fn gp_widget_set_value(widget: *mut CameraWidget, value: *const c_void) -> c_int;
+    pub fn gp_widget_count_choices(widget: *mut CameraWidget) -> c_int;
+    pub fn gp_widget_get_choice(
+        widget: *mut CameraWidget,
+        choice_number: c_int,
+        choice: *mut *const c_char,
+    ) -> c_int;
+    pub fn gp_widget_free(widget: *mut CameraWidget) -> c_int;
+
+    pub fn gp_file_new(file: *mut *mut CameraFile) -> c_int;
+    pub fn gp_file_get_data_and_size(
+        file: *mut CameraFile,
+        data: *mut *const c_char,
+        size: *mut c_ulong,
+    ) -> c_int;
+    pub fn gp_file_unref(file: *mut CameraFile) -> c_int;
+
+    pub fn gp_list_new(list: *mut *mut CameraList) -> c_int;
+    pub fn gp_list_count(list: *mut CameraList) -> c_int;
+    pub fn gp_list_get_name(list: *mut CameraList, index: c_int, name: *mut *const c_char)
+        -> c_int;
+    pub fn gp_list_free(list: *mut CameraList) -> c_int;
+
+    pub fn gp_abilities_list_new(list: *mut *mut CameraAbilitiesList) -> c_int;
+    pub fn gp_abilities_list_load(list: *mut CameraAbilitiesList, context: *mut GPContext)
+        -> c_int;
+    pub fn gp_abilities_list_lookup_model(
+        list: *mut CameraAbilitiesList,
+        model: *const c_char,
+    ) -> c_int;
+    pub fn gp_abilities_list_get_abilities(
+        list: *mut CameraAbilitiesList,
+        index: c_int,
+        abilities: *mut CameraAbilities,
+    ) -> c_int;
+    pub fn gp_abilities_list_free(list: *mut CameraAbilitiesList) -> c_int;
+}
+
+#[link(name = "gphoto2_port")]
+extern "C" {
+    pub fn gp_port_info_list_new(list: *mut *mut GPPortInfoList) -> c_int;
+    pub fn gp_port_info_list_load(list: *mut GPPortInfoList) -> c_int;
+    pub fn gp_port_info_list_lookup_path(list: *mut GPPortInfoList, path: *const c_char) -> c_int;
+    pub fn gp_port_info_list_get_info(
+        list: *mut GPPortInfoList,
+        index: c_int,
+        info: *mut GPPortInfo,
+    ) -> c_int;
+    pub fn gp_port_info_list_free(list: *mut GPPortInfoList) -> c_int;
+}
+
+extern "C" {
+    /// Event data and storage infos are allocated by libgphoto2 with `malloc`
+    pub fn free(ptr: *mut c_void);
+}
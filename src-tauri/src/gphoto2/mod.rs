@@ -0,0 +1,749 @@
+//! A backend for every camera libgphoto2 supports, e.g. Nikon and Sony bodies
+
+mod ffi;
+#[cfg(test)]
+mod tests;
+
+use std::{
+    collections::VecDeque,
+    ffi::{c_char, c_int, c_ulong, c_void, CStr, CString},
+    fmt, fs,
+    path::Path,
+    ptr,
+};
+
+use tauri::async_runtime::Sender;
+
+use crate::{
+    backend::{BackendError, CameraBackend, CaptureId},
+    camera::{
+        photo_file_name, unique_file_name, CameraEvent, CameraSetting, CameraStatus, CaptureError,
+        CaptureUpdate, CardFile, LiveViewFrame, SettingValue, CAPTURE_PREVIEW, LOW_BATTERY_LEVEL,
+    },
+    path::{CAMERA_PHOTO_DIR, UNSOLICITED_PHOTO_DIR},
+};
+
+/// How many queued camera events are handled per poll, the rest waits for the next one
+const MAX_EVENTS_PER_POLL: usize = 16;
+
+/// How many of the latest photos taken through `take_picture` are told apart from the files
+/// added on the camera, a burst can take several before their events are polled
+const RECENT_CAPTURES: usize = 32;
+
+/// A libgphoto2 result code below `GP_OK`
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Gphoto2Error(c_int);
+
+impl fmt::Display for Gphoto2Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = unsafe { CStr::from_ptr(ffi::gp_result_as_string(self.0)) };
+        write!(f, "{} ({})", message.to_string_lossy(), self.0)
+    }
+}
+
+impl std::error::Error for Gphoto2Error {}
+
+impl From<Gphoto2Error> for CaptureError {
+    fn from(err: Gphoto2Error) -> Self {
+        match err.0 {
+            ffi::GP_ERROR_CAMERA_BUSY => Self::Busy,
+            ffi::GP_ERROR_TIMEOUT => Self::Timeout,
+            ffi::GP_ERROR_IO_READ
+            | ffi::GP_ERROR_IO_WRITE
+            | ffi::GP_ERROR_IO_USB_FIND
+            | ffi::GP_ERROR_MODEL_NOT_FOUND => Self::Disconnected,
+            _ => Self::Failed {
+                message: err.to_string(),
+            },
+        }
+    }
+}
+
+/// Turns a libgphoto2 result into an error if it's negative, some functions return a count or
+/// index otherwise
+fn check(result: c_int) -> Result<c_int, Gphoto2Error> {
+    if result < ffi::GP_OK {
+        Err(Gphoto2Error(result))
+    } else {
+        Ok(result)
+    }
+}
+
+fn c_string(value: &str) -> Result<CString, BackendError> {
+    CString::new(value).map_err(|_| format!("{:?} contains a nul byte", value).into())
+}
+
+/// # Safety
+/// `value` must be null or a nul terminated string
+unsafe fn from_c_str(value: *const c_char) -> String {
+    if value.is_null() {
+        String::new()
+    } else {
+        CStr::from_ptr(value).to_string_lossy().into_owned()
+    }
+}
+
+/// A file libgphoto2 fills in memory
+struct File(*mut ffi::CameraFile);
+
+impl File {
+    fn new() -> Result<Self, Gphoto2Error> {
+        let mut file = ptr::null_mut();
+        check(unsafe { ffi::gp_file_new(&mut file) })?;
+        Ok(Self(file))
+    }
+
+    fn data(&self) -> Result<&[u8], Gphoto2Error> {
+        let mut data = ptr::null();
+        let mut size: c_ulong = 0;
+        check(unsafe { ffi::gp_file_get_data_and_size(self.0, &mut data, &mut size) })?;
+
+        if data.is_null() {
+            return Ok(&[]);
+        }
+        Ok(unsafe { std::slice::from_raw_parts(data as *const u8, size as usize) })
+    }
+}
+
+impl Drop for File {
+    fn drop(&mut self) {
+        unsafe { ffi::gp_file_unref(self.0) };
+    }
+}
+
+/// A list of names, e.g. the files in a folder
+struct List(*mut ffi::CameraList);
+
+impl List {
+    fn new() -> Result<Self, Gphoto2Error> {
+        let mut list = ptr::null_mut();
+        check(unsafe { ffi::gp_list_new(&mut list) })?;
+        Ok(Self(list))
+    }
+
+    fn names(&self) -> Result<Vec<String>, Gphoto2Error> {
+        let count = check(unsafe { ffi::gp_list_count(self.0) })?;
+
+        (0..count)
+            .map(|index| {
+                let mut name = ptr::null();
+                check(unsafe { ffi::gp_list_get_name(self.0, index, &mut name) })?;
+                Ok(unsafe { from_c_str(name) })
+            })
+            .collect()
+    }
+}
+
+impl Drop for List {
+    fn drop(&mut self) {
+        unsafe { ffi::gp_list_free(self.0) };
+    }
+}
+
+/// A single config entry of the camera, like `iso` or `batterylevel`
+struct Widget(*mut ffi::CameraWidget);
+
+impl Widget {
+    fn widget_type(&self) -> Result<ffi::CameraWidgetType, Gphoto2Error> {
+        let mut widget_type = 0;
+        check(unsafe { ffi::gp_widget_get_type(self.0, &mut widget_type) })?;
+        Ok(widget_type)
+    }
+
+    /// The current value, if it's text or one of the [`Self::choices`]
+    fn value(&self) -> Result<Option<String>, Gphoto2Error> {
+        if !matches!(
+            self.widget_type()?,
+            ffi::GP_WIDGET_TEXT | ffi::GP_WIDGET_RADIO | ffi::GP_WIDGET_MENU
+        ) {
+            return Ok(None);
+        }
+
+        let mut value: *const c_char = ptr::null();
+        check(unsafe {
+            ffi::gp_widget_get_value(self.0, &mut value as *mut *const c_char as *mut c_void)
+        })?;
+        Ok(Some(unsafe { from_c_str(value) }))
+    }
+
+    fn set_value(&mut self, value: &str) -> Result<(), BackendError> {
+        let value = c_string(value)?;
+        check(unsafe { ffi::gp_widget_set_value(self.0, value.as_ptr() as *const c_void) })?;
+        Ok(())
+    }
+
+    fn choices(&self) -> Result<Vec<String>, Gphoto2Error> {
+        let count = check(unsafe { ffi::gp_widget_count_choices(self.0) })?;
+
+        (0..count)
+            .map(|index| {
+                let mut choice = ptr::null();
+                check(unsafe { ffi::gp_widget_get_choice(self.0, index, &mut choice) })?;
+                Ok(unsafe { from_c_str(choice) })
+            })
+            .collect()
+    }
+}
+
+impl Drop for Widget {
+    fn drop(&mut self) {
+        unsafe { ffi::gp_widget_free(self.0) };
+    }
+}
+
+/// The config names a setting goes by, drivers name them differently, the first one the camera
+/// has is used
+fn config_names(setting: CameraSetting) -> &'static [&'static str] {
+    match setting {
+        CameraSetting::Iso => &["iso"],
+        CameraSetting::Av => &["aperture", "f-number"],
+        CameraSetting::Tv => &["shutterspeed", "shutterspeed2"],
+        CameraSetting::WhiteBalance => &["whitebalance"],
+        CameraSetting::PictureStyle => &["picturestyle"],
+    }
+}
+
+/// The config names of the mode dial position
+const MODE_CONFIG_NAMES: &[&str] = &["autoexposuremode", "expprogram"];
+
+/// A photo that was taken but isn't downloaded yet
+struct PendingCapture {
+    id: CaptureId,
+    folder: String,
+    name: String,
+    respond_to: Sender<CaptureUpdate>,
+}
+
+/// A camera talked to through libgphoto2. Without a model and port the first camera found is
+/// used, the model and port select a specific driver, e.g. `Directory Browse` on `disk:/path`
+pub struct Gphoto2Camera {
+    context: *mut ffi::GPContext,
+    camera: *mut ffi::Camera,
+    /// The port info set on the camera points into this list, so it lives as long as the camera
+    port_infos: *mut ffi::GPPortInfoList,
+    /// The paths of the latest photos taken through `take_picture`, some drivers report each as
+    /// an added file as well
+    recent_captures: VecDeque<String>,
+    /// Downloaded one per poll, in the order they were taken
+    pending: VecDeque<PendingCapture>,
+    completed: Vec<CaptureId>,
+}
+
+impl Gphoto2Camera {
+    pub fn open(model: Option<&str>, port: Option<&str>) -> Result<Self, BackendError> {
+        let mut camera = Self {
+            context: unsafe { ffi::gp_context_new() },
+            camera: ptr::null_mut(),
+            port_infos: ptr::null_mut(),
+            recent_captures: VecDeque::new(),
+            pending: VecDeque::new(),
+            completed: Vec::new(),
+        };
+        check(unsafe { ffi::gp_camera_new(&mut camera.camera) })?;
+
+        if let Some(model) = model {
+            camera.set_model(model)?;
+        }
+        if let Some(port) = port {
+            camera.set_port(port)?;
+        }
+
+        check(unsafe { ffi::gp_camera_init(camera.camera, camera.context) })
+            .map_err(|err| format!("Failed to open the camera: {}", err))?;
+
+        Ok(camera)
+    }
+
+    fn set_model(&mut self, model: &str) -> Result<(), BackendError> {
+        let mut list = ptr::null_mut();
+        check(unsafe { ffi::gp_abilities_list_new(&mut list) })?;
+
+        let result = (|| {
+            check(unsafe { ffi::gp_abilities_list_load(list, self.context) })?;
+            let index = check(unsafe {
+                ffi::gp_abilities_list_lookup_model(list, c_string(model)?.as_ptr())
+            })
+            .map_err(|_| format!("Unknown camera model: {}", model))?;
+
+            let mut abilities = unsafe { std::mem::zeroed::<ffi::CameraAbilities>() };
+            check(unsafe { ffi::gp_abilities_list_get_abilities(list, index, &mut abilities) })?;
+            check(unsafe { ffi::gp_camera_set_abilities(self.camera, abilities) })?;
+            Ok(())
+        })();
+
+        unsafe { ffi::gp_abilities_list_free(list) };
+        result
+    }
+
+    fn set_port(&mut self, port: &str) -> Result<(), BackendError> {
+        check(unsafe { ffi::gp_port_info_list_new(&mut self.port_infos) })?;
+        check(unsafe { ffi::gp_port_info_list_load(self.port_infos) })?;
+
+        let index = check(unsafe {
+            ffi::gp_port_info_list_lookup_path(self.port_infos, c_string(port)?.as_ptr())
+        })
+        .map_err(|_| format!("Unknown port: {}", port))?;
+
+        let mut info = ptr::null_mut();
+        check(unsafe { ffi::gp_port_info_list_get_info(self.port_infos, index, &mut info) })?;
+        check(unsafe { ffi::gp_camera_set_port_info(self.camera, info) })?;
+        Ok(())
+    }
+
+    fn config(&self, name: &str) -> Result<Widget, BackendError> {
+        let mut widget = ptr::null_mut();
+        check(unsafe {
+            ffi::gp_camera_get_single_config(
+                self.camera,
+                c_string(name)?.as_ptr(),
+                &mut widget,
+                self.context,
+            )
+        })?;
+        Ok(Widget(widget))
+    }
+
+    /// The first of `names` the camera has, along with the name it goes by
+    fn find_config(
+        &self,
+        names: &'static [&'static str],
+    ) -> Result<(&'static str, Widget), BackendError> {
+        names
+            .iter()
+            .find_map(|name| self.config(name).ok().map(|widget| (*name, widget)))
+            .ok_or_else(|| format!("The camera has none of {}", names.join(", ")).into())
+    }
+
+    fn get_file(
+        &self,
+        folder: &str,
+        name: &str,
+        file_type: ffi::CameraFileType,
+    ) -> Result<File, BackendError> {
+        let file = File::new()?;
+        check(unsafe {
+            ffi::gp_camera_file_get(
+                self.camera,
+                c_string(folder)?.as_ptr(),
+                c_string(name)?.as_ptr(),
+                file_type,
+                file.0,
+                self.context,
+            )
+        })?;
+        Ok(file)
+    }
+
+    fn file_size(&self, folder: &str, name: &str) -> Result<u64, BackendError> {
+        let mut info = unsafe { std::mem::zeroed::<ffi::CameraFileInfo>() };
+        check(unsafe {
+            ffi::gp_camera_file_get_info(
+                self.camera,
+                c_string(folder)?.as_ptr(),
+                c_string(name)?.as_ptr(),
+                &mut info,
+                self.context,
+            )
+        })?;
+
+        Ok(if info.file.fields & ffi::GP_FILE_INFO_SIZE != 0 {
+            info.file.size
+        } else {
+            0
+        })
+    }
+
+    /// Adds the files in `folder` and its subfolders to `files`
+    fn collect_files(&self, folder: &str, files: &mut Vec<String>) -> Result<(), BackendError> {
+        let folder_c = c_string(folder)?;
+
+        let names = List::new()?;
+        check(unsafe {
+            ffi::gp_camera_folder_list_files(self.camera, folder_c.as_ptr(), names.0, self.context)
+        })?;
+        files.extend(names.names()?.into_iter().map(|name| join(folder, &name)));
+
+        let subfolders = List::new()?;
+        check(unsafe {
+            ffi::gp_camera_folder_list_folders(
+                self.camera,
+                folder_c.as_ptr(),
+                subfolders.0,
+                self.context,
+            )
+        })?;
+        for subfolder in subfolders.names()? {
+            self.collect_files(&join(folder, &subfolder), files)?;
+        }
+
+        Ok(())
+    }
+
+    /// Releases the shutter and waits for the photo
+    fn release_shutter(&mut self) -> Result<ffi::CameraFilePath, CaptureError> {
+        let mut path = unsafe { std::mem::zeroed::<ffi::CameraFilePath>() };
+        check(unsafe {
+            ffi::gp_camera_capture(
+                self.camera,
+                ffi::CameraCaptureType::Image,
+                &mut path,
+                self.context,
+            )
+        })?;
+        Ok(path)
+    }
+
+    /// Downloads a photo taken through `take_picture` and reports it to whoever asked for it
+    fn download(&mut self, capture: PendingCapture) {
+        let result = self.save_capture(&capture.folder, &capture.name, &capture.respond_to);
+
+        if let Ok(file_name) = &result {
+            println!("Photo saved successfully: {}", file_name);
+        }
+        let _ = capture
+            .respond_to
+            .blocking_send(CaptureUpdate::Done(result));
+        self.completed.push(capture.id);
+    }
+
+    fn save_capture(
+        &mut self,
+        folder: &str,
+        name: &str,
+        respond_to: &Sender<CaptureUpdate>,
+    ) -> Result<String, CaptureError> {
+        // drivers that can't extract a thumbnail just skip the preview
+        match self.get_file(folder, name, ffi::CameraFileType::Preview) {
+            Ok(preview) => {
+                *CAPTURE_PREVIEW.lock().unwrap() =
+                    Some(preview.data().unwrap_or_default().to_vec());
+                let _ = respond_to.blocking_send(CaptureUpdate::Preview);
+            }
+            Err(err) => println!("No preview available: {}", err),
+        }
+
+        let camera_dir = CAMERA_PHOTO_DIR
+            .get()
+            .expect("CAMERA_PHOTO_DIR not initialized");
+        let file_name = photo_file_name(camera_dir);
+        self.save_file(folder, name, camera_dir, &file_name)
+            .map_err(|err| CaptureError::DownloadFailed {
+                message: err.to_string(),
+            })?;
+        let _ = respond_to.blocking_send(CaptureUpdate::Progress(100));
+
+        Ok(file_name)
+    }
+
+    /// Downloads a file into `dir` as `file_name`
+    fn save_file(
+        &self,
+        folder: &str,
+        name: &str,
+        dir: &Path,
+        file_name: &str,
+    ) -> Result<(), BackendError> {
+        let file = self.get_file(folder, name, ffi::CameraFileType::Normal)?;
+        fs::write(dir.join(file_name), file.data()?)?;
+
+        Ok(())
+    }
+
+    /// Saves a photo taken on the camera itself into the unsolicited photo directory, files
+    /// that aren't jpegs, like the raw half of a raw+jpeg shot, stay on the card
+    fn save_unsolicited(&mut self, folder: &str, name: &str) -> Option<CameraEvent> {
+        let path = join(folder, name);
+        // each photo is reported once at most
+        if let Some(index) = self
+            .recent_captures
+            .iter()
+            .position(|capture| *capture == path)
+        {
+            self.recent_captures.remove(index);
+            return None;
+        }
+
+        let lowercase_name = name.to_ascii_lowercase();
+        if !lowercase_name.ends_with(".jpg") && !lowercase_name.ends_with(".jpeg") {
+            println!("Leaving a file added on the camera on the card: {}", path);
+            return None;
+        }
+
+        let dir = UNSOLICITED_PHOTO_DIR
+            .get()
+            .expect("UNSOLICITED_PHOTO_DIR not initialized");
+        let file_name = photo_file_name(dir);
+        match self.save_file(folder, name, dir, &file_name) {
+            Ok(()) => {
+                println!("Unsolicited photo saved: {}", file_name);
+                Some(CameraEvent::UnsolicitedPhoto { file_name })
+            }
+            Err(err) => {
+                println!("Failed to save unsolicited photo {}: {}", path, err);
+                None
+            }
+        }
+    }
+}
+
+impl CameraBackend for Gphoto2Camera {
+    /// Releases the shutter, libgphoto2 returns once the camera took the photo. It's downloaded
+    /// by a later poll, like the EDSDK transfers photos through its events
+    fn take_picture(
+        &mut self,
+        id: CaptureId,
+        respond_to: Sender<CaptureUpdate>,
+    ) -> Result<(), CaptureError> {
+        let path = self.release_shutter()?;
+
+        let folder = unsafe { from_c_str(path.folder.as_ptr()) };
+        let name = unsafe { from_c_str(path.name.as_ptr()) };
+        if self.recent_captures.len() == RECENT_CAPTURES {
+            self.recent_captures.pop_front();
+        }
+        self.recent_captures.push_back(join(&folder, &name));
+
+        self.pending.push_back(PendingCapture {
+            id,
+            folder,
+            name,
+            respond_to,
+        });
+        Ok(())
+    }
+
+    /// The photos stay on the card
+    fn abandon_capture(&mut self, id: CaptureId) {
+        self.pending.retain(|capture| capture.id != id);
+    }
+
+    fn take_completed_captures(&mut self) -> Vec<CaptureId> {
+        std::mem::take(&mut self.completed)
+    }
+
+    fn is_capturing(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Drains the camera's event queue, photos taken on the camera are saved as unsolicited ones.
+    /// Then the oldest photo taken through `take_picture` is downloaded, the camera thread gets to
+    /// keep the camera awake in between photos
+    fn poll_events(&mut self) -> Result<Vec<CameraEvent>, BackendError> {
+        let mut events = Vec::new();
+
+        for _ in 0..MAX_EVENTS_PER_POLL {
+            let mut event_type = ffi::GP_EVENT_TIMEOUT;
+            let mut event_data = ptr::null_mut();
+            check(unsafe {
+                ffi::gp_camera_wait_for_event(
+                    self.camera,
+                    0,
+                    &mut event_type,
+                    &mut event_data,
+                    self.context,
+                )
+            })?;
+
+            if event_type == ffi::GP_EVENT_FILE_ADDED && !event_data.is_null() {
+                let path = unsafe { &*(event_data as *const ffi::CameraFilePath) };
+                let folder = unsafe { from_c_str(path.folder.as_ptr()) };
+                let name = unsafe { from_c_str(path.name.as_ptr()) };
+                events.extend(self.save_unsolicited(&folder, &name));
+            }
+
+            // the event data is ours to free, whatever it is
+            unsafe { ffi::free(event_data) };
+
+            if event_type == ffi::GP_EVENT_TIMEOUT {
+                break;
+            }
+        }
+
+        if let Some(capture) = self.pending.pop_front() {
+            self.download(capture);
+        }
+
+        Ok(events)
+    }
+
+    fn live_view_frame(&mut self) -> Result<LiveViewFrame, BackendError> {
+        let file = File::new()?;
+        check(unsafe { ffi::gp_camera_capture_preview(self.camera, file.0, self.context) })?;
+
+        Ok(LiveViewFrame {
+            jpeg: file.data()?.to_vec(),
+            histogram: None,
+            zoom: None,
+            zoom_rect: None,
+            image_position: None,
+            coordinate_system: None,
+            af_frames: Vec::new(),
+        })
+    }
+
+    fn status(&self) -> CameraStatus {
+        // reported as text like "100%"
+        let battery_level = self
+            .config("batterylevel")
+            .ok()
+            .and_then(|widget| widget.value().ok().flatten())
+            .and_then(|level| level.trim().trim_end_matches('%').parse::<u32>().ok());
+
+        let mode = self
+            .find_config(MODE_CONFIG_NAMES)
+            .ok()
+            .and_then(|(_, widget)| widget.value().ok().flatten());
+
+        let mut free_space = None;
+        let mut available_shots = None;
+        let mut storage_info = ptr::null_mut();
+        let mut count = 0;
+        if unsafe {
+            ffi::gp_camera_get_storageinfo(self.camera, &mut storage_info, &mut count, self.context)
+        } == ffi::GP_OK
+        {
+            let storages = unsafe { std::slice::from_raw_parts(storage_info, count as usize) };
+            for storage in storages {
+                if storage.fields & ffi::GP_STORAGEINFO_FREESPACEKBYTES != 0 {
+                    *free_space.get_or_insert(0) += storage.free_kbytes * 1024;
+                }
+                if storage.fields & ffi::GP_STORAGEINFO_FREESPACEIMAGES != 0 {
+                    *available_shots.get_or_insert(0) += storage.free_images as u32;
+                }
+            }
+            unsafe { ffi::free(storage_info as *mut c_void) };
+        }
+
+        CameraStatus {
+            battery_level,
+            ac_power: false,
+            low_battery: battery_level.is_some_and(|level| level <= LOW_BATTERY_LEVEL),
+            battery_quality: None,
+            free_space,
+            available_shots,
+            lens_attached: None,
+            mode,
+        }
+    }
+
+    /// The codes are the indices of the choices libgphoto2 reports
+    fn available_values(&self, setting: CameraSetting) -> Result<Vec<SettingValue>, BackendError> {
+        let (_, widget) = self.find_config(config_names(setting))?;
+
+        Ok(widget
+            .choices()?
+            .into_iter()
+            .enumerate()
+            .map(|(code, label)| SettingValue {
+                code: code as u32,
+                label,
+            })
+            .collect())
+    }
+
+    fn set_setting(&mut self, setting: CameraSetting, code: u32) -> Result<(), BackendError> {
+        let (name, mut widget) = self.find_config(config_names(setting))?;
+
+        let choice = widget
+            .choices()?
+            .into_iter()
+            .nth(code as usize)
+            .ok_or_else(|| format!("{} is no valid value for {}", code, name))?;
+        widget.set_value(&choice)?;
+
+        check(unsafe {
+            ffi::gp_camera_set_single_config(
+                self.camera,
+                c_string(name)?.as_ptr(),
+                widget.0,
+                self.context,
+            )
+        })?;
+        Ok(())
+    }
+
+    /// The first folder of a path is the volume, e.g. `store_00010001`
+    fn list_card_files(&self) -> Result<Vec<CardFile>, BackendError> {
+        let mut paths = Vec::new();
+        self.collect_files("/", &mut paths)?;
+
+        paths
+            .into_iter()
+            .map(|path| {
+                let (folder, name) = split_path(&path);
+                let size = self.file_size(folder, name)?;
+
+                let (volume, path) = path[1..].split_once('/').unwrap_or(("", &path[1..]));
+                Ok(CardFile {
+                    volume: volume.to_string(),
+                    path: path.to_string(),
+                    size,
+                })
+            })
+            .collect()
+    }
+
+    fn download_card_file(&mut self, file: &CardFile) -> Result<String, BackendError> {
+        let path = card_file_path(file);
+        let (folder, name) = split_path(&path);
+
+        // card names repeat across bodies and once the counter is reset
+        let camera_dir = CAMERA_PHOTO_DIR
+            .get()
+            .expect("CAMERA_PHOTO_DIR not initialized");
+        let file_name = unique_file_name(camera_dir, name);
+        self.save_file(folder, name, camera_dir, &file_name)?;
+        Ok(file_name)
+    }
+
+    fn delete_card_file(&mut self, file: &CardFile) -> Result<(), BackendError> {
+        let path = card_file_path(file);
+        let (folder, name) = split_path(&path);
+
+        check(unsafe {
+            ffi::gp_camera_file_delete(
+                self.camera,
+                c_string(folder)?.as_ptr(),
+                c_string(name)?.as_ptr(),
+                self.context,
+            )
+        })?;
+        Ok(())
+    }
+}
+
+impl Drop for Gphoto2Camera {
+    fn drop(&mut self) {
+        unsafe {
+            if !self.camera.is_null() {
+                ffi::gp_camera_exit(self.camera, self.context);
+                ffi::gp_camera_unref(self.camera);
+            }
+            if !self.port_infos.is_null() {
+                ffi::gp_port_info_list_free(self.port_infos);
+            }
+            ffi::gp_context_unref(self.context);
+        }
+    }
+}
+
+fn join(folder: &str, name: &str) -> String {
+    format!("{}/{}", folder.trim_end_matches('/'), name)
+}
+
+/// Splits an absolute path into its folder and file name
+fn split_path(path: &str) -> (&str, &str) {
+    match path.rsplit_once('/') {
+        Some(("", name)) => ("/", name),
+        Some((folder, name)) => (folder, name),
+        None => ("/", path),
+    }
+}
+
+fn card_file_path(file: &CardFile) -> String {
+    if file.volume.is_empty() {
+        format!("/{}", file.path)
+    } else {
+        format!("/{}/{}", file.volume, file.path)
+    }
+}
@@ -0,0 +1,124 @@
+//! Runs the backend against a libgphoto2 camera without real hardware. Like the app, the camera
+//! is picked through `PHOTOBOOTH_GPHOTO2_MODEL` and `PHOTOBOOTH_GPHOTO2_PORT`, without them the
+//! first camera found is used, e.g. the virtual camera of a libgphoto2 built with `--enable-vusb`:
+//!
+//! ```sh
+//! PHOTOBOOTH_GPHOTO2_MODEL="Directory Browse" PHOTOBOOTH_GPHOTO2_PORT=disk:/path/to/photos \
+//!     cargo test --features simulated,gphoto2 gphoto2
+//! ```
+
+use std::{fs, time::Duration};
+
+use tauri::async_runtime::channel;
+
+use super::{config_names, Gphoto2Camera};
+use crate::{
+    backend::{CameraBackend, CaptureId},
+    camera::CameraSetting,
+    path::CAMERA_PHOTO_DIR,
+    test_support::{answer, poll_until, TestCamera},
+};
+
+/// Downloading a full size photo over USB takes a while
+const PHOTO_TIMEOUT: Duration = Duration::from_secs(30);
+
+fn open_camera() -> TestCamera<Gphoto2Camera> {
+    TestCamera::open(|| {
+        let var = |name| std::env::var(name).ok().filter(|value| !value.is_empty());
+        let model = var("PHOTOBOOTH_GPHOTO2_MODEL");
+        let port = var("PHOTOBOOTH_GPHOTO2_PORT");
+
+        Gphoto2Camera::open(model.as_deref(), port.as_deref())
+            .expect("Failed to open the libgphoto2 camera")
+    })
+}
+
+#[test]
+fn captures_and_downloads_photos() {
+    let mut camera = open_camera();
+
+    let (tx, mut rx) = channel(16);
+    camera
+        .take_picture(CaptureId(0), tx)
+        .expect("Failed to release the shutter");
+    assert!(camera.is_capturing());
+
+    let file_name = poll_until(&mut *camera, PHOTO_TIMEOUT, |_| answer(&mut rx))
+        .expect("Failed to take the photo");
+    assert!(!camera.is_capturing());
+
+    let camera_dir = CAMERA_PHOTO_DIR.get().unwrap();
+    let photo = fs::read(camera_dir.join(&file_name)).unwrap();
+    assert!(!photo.is_empty());
+}
+
+#[test]
+fn downloads_card_files_without_overwriting() {
+    let mut camera = open_camera();
+
+    let files = camera.list_card_files().expect("Failed to list the files");
+    let file = files.first().expect("The camera has no files");
+
+    let first = camera.download_card_file(file).unwrap();
+    let second = camera.download_card_file(file).unwrap();
+    assert_ne!(first, second);
+
+    let camera_dir = CAMERA_PHOTO_DIR.get().unwrap();
+    assert_eq!(
+        fs::read(camera_dir.join(&first)).unwrap(),
+        fs::read(camera_dir.join(&second)).unwrap()
+    );
+}
+
+#[test]
+fn shows_live_view() {
+    let mut camera = open_camera();
+
+    let frame = camera
+        .live_view_frame()
+        .expect("Failed to capture a preview");
+    assert!(!frame.jpeg.is_empty());
+}
+
+#[test]
+fn reads_and_sets_config() {
+    let mut camera = open_camera();
+
+    // drivers offer different settings, the first one with choices is tried, the directory browse
+    // driver has none
+    let Some((setting, values)) = [
+        CameraSetting::Iso,
+        CameraSetting::Av,
+        CameraSetting::Tv,
+        CameraSetting::WhiteBalance,
+        CameraSetting::PictureStyle,
+    ]
+    .into_iter()
+    .find_map(|setting| {
+        camera
+            .available_values(setting)
+            .ok()
+            .filter(|values| !values.is_empty())
+            .map(|values| (setting, values))
+    }) else {
+        eprintln!("The camera has no settings with choices, skipping");
+        return;
+    };
+
+    let current = |camera: &Gphoto2Camera| {
+        let (_, widget) = camera.find_config(config_names(setting)).unwrap();
+        widget.value().unwrap()
+    };
+    let original = current(&camera);
+
+    let value = values.last().unwrap();
+    camera.set_setting(setting, value.code).unwrap();
+    assert_eq!(current(&camera).as_ref(), Some(&value.label));
+
+    if let Some(original) = values
+        .iter()
+        .find(|value| Some(&value.label) == original.as_ref())
+    {
+        camera.set_setting(setting, original.code).unwrap();
+    }
+}
@@ -0,0 +1,331 @@
+use std::{
+    collections::VecDeque,
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use tauri::async_runtime::Sender;
+
+use crate::{
+    backend::{BackendError, CameraBackend, CaptureId},
+    camera::{
+        photo_file_name, CameraEvent, CameraSetting, CameraStatus, CaptureError, CaptureUpdate,
+        LiveViewFrame, SettingValue, CAPTURE_PREVIEW,
+    },
+    path::CAMERA_PHOTO_DIR,
+};
+
+/// How long a replayed photo takes from releasing the shutter until it's saved
+const CAPTURE_DELAY: Duration = Duration::from_millis(500);
+
+/// Where the replayed images come from
+enum Source {
+    /// One photo per file, sorted by name
+    Folder { files: Vec<PathBuf> },
+    /// The frames of an mjpeg file, each a complete jpeg
+    Mjpeg { frames: Vec<Vec<u8>> },
+}
+
+struct PendingCapture {
+    id: CaptureId,
+    jpeg: Vec<u8>,
+    due: Instant,
+    respond_to: Sender<CaptureUpdate>,
+}
+
+/// Replays recorded images as if they came from a camera. A folder is shown one photo at a time,
+/// the live view shows the photo the next capture takes. An mjpeg file is looped as the live
+/// view and a capture takes the current frame
+pub struct ReplayCamera {
+    source: Source,
+    /// The file or frame the live view currently shows
+    position: usize,
+    /// The jpeg at `position`, a folder's files are only read once they're shown
+    current: Vec<u8>,
+    pending: VecDeque<PendingCapture>,
+    completed: Vec<CaptureId>,
+}
+
+impl ReplayCamera {
+    pub fn open(path: &Path) -> Result<Self, BackendError> {
+        let source = if path.is_dir() {
+            let mut files = fs::read_dir(path)?
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|file| has_extension(file, &["jpg", "jpeg"]))
+                .collect::<Vec<_>>();
+            files.sort();
+            Source::Folder { files }
+        } else if has_extension(path, &["mjpeg", "mjpg"]) {
+            Source::Mjpeg {
+                frames: split_mjpeg(&fs::read(path)?),
+            }
+        } else {
+            return Err(format!("{} is neither a folder nor an mjpeg file", path.display()).into());
+        };
+
+        let mut camera = Self {
+            source,
+            position: 0,
+            current: Vec::new(),
+            pending: VecDeque::new(),
+            completed: Vec::new(),
+        };
+        if camera.len() == 0 {
+            return Err(format!("No images found in {}", path.display()).into());
+        }
+        camera.show(0)?;
+
+        println!("Replaying {} images from {}", camera.len(), path.display());
+        Ok(camera)
+    }
+
+    fn len(&self) -> usize {
+        match &self.source {
+            Source::Folder { files } => files.len(),
+            Source::Mjpeg { frames } => frames.len(),
+        }
+    }
+
+    /// Moves to the image at `position`, wrapping around at the end
+    fn show(&mut self, position: usize) -> Result<(), BackendError> {
+        self.position = position % self.len();
+        self.current = match &self.source {
+            Source::Folder { files } => fs::read(&files[self.position])?,
+            Source::Mjpeg { frames } => frames[self.position].clone(),
+        };
+        Ok(())
+    }
+}
+
+impl CameraBackend for ReplayCamera {
+    fn take_picture(
+        &mut self,
+        id: CaptureId,
+        respond_to: Sender<CaptureUpdate>,
+    ) -> Result<(), CaptureError> {
+        self.pending.push_back(PendingCapture {
+            id,
+            jpeg: self.current.clone(),
+            due: Instant::now() + CAPTURE_DELAY,
+            respond_to,
+        });
+
+        // the next photo of a folder is a different one
+        if let Source::Folder { .. } = self.source {
+            if let Err(err) = self.show(self.position + 1) {
+                println!("Failed to read the next image: {}", err);
+            }
+        }
+        Ok(())
+    }
+
+    fn abandon_capture(&mut self, id: CaptureId) {
+        self.pending.retain(|capture| capture.id != id);
+    }
+
+    fn take_completed_captures(&mut self) -> Vec<CaptureId> {
+        std::mem::take(&mut self.completed)
+    }
+
+    fn is_capturing(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Saves the photos that are due
+    fn poll_events(&mut self) -> Result<Vec<CameraEvent>, BackendError> {
+        while self
+            .pending
+            .front()
+            .is_some_and(|capture| capture.due <= Instant::now())
+        {
+            let capture = self.pending.pop_front().unwrap();
+
+            *CAPTURE_PREVIEW.lock().unwrap() = Some(capture.jpeg.clone());
+            let _ = capture.respond_to.blocking_send(CaptureUpdate::Preview);
+
+            let result = save_photo(&capture.jpeg);
+            let _ = capture
+                .respond_to
+                .blocking_send(CaptureUpdate::Progress(100));
+            let _ = capture
+                .respond_to
+                .blocking_send(CaptureUpdate::Done(result));
+            self.completed.push(capture.id);
+        }
+
+        Ok(Vec::new())
+    }
+
+    fn live_view_frame(&mut self) -> Result<LiveViewFrame, BackendError> {
+        let jpeg = self.current.clone();
+
+        // the recording keeps playing
+        if let Source::Mjpeg { .. } = self.source {
+            self.show(self.position + 1)?;
+        }
+
+        Ok(LiveViewFrame {
+            jpeg,
+            histogram: None,
+            zoom: None,
+            zoom_rect: None,
+            image_position: None,
+            coordinate_system: None,
+            af_frames: Vec::new(),
+        })
+    }
+
+    fn status(&self) -> CameraStatus {
+        CameraStatus {
+            battery_level: None,
+            ac_power: true,
+            low_battery: false,
+            battery_quality: None,
+            free_space: None,
+            available_shots: None,
+            lens_attached: None,
+            mode: None,
+        }
+    }
+
+    fn available_values(&self, _setting: CameraSetting) -> Result<Vec<SettingValue>, BackendError> {
+        Ok(Vec::new())
+    }
+
+    fn set_setting(&mut self, setting: CameraSetting, _code: u32) -> Result<(), BackendError> {
+        Err(format!("{:?} can't be set while replaying", setting).into())
+    }
+}
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            extensions
+                .iter()
+                .any(|candidate| extension.eq_ignore_ascii_case(candidate))
+        })
+}
+
+/// Splits an mjpeg stream into its jpegs, each runs from a start of image to its matching end of
+/// image marker. A thumbnail embedded in the EXIF data is a jpeg of its own, so its markers are
+/// nested in the frame's. Anything in between frames, like multipart headers, is skipped, and so is
+/// a frame the recording cut off
+fn split_mjpeg(data: &[u8]) -> Vec<Vec<u8>> {
+    const START_OF_IMAGE: [u8; 2] = [0xff, 0xd8];
+    const END_OF_IMAGE: [u8; 2] = [0xff, 0xd9];
+
+    let mut frames = Vec::new();
+    let mut rest = data;
+
+    while let Some(start) = rest.windows(2).position(|marker| marker == START_OF_IMAGE) {
+        rest = &rest[start..];
+
+        let mut depth = 0;
+        let mut end = None;
+        let mut index = 0;
+        while index + 1 < rest.len() {
+            let marker = &rest[index..index + 2];
+            if marker == START_OF_IMAGE {
+                depth += 1;
+            } else if marker == END_OF_IMAGE {
+                depth -= 1;
+                if depth == 0 {
+                    end = Some(index + 2);
+                    break;
+                }
+            } else {
+                index += 1;
+                continue;
+            }
+            index += 2;
+        }
+
+        let Some(end) = end else {
+            break;
+        };
+        frames.push(rest[..end].to_vec());
+        rest = &rest[end..];
+    }
+
+    frames
+}
+
+fn save_photo(jpeg: &[u8]) -> Result<String, CaptureError> {
+    let camera_dir = CAMERA_PHOTO_DIR
+        .get()
+        .expect("CAMERA_PHOTO_DIR not initialized");
+    let file_name = photo_file_name(camera_dir);
+
+    fs::write(camera_dir.join(&file_name), jpeg).map_err(|err| CaptureError::DownloadFailed {
+        message: err.to_string(),
+    })?;
+
+    Ok(file_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A jpeg as far as the markers go, `content` stands in for its segments
+    fn jpeg(content: &[u8]) -> Vec<u8> {
+        [&[0xff, 0xd8][..], content, &[0xff, 0xd9]].concat()
+    }
+
+    #[test]
+    fn splits_frames_between_multipart_headers() {
+        let first = jpeg(b"first");
+        let second = jpeg(b"second");
+        let header = b"--frame\r\nContent-Type: image/jpeg\r\n\r\n";
+        let data = [&header[..], &first, b"\r\n", header, &second, b"\r\n"].concat();
+
+        assert_eq!(split_mjpeg(&data), [first, second]);
+    }
+
+    #[test]
+    fn keeps_embedded_thumbnails_in_their_frame() {
+        let thumbnail = jpeg(b"thumbnail");
+        let frame = jpeg(&[&b"exif"[..], &thumbnail, b"image"].concat());
+        let next = jpeg(b"next");
+
+        assert_eq!(
+            split_mjpeg(&[frame.clone(), next.clone()].concat()),
+            [frame, next]
+        );
+    }
+
+    #[test]
+    fn drops_a_cut_off_frame() {
+        let frame = jpeg(b"frame");
+        let cut_off = &jpeg(b"cut off")[..6];
+
+        assert_eq!(split_mjpeg(&[&frame[..], cut_off].concat()), [frame]);
+    }
+
+    #[test]
+    fn wraps_around_at_the_end_of_a_folder() {
+        let dir = std::env::temp_dir().join(format!("photobooth-replay-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let images = [jpeg(b"a"), jpeg(b"b")];
+        for (index, image) in images.iter().enumerate() {
+            fs::write(dir.join(format!("{}.jpg", index)), image).unwrap();
+        }
+        // not an image, so it isn't replayed
+        fs::write(dir.join("notes.txt"), b"notes").unwrap();
+
+        let mut camera = ReplayCamera::open(&dir).unwrap();
+        assert_eq!(camera.len(), 2);
+        assert_eq!(camera.current, images[0]);
+
+        camera.show(camera.position + 1).unwrap();
+        assert_eq!(camera.current, images[1]);
+
+        camera.show(camera.position + 1).unwrap();
+        assert_eq!(camera.position, 0);
+        assert_eq!(camera.current, images[0]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
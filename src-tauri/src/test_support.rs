@@ -0,0 +1,83 @@
+//! Helpers for the tests that run a backend against a camera, simulated or not
+
+use std::{
+    ops::{Deref, DerefMut},
+    sync::{Mutex, MutexGuard},
+    thread,
+    time::{Duration, Instant},
+};
+
+use tauri::async_runtime::Receiver;
+
+use crate::{
+    backend::CameraBackend,
+    camera::{CameraEvent, CaptureError, CaptureUpdate},
+    path::init_test_dirs,
+};
+
+/// Tests run in parallel, but a backend opens the one camera there is
+static TURN: Mutex<()> = Mutex::new(());
+
+/// A camera that a single test has to itself, it's closed before the next test gets its turn
+pub struct TestCamera<T> {
+    camera: T,
+    _turn: MutexGuard<'static, ()>,
+}
+
+impl<T> TestCamera<T> {
+    /// Waits for the camera to be free and opens it with `open`, the photo directories point at
+    /// a folder in the temp directory
+    pub fn open(open: impl FnOnce() -> T) -> Self {
+        // a failed test doesn't fail the ones after it
+        let turn = TURN.lock().unwrap_or_else(|err| err.into_inner());
+        init_test_dirs();
+
+        Self {
+            camera: open(),
+            _turn: turn,
+        }
+    }
+}
+
+impl<T> Deref for TestCamera<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.camera
+    }
+}
+
+impl<T> DerefMut for TestCamera<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.camera
+    }
+}
+
+/// Polls `camera` until `until` returns something, it's passed the camera events of each poll.
+/// Fails the test once `timeout` passed
+pub fn poll_until<T>(
+    camera: &mut dyn CameraBackend,
+    timeout: Duration,
+    mut until: impl FnMut(Vec<CameraEvent>) -> Option<T>,
+) -> T {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let events = camera.poll_events().expect("Failed to poll events");
+        if let Some(value) = until(events) {
+            return value;
+        }
+
+        assert!(Instant::now() < deadline, "Nothing arrived in time");
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+/// How the photo turned out once it's answered, the preview and progress updates are skipped
+pub fn answer(rx: &mut Receiver<CaptureUpdate>) -> Option<Result<String, CaptureError>> {
+    while let Ok(update) = rx.try_recv() {
+        if let CaptureUpdate::Done(result) = update {
+            return Some(result);
+        }
+    }
+    None
+}
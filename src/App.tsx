@@ -1,4 +1,4 @@
-import { useCallback, useEffect, useState } from "react";
+import { useCallback, useState } from "react";
 import { PhotoboothState } from "./types/state";
 import { User } from "./types/user";
 import Welcome from "./pages/Welcome";
@@ -6,38 +6,42 @@ import Countdown from "./pages/Countdown";
 import Result from "./pages/Result";
 import { allPhotosTaken, resultFromState } from "./types/result";
 
-import { convertFileSrc, invoke } from "@tauri-apps/api/core";
+import { invoke } from "@tauri-apps/api/core";
 import Test from "./pages/Test";
-import { appDataDir, join } from "@tauri-apps/api/path";
-import { listen } from "@tauri-apps/api/event";
-
-const EVF_FILE = "evf.jpeg";
+import { useEvfImage } from "./utils/evf";
+import {
+  captureErrorMessage,
+  useCapturePreview,
+  useCaptureProgress,
+} from "./utils/capture";
+import { CaptureError } from "./types/capture";
+import {
+  statusWarnings,
+  useCameraConnected,
+  useCameraStatus,
+} from "./utils/status";
 
 const App = () => {
-  const [evfImage, setEvfImage] = useState<string | null>(null);
+  const evfImage = useEvfImage();
+  const cameraConnected = useCameraConnected();
+  const warnings = statusWarnings(cameraConnected, useCameraStatus());
   const [state, setState] = useState<PhotoboothState>({ state: "ready" });
+  // a failed photo restarts its countdown
+  const [captureAttempt, setCaptureAttempt] = useState(0);
+  const [captureError, setCaptureError] = useState<string | null>(null);
+  const captureKey =
+    state.state === "countdown"
+      ? `${state.photos.length}-${captureAttempt}`
+      : null;
+  const capturePreview = useCapturePreview(captureKey);
+  const captureProgress = useCaptureProgress(captureKey);
 
-  useEffect(() => {
-    const unlisten = (async () => {
-      const appData = await appDataDir();
-      const path = await join(appData, EVF_FILE);
-      const photoSrc = convertFileSrc(path);
-
-      return listen("evf-update", () => {
-        setEvfImage(`${photoSrc}?${Date.now()}`);
-      });
-    })();
-
-    return () => {
-      unlisten.then((f) => f());
-    };
-  }, []);
   const handleStart = useCallback(
     (user: User) => {
-      if (state.state !== "ready") return;
+      if (state.state !== "ready" || !cameraConnected) return;
       setState({ state: "countdown", user, photos: [] });
     },
-    [state],
+    [state, cameraConnected],
   );
 
   const handleCountdownFinish = useCallback(async () => {
@@ -45,10 +49,18 @@ const App = () => {
 
     const takePhoto = async () => {
       await new Promise((resolve) => setTimeout(resolve, 2000));
-      return invoke("take_photo").then((res) => res as string);
+      return invoke<string[]>("take_photo").then(([photo]) => photo);
     };
 
-    const photo = await takePhoto();
+    let photo: string;
+    try {
+      photo = await takePhoto();
+    } catch (error) {
+      setCaptureError(captureErrorMessage(error as CaptureError));
+      setCaptureAttempt((attempt) => attempt + 1);
+      return;
+    }
+    setCaptureError(null);
 
     const newPhotos = [...state.photos, photo];
 
@@ -65,7 +77,10 @@ const App = () => {
     }
   }, [state]);
 
-  const onReset = useCallback(() => setState({ state: "ready" }), []);
+  const onReset = useCallback(() => {
+    setCaptureError(null);
+    setState({ state: "ready" });
+  }, []);
 
   const renderPage = () => {
     switch (state.state) {
@@ -74,7 +89,10 @@ const App = () => {
       case "countdown":
         return (
           <Countdown
+            key={captureAttempt}
             nPhoto={[state.photos.length + 1, 4]}
+            progress={captureProgress}
+            error={captureError}
             onFinish={handleCountdownFinish}
           />
         );
@@ -88,16 +106,29 @@ const App = () => {
   return (
     <div className="bg-linear-to-br from-purple-200 via-pink-200 to-red-200 min-h-screen w-full flex items-center justify-center p-4">
       <div className="absolute top-0 left-0 size-full flex justify-center items-center">
-        {evfImage !== null && (
+        {capturePreview !== null ? (
           <img
-            src={evfImage}
-            alt="EVF Preview"
+            src={capturePreview}
+            alt="Capture Preview"
             className="w-full h-full object-cover"
           />
+        ) : (
+          evfImage !== null && (
+            <img
+              src={evfImage}
+              alt="EVF Preview"
+              className="w-full h-full object-cover"
+            />
+          )
         )}
       </div>
       <main className="w-full max-w-lg mx-auto">{renderPage()}</main>
       <div className="absolute bottom-0 w-full flex justify-end gap-2 p-2 text-gray-500 text-sm">
+        {warnings.length > 0 && (
+          <span className="mr-auto text-red-600 font-semibold">
+            {warnings.join(" · ")}
+          </span>
+        )}
         &copy; {new Date().getFullYear()} Matteo Lutz
       </div>
     </div>
@@ -4,9 +4,18 @@ const COUNTDOWN_DURATION = 3;
 
 type NthPhotoOf = [number, number];
 
-const Countdown: FC<{ onFinish: () => void; nPhoto: NthPhotoOf }> = ({
+const Countdown: FC<{
+  onFinish: () => void;
+  nPhoto: NthPhotoOf;
+  /** How many percent of the photo were downloaded, `null` until the download starts */
+  progress?: number | null;
+  /** Why the previous attempt at this photo failed */
+  error?: string | null;
+}> = ({
   onFinish,
   nPhoto: [currentPhoto, totalPhotos],
+  progress = null,
+  error = null,
 }) => {
   const [count, setCount] = useState(COUNTDOWN_DURATION);
   const prevPhoto = useRef(currentPhoto);
@@ -36,12 +45,25 @@ const Countdown: FC<{ onFinish: () => void; nPhoto: NthPhotoOf }> = ({
         {/*Macht Euch bereit!*/}
         Foto {currentPhoto} von {totalPhotos}
       </p>
+      {error !== null && (
+        <p className="text-2xl text-red-600 font-semibold mb-8">
+          {error} – noch ein Versuch!
+        </p>
+      )}
       <div
         key={count}
         className="text-9xl font-bold text-white bg-orange-500 rounded-full w-48 h-48 flex items-center justify-center shadow-2xl animate-ping-once"
       >
         {count > 0 ? count : "📸"}
       </div>
+      {count <= 0 && progress !== null && (
+        <div className="w-48 h-2 mt-8 bg-white/60 rounded-full overflow-hidden">
+          <div
+            className="h-full bg-orange-500 transition-all"
+            style={{ width: `${progress}%` }}
+          />
+        </div>
+      )}
       <style>{`
           @keyframes ping-once {
             0% { transform: scale(0.5); opacity: 0; }
@@ -0,0 +1,10 @@
+/**
+ * Why `take_photo` failed, tagged with its `kind`.
+ */
+export type CaptureError =
+  | { kind: "focusFailed" }
+  | { kind: "busy" }
+  | { kind: "timeout" }
+  | { kind: "disconnected" }
+  | { kind: "downloadFailed"; message: string }
+  | { kind: "failed"; message: string };
@@ -0,0 +1,38 @@
+export type Point = {
+  x: number;
+  y: number;
+};
+
+export type Size = {
+  width: number;
+  height: number;
+};
+
+export type Rect = Point & Size;
+
+export type AfFrame = {
+  rect: Rect;
+  selected: boolean;
+  inFocus: boolean;
+};
+
+export type LiveViewHistogram = {
+  y: number[];
+  r: number[];
+  g: number[];
+  b: number[];
+  meanLuminance: number | null;
+  clippedHighlights: number | null;
+};
+
+/**
+ * Metadata of a live view frame, rects and positions are relative to `coordinateSystem`.
+ */
+export type LiveViewFrame = {
+  histogram: LiveViewHistogram | null;
+  zoom: number | null;
+  zoomRect: Rect | null;
+  imagePosition: Point | null;
+  coordinateSystem: Size | null;
+  afFrames: AfFrame[];
+};
@@ -0,0 +1,16 @@
+/**
+ * Battery, storage and mode of the camera, values the camera doesn't report are `null`.
+ */
+export type CameraStatus = {
+  /** In percent, `null` on AC power */
+  batteryLevel: number | null;
+  acPower: boolean;
+  lowBattery: boolean;
+  batteryQuality: string | null;
+  /** Free bytes on all cards */
+  freeSpace: number | null;
+  availableShots: number | null;
+  lensAttached: boolean | null;
+  /** The mode dial position, e.g. "M" */
+  mode: string | null;
+};
@@ -0,0 +1,74 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { useEffect, useState } from "react";
+import { CaptureError } from "../types/capture";
+
+/**
+ * Tells the guests what went wrong when `take_photo` failed.
+ */
+export const captureErrorMessage = (error: CaptureError): string => {
+  switch (error.kind) {
+    case "focusFailed":
+      return "Die Kamera konnte nicht scharfstellen";
+    case "busy":
+      return "Die Kamera ist noch beschäftigt";
+    case "timeout":
+      return "Das Foto ist nicht angekommen";
+    case "disconnected":
+      return "Keine Kamera verbunden";
+    case "downloadFailed":
+    case "failed":
+      return "Das Foto hat nicht geklappt";
+  }
+};
+
+/**
+ * Returns an object url of the thumbnail of the photo being taken, or `null` if it isn't there yet.
+ * The thumbnail arrives seconds before `take_photo` returns, it's reset whenever `key` changes.
+ */
+export const useCapturePreview = (key: unknown): string | null => {
+  const [preview, setPreview] = useState<string | null>(null);
+
+  useEffect(() => {
+    let currentUrl: string | null = null;
+
+    const unlisten = listen("capture-preview", async () => {
+      const jpeg = await invoke<ArrayBuffer>("get_capture_preview");
+      const url = URL.createObjectURL(new Blob([jpeg], { type: "image/jpeg" }));
+
+      setPreview(url);
+
+      if (currentUrl !== null) URL.revokeObjectURL(currentUrl);
+      currentUrl = url;
+    });
+
+    return () => {
+      unlisten.then((f) => f());
+      if (currentUrl !== null) URL.revokeObjectURL(currentUrl);
+      setPreview(null);
+    };
+  }, [key]);
+
+  return preview;
+};
+
+/**
+ * Returns how many percent of the photo being taken were downloaded, or `null` if the download
+ * didn't start yet. It's reset whenever `key` changes.
+ */
+export const useCaptureProgress = (key: unknown): number | null => {
+  const [progress, setProgress] = useState<number | null>(null);
+
+  useEffect(() => {
+    const unlisten = listen<{ percent: number }>("capture-progress", (event) =>
+      setProgress(event.payload.percent),
+    );
+
+    return () => {
+      unlisten.then((f) => f());
+      setProgress(null);
+    };
+  }, [key]);
+
+  return progress;
+};
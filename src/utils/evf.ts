@@ -0,0 +1,53 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { useEffect, useState } from "react";
+import { LiveViewFrame } from "../types/evf";
+
+/**
+ * Returns an object url of the latest live view frame, or `null` if no frame was received yet.
+ */
+export const useEvfImage = (): string | null => {
+  const [evfImage, setEvfImage] = useState<string | null>(null);
+
+  useEffect(() => {
+    let currentUrl: string | null = null;
+
+    const unlisten = listen("evf-update", async () => {
+      const frame = await invoke<ArrayBuffer>("get_evf_frame");
+      const url = URL.createObjectURL(
+        new Blob([frame], { type: "image/jpeg" }),
+      );
+
+      setEvfImage(url);
+
+      if (currentUrl !== null) URL.revokeObjectURL(currentUrl);
+      currentUrl = url;
+    });
+
+    return () => {
+      unlisten.then((f) => f());
+      if (currentUrl !== null) URL.revokeObjectURL(currentUrl);
+    };
+  }, []);
+
+  return evfImage;
+};
+
+/**
+ * Returns the metadata of the latest live view frame, or `null` if no frame was received yet.
+ */
+export const useLiveViewFrame = (): LiveViewFrame | null => {
+  const [frame, setFrame] = useState<LiveViewFrame | null>(null);
+
+  useEffect(() => {
+    const unlisten = listen<LiveViewFrame>("evf-update", (event) =>
+      setFrame(event.payload),
+    );
+
+    return () => {
+      unlisten.then((f) => f());
+    };
+  }, []);
+
+  return frame;
+};
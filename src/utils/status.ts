@@ -0,0 +1,78 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { useEffect, useState } from "react";
+import { CameraStatus } from "../types/status";
+
+/**
+ * Below this many available shots the cards count as nearly full.
+ */
+const FEW_SHOTS = 20;
+
+/**
+ * Returns the latest camera status, or `null` if the camera didn't report one yet.
+ */
+export const useCameraStatus = (): CameraStatus | null => {
+  const [status, setStatus] = useState<CameraStatus | null>(null);
+
+  useEffect(() => {
+    invoke<CameraStatus>("camera_status")
+      .then(setStatus)
+      .catch(() => {});
+
+    const unlisten = listen<CameraStatus>("camera-status", (event) =>
+      setStatus(event.payload),
+    );
+
+    return () => {
+      unlisten.then((f) => f());
+    };
+  }, []);
+
+  return status;
+};
+
+/**
+ * Returns whether a camera is connected, the app keeps looking for one while it isn't.
+ */
+export const useCameraConnected = (): boolean => {
+  const [connected, setConnected] = useState(false);
+
+  useEffect(() => {
+    invoke<boolean>("camera_connected")
+      .then(setConnected)
+      .catch(() => {});
+
+    const unlistenConnected = listen("camera-connected", () =>
+      setConnected(true),
+    );
+    const unlistenDisconnected = listen("camera-disconnected", () =>
+      setConnected(false),
+    );
+
+    return () => {
+      unlistenConnected.then((f) => f());
+      unlistenDisconnected.then((f) => f());
+    };
+  }, []);
+
+  return connected;
+};
+
+/**
+ * Returns what the operator should take care of, empty if everything is fine.
+ */
+export const statusWarnings = (
+  connected: boolean,
+  status: CameraStatus | null,
+): string[] => {
+  if (!connected) return ["Keine Kamera verbunden"];
+  if (status === null) return [];
+
+  const warnings = [];
+  if (status.lowBattery) warnings.push(`Akku schwach (${status.batteryLevel}%)`);
+  if (status.availableShots !== null && status.availableShots < FEW_SHOTS)
+    warnings.push(`Speicherkarte fast voll (${status.availableShots} Fotos)`);
+  if (status.lensAttached === false) warnings.push("Kein Objektiv");
+
+  return warnings;
+};